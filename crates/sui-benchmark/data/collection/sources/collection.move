@@ -0,0 +1,85 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Collections churned by the collection benchmark workload to measure
+/// the computation cost of vector and table operations in the VM.
+module collection::collection {
+    use std::vector;
+    use sui::object::{Self, UID};
+    use sui::table::{Self, Table};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    /// Number of entries kept in each collection, the oldest entries
+    /// are removed once it is exceeded
+    const CAPACITY: u64 = 1000;
+
+    /// An owned object holding the same entries in a vector and a table.
+    struct Collection has key {
+        id: UID,
+        items: vector<u64>,
+        entries: Table<u64, u64>,
+        // Key of the next entry to insert
+        next: u64,
+    }
+
+    public fun length(c: &Collection): u64 {
+        vector::length(&c.items)
+    }
+
+    /// Create an empty Collection owned by the sender.
+    public entry fun create(ctx: &mut TxContext) {
+        transfer::transfer(
+            Collection {
+                id: object::new(ctx),
+                items: vector::empty(),
+                entries: table::new(ctx),
+                next: 0,
+            },
+            tx_context::sender(ctx)
+        )
+    }
+
+    /// Insert `num_ops` entries into both collections, then remove the
+    /// oldest entries until they are back within CAPACITY.
+    public entry fun churn(c: &mut Collection, num_ops: u64) {
+        let i = 0;
+        while (i < num_ops) {
+            vector::push_back(&mut c.items, c.next);
+            table::add(&mut c.entries, c.next, c.next);
+            c.next = c.next + 1;
+            i = i + 1;
+        };
+        while (table::length(&c.entries) > CAPACITY) {
+            let oldest = c.next - table::length(&c.entries);
+            table::remove(&mut c.entries, oldest);
+            // Removing the front shifts the whole vector
+            vector::remove(&mut c.items, 0);
+        };
+    }
+}
+
+#[test_only]
+module collection::collection_test {
+    use sui::test_scenario;
+    use collection::collection::{Self, Collection};
+
+    #[test]
+    fun test_churn() {
+        let owner = @0xC0FFEE;
+        let scenario_val = test_scenario::begin(owner);
+        let scenario = &mut scenario_val;
+        collection::create(test_scenario::ctx(scenario));
+
+        test_scenario::next_tx(scenario, owner);
+        {
+            let c = test_scenario::take_from_sender<Collection>(scenario);
+            collection::churn(&mut c, 600);
+            assert!(collection::length(&c) == 600, 0);
+            collection::churn(&mut c, 600);
+            assert!(collection::length(&c) == 1000, 1);
+            test_scenario::return_to_sender(scenario, c);
+        };
+        test_scenario::end(scenario_val);
+    }
+}
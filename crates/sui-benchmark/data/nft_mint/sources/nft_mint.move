@@ -0,0 +1,57 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Entry function of the NFT mint benchmark workload, each call mints one
+/// NFT owned by the sender, as a marketplace would.
+module nft_mint::nft_mint {
+    use std::string::{Self, String};
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+    use sui::url::{Self, Url};
+
+    /// An NFT whose size is set by the length of its metadata.
+    struct Nft has key, store {
+        id: UID,
+        name: String,
+        url: Url,
+        description: String,
+    }
+
+    /// Mint an Nft owned by the sender.
+    public entry fun mint(
+        name: vector<u8>,
+        url: vector<u8>,
+        description: vector<u8>,
+        ctx: &mut TxContext
+    ) {
+        let nft = Nft {
+            id: object::new(ctx),
+            name: string::utf8(name),
+            url: url::new_unsafe_from_bytes(url),
+            description: string::utf8(description),
+        };
+        transfer::transfer(nft, tx_context::sender(ctx));
+    }
+}
+
+#[test_only]
+module nft_mint::nft_mint_test {
+    use sui::test_scenario;
+    use nft_mint::nft_mint::{Self, Nft};
+
+    #[test]
+    fun test_mint() {
+        let owner = @0xC0FFEE;
+        let scenario_val = test_scenario::begin(owner);
+        let scenario = &mut scenario_val;
+        nft_mint::mint(b"name", b"https://sui.io", b"description", test_scenario::ctx(scenario));
+
+        test_scenario::next_tx(scenario, owner);
+        {
+            let ids = test_scenario::ids_for_sender<Nft>(scenario);
+            assert!(std::vector::length(&ids) == 1, 0);
+        };
+        test_scenario::end(scenario_val);
+    }
+}
@@ -0,0 +1,9 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Entry function of the noop benchmark workload, transactions calling it
+/// only touch their gas coin so their cost is mostly verification.
+module noop::noop {
+    /// Does nothing.
+    public entry fun noop() {}
+}
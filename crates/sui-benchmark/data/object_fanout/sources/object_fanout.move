@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Entry function of the object fanout benchmark workload, each call only
+/// creates new owned objects to stress object creation and storage.
+module object_fanout::object_fanout {
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    /// An empty object owned by the sender.
+    struct Leaf has key {
+        id: UID,
+    }
+
+    /// Create `count` Leaf objects owned by the sender.
+    public entry fun create(count: u64, ctx: &mut TxContext) {
+        let i = 0;
+        while (i < count) {
+            transfer::transfer(Leaf { id: object::new(ctx) }, tx_context::sender(ctx));
+            i = i + 1;
+        };
+    }
+}
+
+#[test_only]
+module object_fanout::object_fanout_test {
+    use sui::test_scenario;
+    use object_fanout::object_fanout::{Self, Leaf};
+
+    #[test]
+    fun test_create() {
+        let owner = @0xC0FFEE;
+        let scenario_val = test_scenario::begin(owner);
+        let scenario = &mut scenario_val;
+        object_fanout::create(3, test_scenario::ctx(scenario));
+
+        test_scenario::next_tx(scenario, owner);
+        {
+            let ids = test_scenario::ids_for_sender<Leaf>(scenario);
+            assert!(std::vector::length(&ids) == 3, 0);
+        };
+        test_scenario::end(scenario_val);
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Objects moved in and out of a container by the wrap_unwrap benchmark
+/// workload, every transaction either wraps or unwraps an object.
+module wrap_unwrap::wrap_unwrap {
+    use std::option::{Self, Option};
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    /// An object that is alternately wrapped and owned by the sender.
+    struct Inner has key, store {
+        id: UID,
+    }
+
+    /// An owned object holding at most one wrapped Inner.
+    struct Container has key {
+        id: UID,
+        inner: Option<Inner>,
+    }
+
+    public fun is_wrapped(c: &Container): bool {
+        option::is_some(&c.inner)
+    }
+
+    /// Create a Container owned by the sender with an Inner wrapped in it.
+    public entry fun create(ctx: &mut TxContext) {
+        let inner = Inner { id: object::new(ctx) };
+        transfer::transfer(
+            Container {
+                id: object::new(ctx),
+                inner: option::some(inner),
+            },
+            tx_context::sender(ctx)
+        )
+    }
+
+    /// Wrap `inner` into the empty container `c`.
+    public entry fun wrap(c: &mut Container, inner: Inner) {
+        option::fill(&mut c.inner, inner);
+    }
+
+    /// Take the wrapped Inner out of `c` and give it to the sender.
+    public entry fun unwrap(c: &mut Container, ctx: &mut TxContext) {
+        let inner = option::extract(&mut c.inner);
+        transfer::transfer(inner, tx_context::sender(ctx));
+    }
+}
+
+#[test_only]
+module wrap_unwrap::wrap_unwrap_test {
+    use sui::test_scenario;
+    use wrap_unwrap::wrap_unwrap::{Self, Container, Inner};
+
+    #[test]
+    fun test_wrap_unwrap() {
+        let owner = @0xC0FFEE;
+        let scenario_val = test_scenario::begin(owner);
+        let scenario = &mut scenario_val;
+        wrap_unwrap::create(test_scenario::ctx(scenario));
+
+        test_scenario::next_tx(scenario, owner);
+        {
+            let c = test_scenario::take_from_sender<Container>(scenario);
+            assert!(wrap_unwrap::is_wrapped(&c), 0);
+            wrap_unwrap::unwrap(&mut c, test_scenario::ctx(scenario));
+            assert!(!wrap_unwrap::is_wrapped(&c), 1);
+            test_scenario::return_to_sender(scenario, c);
+        };
+
+        test_scenario::next_tx(scenario, owner);
+        {
+            let c = test_scenario::take_from_sender<Container>(scenario);
+            let inner = test_scenario::take_from_sender<Inner>(scenario);
+            wrap_unwrap::wrap(&mut c, inner);
+            assert!(wrap_unwrap::is_wrapped(&c), 2);
+            test_scenario::return_to_sender(scenario, c);
+        };
+        test_scenario::end(scenario_val);
+    }
+}
@@ -15,6 +15,8 @@ use sui_types::crypto::{deterministic_random_account_key, AccountKeyPair};
 use tokio::time::sleep;
 
 use crate::options::Opts;
+use crate::read_routing_proxy::ReadRoutingProxy;
+use crate::rng::bench_rng;
 use crate::util::get_ed25519_keypair_from_keystore;
 use crate::workloads::Gas;
 use crate::{FullNodeProxy, LocalValidatorAggregatorProxy, ValidatorProxy};
@@ -57,6 +59,7 @@ impl Env {
                     opts.committee_size as usize,
                     opts.server_metric_port,
                     opts.num_server_threads,
+                    opts.connections_per_proxy,
                 )
                 .await
             }
@@ -70,7 +73,9 @@ impl Env {
                     opts.genesis_blob_path.as_str(),
                     opts.use_fullnode_for_reconfig,
                     opts.use_fullnode_for_execution,
+                    opts.use_fullnode_for_reads,
                     opts.fullnode_rpc_address.clone(),
+                    opts.connections_per_proxy,
                 )
                 .await
             }
@@ -84,6 +89,7 @@ impl Env {
         committee_size: usize,
         server_metric_port: u16,
         num_server_threads: u64,
+        connections_per_proxy: usize,
     ) -> Result<BenchmarkSetup> {
         info!("Running benchmark setup in local mode..");
         let mut network_config = test_and_configure_authority_configs(committee_size);
@@ -151,6 +157,7 @@ impl Env {
                 &config,
                 registry,
                 Some(&fullnode_rpc_url),
+                connections_per_proxy,
             )
             .await,
         );
@@ -182,7 +189,9 @@ impl Env {
         genesis_blob_path: &str,
         use_fullnode_for_reconfig: bool,
         use_fullnode_for_execution: bool,
+        use_fullnode_for_reads: bool,
         fullnode_rpc_address: Option<String>,
+        connections_per_proxy: usize,
     ) -> Result<BenchmarkSetup> {
         info!("Running benchmark setup in remote mode ..");
         std::thread::spawn(move || {
@@ -199,16 +208,19 @@ impl Env {
         let proxy: Arc<dyn ValidatorProxy + Send + Sync> = if use_fullnode_for_execution {
             info!("Using FullNodeProxy: {:?}", fullnode_rpc_url);
             Arc::new(
-                FullNodeProxy::from_url(&fullnode_rpc_url.expect(
-                    "fullnode-rpc-url is required when use-fullnode-for-execution is true",
-                ))
+                FullNodeProxy::from_url(
+                    &fullnode_rpc_url.expect(
+                        "fullnode-rpc-url is required when use-fullnode-for-execution is true",
+                    ),
+                    connections_per_proxy,
+                )
                 .await?,
             )
         } else {
             info!("Using LocalValidatorAggregatorProxy");
             let reconfig_fullnode_rpc_url =
                 if use_fullnode_for_reconfig {
-                    Some(fullnode_rpc_url.expect(
+                    Some(fullnode_rpc_url.clone().expect(
                         "fullnode-rpc-url is required when use-fullnode-for-reconfig is true",
                     ))
                 } else {
@@ -216,14 +228,27 @@ impl Env {
                 };
             let genesis = sui_config::node::Genesis::new_from_file(genesis_blob_path);
             let genesis = genesis.genesis()?;
-            Arc::new(
+            let validator_proxy: Arc<dyn ValidatorProxy + Send + Sync> = Arc::new(
                 LocalValidatorAggregatorProxy::from_genesis(
                     genesis,
                     registry,
                     reconfig_fullnode_rpc_url.as_deref(),
+                    connections_per_proxy,
                 )
                 .await,
-            )
+            );
+            if use_fullnode_for_reads {
+                info!("Serving reads from FullNodeProxy: {:?}", fullnode_rpc_url);
+                let read_proxy = FullNodeProxy::from_url(
+                    &fullnode_rpc_url
+                        .expect("fullnode-rpc-url is required when use-fullnode-for-reads is true"),
+                    connections_per_proxy,
+                )
+                .await?;
+                Arc::new(ReadRoutingProxy::new(validator_proxy, Arc::new(read_proxy)))
+            } else {
+                validator_proxy
+            }
         };
         info!(
             "Reconfiguration - Reconfiguration to epoch {} is done",
@@ -233,11 +258,11 @@ impl Env {
         let offset = ObjectID::from_hex_literal(primary_gas_id)?;
         let ids = ObjectID::in_range(offset, primary_gas_objects)?;
         let primary_gas_id = ids
-            .choose(&mut rand::thread_rng())
+            .choose(&mut bench_rng())
             .context("Failed to choose a random primary gas id")?;
         let primary_gas = proxy.get_object(*primary_gas_id).await?;
         let pay_coin_id = ids
-            .choose(&mut rand::thread_rng())
+            .choose(&mut bench_rng())
             .context("Failed to choose a random pay coin")?;
         let pay_coin = proxy.get_object(*pay_coin_id).await?;
         let primary_gas_account = primary_gas.owner.get_owner_address()?;
@@ -5,24 +5,59 @@ use clap::*;
 
 use prometheus::Registry;
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use sui_benchmark::drivers::bench_driver::BenchDriver;
+use sui_benchmark::delaying_proxy::DelayingProxy;
+use sui_benchmark::drivers::bench_driver::{BenchDriver, BenchDriverConfig};
 use sui_benchmark::drivers::driver::Driver;
+use sui_benchmark::drivers::progress::{is_complete, BenchProgress, ProgressFile};
+use sui_benchmark::drivers::validator_metrics::{metrics_deltas, scrape_validator_metrics};
+use sui_benchmark::drivers::warmup::STABLE_RUN;
 use sui_benchmark::drivers::BenchmarkCmp;
 use sui_benchmark::drivers::BenchmarkStats;
+use sui_benchmark::drivers::HistogramWrapper;
+use sui_benchmark::drivers::RunMetadata;
+use sui_benchmark::effects_digest_log::EffectsDigestLogProxy;
+use sui_benchmark::gas_manifest::GasManifest;
+use sui_benchmark::recording_proxy::RecordingProxy;
+use sui_benchmark::retrying_proxy::RetryingProxy;
+use sui_benchmark::rng::set_global_seed;
+use sui_benchmark::teardown::return_gas;
 
 use sui_node::metrics;
 
 use sui_benchmark::benchmark_setup::Env;
-use sui_benchmark::options::Opts;
+use sui_benchmark::options::{describe_flags, Opts, RunSpec, SMOKE_DURATION};
 
+use sui_benchmark::workloads::custom_call::CustomCallFactory;
+use sui_benchmark::workloads::package::set_package_bytecode_dir;
+use sui_benchmark::workloads::registry::WorkloadRegistry;
+use sui_benchmark::workloads::workload::WorkloadType;
 use sui_benchmark::workloads::workload_configuration::WorkloadConfiguration;
+use sui_benchmark::workloads::{set_transaction_tag, TAG_MODULUS};
 
 use sui_benchmark::system_state_observer::SystemStateObserver;
 use tokio::runtime::Builder;
 use tokio::sync::Barrier;
 
+const GIT_REVISION: &str = {
+    if let Some(revision) = option_env!("GIT_REVISION") {
+        revision
+    } else {
+        let version = git_version::git_version!(
+            args = ["--always", "--dirty", "--exclude", "*"],
+            fallback = ""
+        );
+
+        if version.is_empty() {
+            panic!("unable to query git revision");
+        }
+        version
+    }
+};
+
 /// To spin up a local cluster and direct some load
 /// at it with 50/50 shared and owned traffic, use
 /// it something like:
@@ -49,7 +84,81 @@ use tokio::sync::Barrier;
 /// --transfer-object 50```
 #[tokio::main]
 async fn main() -> Result<()> {
-    let opts: Opts = Opts::parse();
+    let matches = Opts::command().get_matches();
+    let mut opts = Opts::from_arg_matches(&matches)?;
+    opts.apply_config_file(&matches)?;
+    opts.apply_preset(&matches);
+    opts.apply_closed_loop();
+    let smoke_workloads = if opts.smoke {
+        let workloads = opts.apply_smoke();
+        if workloads.is_empty() {
+            return Err(anyhow!("--smoke needs a bench run with enabled workloads"));
+        }
+        eprintln!(
+            "Smoke test of {} workloads for {:?}",
+            workloads.len(),
+            SMOKE_DURATION
+        );
+        workloads
+    } else {
+        vec![]
+    };
+    let drain_in_flight = opts.apply_duration().map_err(|e| anyhow!(e))?;
+    if let Some(preset) = opts.preset {
+        eprintln!("Applied preset {}", preset);
+    }
+    if opts.normalize_weights {
+        let weights = opts.normalize_weights();
+        let mix: Vec<String> = weights
+            .iter()
+            .map(|(workload, percent)| format!("{} {}%", workload, percent))
+            .collect();
+        eprintln!("Normalized workload weights: {}", mix.join(", "));
+    }
+    let mut progress = opts
+        .resume
+        .as_deref()
+        .map(BenchProgress::load_or_default)
+        .transpose()?;
+    let seed = match (&opts.resume, progress.as_mut()) {
+        (Some(path), Some(progress)) => {
+            let run_duration = progress.remaining(opts.run_duration);
+            if is_complete(run_duration) {
+                eprintln!("Run saved in {} is already complete", path);
+                return Ok(());
+            }
+            let seed = progress.start_session(opts.global_seed);
+            progress.save(path)?;
+            if progress.sessions > 1 {
+                eprintln!(
+                    "Resuming run from {}: session {}, {} transactions succeeded over {:?}",
+                    path, progress.sessions, progress.num_success, progress.elapsed
+                );
+            }
+            seed
+        }
+        _ => opts.global_seed,
+    };
+    if let Some(seed) = seed {
+        set_global_seed(seed);
+    }
+    if let Some(package_bytecode) = &opts.package_bytecode {
+        set_package_bytecode_dir(PathBuf::from(package_bytecode))?;
+    }
+    if let Some(tag) = opts.tx_tag {
+        if tag >= TAG_MODULUS {
+            return Err(anyhow!(
+                "--tx-tag must be below {}, got {}",
+                TAG_MODULUS,
+                tag
+            ));
+        }
+        set_transaction_tag(tag);
+        eprintln!(
+            "Tagging transactions: gas budgets end in {:03} (budget % {} == {})",
+            tag, TAG_MODULUS, tag
+        );
+    }
     let mut config = telemetry_subscribers::TelemetryConfig::new();
     config.log_string = Some("warn".to_string());
     if !opts.log_path.is_empty() {
@@ -57,25 +166,113 @@ async fn main() -> Result<()> {
     }
     let _guard = config.with_env().init();
 
-    let registry_service = metrics::start_prometheus_server(
-        format!("{}:{}", opts.client_metric_host, opts.client_metric_port)
-            .parse()
-            .unwrap(),
-    );
-    let registry: Registry = registry_service.default_registry();
+    if let RunSpec::MergeHistograms { files } = &opts.run_spec {
+        let merged = HistogramWrapper::merge(files)?;
+        eprintln!("Merged Latency Report ({} files):", files.len());
+        eprintln!("{}", merged.to_table());
+        return Ok(());
+    }
+
+    // Workloads defined outside this crate are registered here
+    let mut workload_registry = WorkloadRegistry::default();
+    if let Some(calls) = &opts.custom_calls {
+        workload_registry.register(
+            Arc::new(CustomCallFactory::new(calls.clone())),
+            calls.total_weight(),
+        )?;
+    }
+    if let RunSpec::ListWorkloads = &opts.run_spec {
+        for (workload, flags) in workload_registry.workload_flags() {
+            println!("{}: {}", workload, workload.description());
+            for flag in describe_flags(flags) {
+                println!("    {}", flag);
+            }
+        }
+        return Ok(());
+    }
+
+    if opts.verify_effects_signatures && !opts.local && opts.use_fullnode_for_execution {
+        return Err(anyhow!(
+            "--verify-effects-signatures requires executing through the validators, \
+            fullnode responses are not signed"
+        ));
+    }
+    if opts.effects_digest_log.is_some() && !opts.local && opts.use_fullnode_for_execution {
+        return Err(anyhow!(
+            "--effects-digest-log requires executing through the validators, \
+            fullnode responses carry no effects digest"
+        ));
+    }
+
+    let registry = if opts.labels.is_empty() {
+        Registry::new()
+    } else {
+        // Client metrics carry the run labels
+        let labels = opts
+            .labels
+            .iter()
+            .map(|label| (label.key.clone(), label.value.clone()))
+            .collect();
+        Registry::new_custom(None, Some(labels))?
+    };
+    // The client metrics are only served with --client-metric-port
+    if let Some(port) = opts.client_metric_port {
+        let registry_service = metrics::start_prometheus_server(
+            format!("{}:{}", opts.client_metric_host, port)
+                .parse()
+                .unwrap(),
+        );
+        registry_service.add(registry.clone());
+    }
 
     let barrier = Arc::new(Barrier::new(2));
     let cloned_barrier = barrier.clone();
     let env = if opts.local { Env::Local } else { Env::Remote };
-    let benchmark_setup = env.setup(cloned_barrier, &registry, &opts).await?;
+    let mut benchmark_setup = env.setup(cloned_barrier, &registry, &opts).await?;
+    if let Some(wait) = opts.effects_wait {
+        benchmark_setup.validator_proxy =
+            Arc::from(benchmark_setup.validator_proxy.with_effects_wait(wait)?);
+    }
+    let effects_wait = benchmark_setup.validator_proxy.effects_wait();
+    if let Some(path) = &opts.record_session {
+        benchmark_setup.validator_proxy = Arc::new(RecordingProxy::new(
+            benchmark_setup.validator_proxy.clone(),
+            Path::new(path),
+        )?);
+    }
+    if let Some(path) = &opts.effects_digest_log {
+        benchmark_setup.validator_proxy = Arc::new(EffectsDigestLogProxy::new(
+            benchmark_setup.validator_proxy.clone(),
+            Path::new(path),
+        )?);
+    }
+    if let Some(delay) = opts.proxy_delay {
+        benchmark_setup.validator_proxy = Arc::new(DelayingProxy::new(
+            benchmark_setup.validator_proxy.clone(),
+            delay,
+        ));
+    }
+    // Outermost, so every attempt goes through the other proxies
+    if let Some(retry_config) = opts.retry_config() {
+        benchmark_setup.validator_proxy = Arc::new(RetryingProxy::new(
+            benchmark_setup.validator_proxy.clone(),
+            retry_config,
+        ));
+    }
     let system_state_observer = {
-        let mut system_state_observer =
-            SystemStateObserver::new(benchmark_setup.validator_proxy.clone());
+        let mut system_state_observer = SystemStateObserver::new(
+            benchmark_setup.validator_proxy.clone(),
+            Duration::from_secs(opts.system_state_poll_secs),
+        );
         system_state_observer.reference_gas_price.changed().await?;
         eprintln!(
             "Found reference gas price from system state object = {:?}",
             *system_state_observer.reference_gas_price.borrow()
         );
+        eprintln!(
+            "Committee at run start: {}",
+            *system_state_observer.committee.borrow()
+        );
         Arc::new(system_state_observer)
     };
     let stress_stat_collection = opts.stress_stat_collection;
@@ -89,14 +286,33 @@ async fn main() -> Result<()> {
         .unwrap();
     let prev_benchmark_stats_path = opts.compare_with.clone();
     let curr_benchmark_stats_path = opts.benchmark_stats_path.clone();
+    let histogram_export_path = opts.histogram_export.clone();
+    let hgrm_output_dir = opts.hgrm_output.clone();
+    let run_metadata = RunMetadata {
+        version: format!("{}-{}", env!("CARGO_PKG_VERSION"), GIT_REVISION),
+        config: serde_json::to_value(&opts)?,
+        labels: opts
+            .labels
+            .iter()
+            .map(|label| (label.key.clone(), label.value.clone()))
+            .collect(),
+        effects_wait: Some(effects_wait),
+    };
+    let closed_loop = opts.closed_loop;
+    let create_fanout = matches!(
+        &opts.run_spec,
+        RunSpec::Bench { weights, .. } if weights.get(WorkloadType::ObjectFanout) > 0
+    )
+    .then_some(opts.create_fanout);
     let registry_clone = registry.clone();
     let handle = std::thread::spawn(move || {
         client_runtime.block_on(async move {
-            let workload_configuration = if opts.disjoint_mode {
+            let workload_configuration = if opts.disjoint_mode || opts.sequential_workloads {
                 WorkloadConfiguration::Disjoint
             } else {
                 WorkloadConfiguration::Combined
             };
+            let primary_owner = benchmark_setup.primary_gas.1.get_owner_address()?;
             let workloads = workload_configuration
                 .configure(
                     benchmark_setup.primary_gas,
@@ -104,17 +320,43 @@ async fn main() -> Result<()> {
                     benchmark_setup.pay_coin_type_tag,
                     benchmark_setup.validator_proxy.clone(),
                     &opts,
+                    &workload_registry,
                     system_state_observer.clone(),
                 )
                 .await?;
-            let interval = opts.run_duration;
+            if opts.dry_run {
+                // The gas requirements were printed, nothing was submitted
+                return Ok(None);
+            }
+            let interval = progress.as_ref().map_or(opts.run_duration, |progress| {
+                progress.remaining(opts.run_duration)
+            });
             // We only show continuous progress in stderr
             // if benchmark is running in unbounded mode,
             // otherwise summarized benchmark results are
             // published in the end
             let show_progress = interval.is_unbounded();
-            let driver = BenchDriver::new(opts.stat_collection_interval, stress_stat_collection);
-            driver
+            let driver = BenchDriver::new(BenchDriverConfig::from_opts(
+                &opts,
+                opts.resume
+                    .clone()
+                    .zip(progress)
+                    .map(|(path, progress)| ProgressFile { path, progress }),
+                drain_in_flight,
+            ));
+            // Taken before the driver consumes the workloads
+            let teardown_gas: Vec<_> = if opts.teardown {
+                workloads
+                    .iter()
+                    .flat_map(|workload| workload.payload_config.all_gas())
+                    .collect()
+            } else {
+                vec![]
+            };
+            let gas_price = *system_state_observer.reference_gas_price.borrow();
+            let validator_metrics_start =
+                scrape_validator_metrics(&opts.validator_metrics_urls).await;
+            let (mut benchmark_stats, stress_stats) = driver
                 .run(
                     workloads,
                     benchmark_setup.validator_proxy.clone(),
@@ -123,22 +365,297 @@ async fn main() -> Result<()> {
                     show_progress,
                     interval,
                 )
-                .await
+                .await?;
+            let validator_metrics_end =
+                scrape_validator_metrics(&opts.validator_metrics_urls).await;
+            if let Some(path) = &opts.gas_manifest {
+                // The run moved the coins to new versions, the next run
+                // expects those
+                if let Some(mut manifest) = GasManifest::load(path)? {
+                    let left_stale = manifest
+                        .refresh(benchmark_setup.validator_proxy.as_ref())
+                        .await;
+                    manifest.save(path)?;
+                    if left_stale > 0 {
+                        eprintln!(
+                            "{} coins of gas manifest {} changed owner or were deleted during \
+                            the run, the next run needs new gas",
+                            left_stale, path
+                        );
+                    }
+                }
+            }
+            benchmark_stats.validator_metrics =
+                metrics_deltas(&validator_metrics_start, &validator_metrics_end);
+            if opts.teardown {
+                let report = return_gas(
+                    benchmark_setup.validator_proxy.clone(),
+                    teardown_gas,
+                    primary_owner,
+                    gas_price,
+                )
+                .await;
+                eprintln!(
+                    "Teardown: returned {} coins holding {} MIST from {} accounts to {}, {} left behind",
+                    report.returned_coins,
+                    report.returned_mist,
+                    report.accounts,
+                    primary_owner,
+                    report.left_behind
+                );
+            }
+            Ok(Some((benchmark_stats, stress_stats)))
         })
     });
     let joined = handle.join();
     if let Err(err) = joined {
         Err(anyhow!("Failed to join client runtime: {:?}", err))
     } else {
-        let (benchmark_stats, stress_stats) = joined.unwrap().unwrap();
+        let (mut benchmark_stats, stress_stats) = match joined.unwrap().unwrap() {
+            Some(stats) => stats,
+            None => return Ok(()),
+        };
+        eprintln!("Benchmark version {}", run_metadata.version);
+        eprintln!("Latencies wait for {} effects", effects_wait);
+        benchmark_stats.run_metadata = Some(run_metadata);
+        if closed_loop {
+            eprintln!("Closed-loop run, tps is the throughput sustained by the in-flight window");
+        }
+        if let Some(measured_from) = benchmark_stats.measured_from {
+            eprintln!(
+                "Warmup discarded, results are measured from {:?} into the run",
+                measured_from
+            );
+        }
         let benchmark_table = benchmark_stats.to_table();
         eprintln!("Benchmark Report:");
         eprintln!("{}", benchmark_table);
+        if let Some(create_fanout) = create_fanout {
+            match benchmark_stats
+                .outcome_latency_ms
+                .get(&WorkloadType::ObjectFanout.to_string())
+            {
+                Some(outcome) => {
+                    let num_created = (outcome.success_ms.histogram.len()
+                        + outcome.retried_ms.histogram.len())
+                        * create_fanout;
+                    eprintln!(
+                        "Objects created per second: {} ({} per object_fanout transaction)",
+                        num_created / benchmark_stats.duration.as_secs().max(1),
+                        create_fanout
+                    );
+                }
+                None => eprintln!("No object_fanout transaction completed"),
+            }
+        }
+        if !benchmark_stats.workload_windows.is_empty() {
+            eprintln!("Sequential Workloads Timeline:");
+            eprintln!("{}", benchmark_stats.timeline_table());
+        }
+        if !benchmark_stats.outcome_latency_ms.is_empty() {
+            eprintln!("Latency By Outcome:");
+            eprintln!("{}", benchmark_stats.outcome_table());
+            eprintln!("Retries:");
+            eprintln!("{}", benchmark_stats.retry_table());
+        }
+        if let Some(proxy_retries) = &benchmark_stats.proxy_retries {
+            eprintln!(
+                "Proxy Retries: {} transactions succeeded first try, {} after retrying, \
+                {} retries, {} still failing after --max-retries",
+                proxy_retries.succeeded_first_try,
+                proxy_retries.succeeded_after_retry,
+                proxy_retries.retries,
+                proxy_retries.exhausted
+            );
+        }
+        if !benchmark_stats.build_latency.is_empty() {
+            eprintln!("Build-Inclusive Latency:");
+            eprintln!("{}", benchmark_stats.build_latency_table());
+        }
+        if !benchmark_stats.effects_signers.is_empty() {
+            eprintln!("Effects Certificates Signed By Validator:");
+            eprintln!("{}", benchmark_stats.effects_signers_table());
+            if let Some(committee) = &benchmark_stats.committee {
+                let num_silent = committee
+                    .num_validators
+                    .saturating_sub(benchmark_stats.effects_signers.len());
+                if num_silent > 0 {
+                    eprintln!(
+                        "Warning: {} of {} validators signed no effects certificate",
+                        num_silent, committee.num_validators
+                    );
+                }
+            }
+        }
+        if !benchmark_stats.gas_usage.is_empty() {
+            eprintln!("Gas Budget vs Used (gas units):");
+            eprintln!("{}", benchmark_stats.gas_usage_table());
+            for (workload, gas_usage) in benchmark_stats.gas_usage.iter() {
+                if gas_usage.overbudgeted() {
+                    eprintln!(
+                        "Warning: {} transactions set a budget of {} but used at most {}, \
+                        gas cost changes may go unnoticed",
+                        workload, gas_usage.max_budget, gas_usage.max_used
+                    );
+                }
+            }
+        }
+        if !benchmark_stats.postcondition_failures.is_empty() {
+            eprintln!("Transactions executed but failing their postcondition:");
+            for (workload, num_failed) in &benchmark_stats.postcondition_failures {
+                eprintln!("  {}: {}", workload, num_failed);
+            }
+        }
+        for excluded in &benchmark_stats.excluded_connections {
+            eprintln!(
+                "Connection {} was excluded after {:?} for failing persistently, \
+                throughput after it ran on the remaining connections: {}",
+                excluded.connection, excluded.elapsed, excluded.error
+            );
+        }
+        for validator in &benchmark_stats.validator_metrics {
+            match &validator.error {
+                Some(err) => eprintln!(
+                    "Validator metrics of {} unavailable: {}",
+                    validator.url, err
+                ),
+                None => {
+                    eprintln!("Validator metrics of {} over the run:", validator.url);
+                    for (name, delta) in &validator.deltas {
+                        eprintln!(
+                            "  {}: {} ({:.2}/s)",
+                            name,
+                            delta,
+                            delta / benchmark_stats.duration.as_secs_f64().max(1.0)
+                        );
+                    }
+                }
+            }
+        }
+        if let Some(progress) = benchmark_stats.progress.as_ref().filter(|p| p.sessions > 1) {
+            eprintln!(
+                "Over all {} sessions: {} succeeded, {} errors, {} timeouts in {:?}",
+                progress.sessions,
+                progress.num_success,
+                progress.num_error,
+                progress.num_timeout,
+                progress.elapsed
+            );
+        }
+        if let Some(spend_budget) = &benchmark_stats.spend_budget {
+            eprintln!(
+                "Spent {} MIST on gas of the {} MIST budget{}",
+                spend_budget.spent,
+                spend_budget.cap,
+                if spend_budget.reached {
+                    ", the run was stopped at the budget"
+                } else {
+                    ""
+                }
+            );
+        }
+        if !benchmark_stats.duplicate_transactions.is_empty() {
+            eprintln!(
+                "{} transactions were built more than once, payloads share gas coins or objects:",
+                benchmark_stats.duplicate_transactions.len()
+            );
+            for duplicate in &benchmark_stats.duplicate_transactions {
+                eprintln!(
+                    "  {} built by {} after {}",
+                    duplicate.digest, duplicate.workload, duplicate.first_workload
+                );
+            }
+        }
+        if !benchmark_stats.ordering.is_empty() {
+            eprintln!("Shared Object Ordering:");
+            eprintln!("{}", benchmark_stats.ordering_table());
+        }
+        if let Some(clock_skew) = &benchmark_stats.clock_skew {
+            eprintln!("Latency To Checkpoint Timestamp:");
+            eprintln!("{}", clock_skew.checkpoint_latency_ms.to_table());
+            if clock_skew.affected() > 0 {
+                eprintln!(
+                    "Warning: {} of {} checkpoint latencies are implausible ({} negative, clamped \
+                    to zero, {} longer than measured locally), client and network clocks are \
+                    skewed by up to {}ms",
+                    clock_skew.affected(),
+                    clock_skew.measurements,
+                    clock_skew.negative,
+                    clock_skew.too_long,
+                    clock_skew.max_skew_ms
+                );
+            }
+        }
+        if !benchmark_stats.slowest.is_empty() {
+            eprintln!("Slowest Transactions:");
+            eprintln!("{}", benchmark_stats.slowest_table());
+        }
+        if let Some(warmup) = &benchmark_stats.warmup {
+            eprintln!(
+                "Warmup Latency (first {} transactions):",
+                warmup.samples.len()
+            );
+            eprintln!("{}", warmup.to_table());
+            match warmup.stabilized_after() {
+                Some(elapsed) => eprintln!(
+                    "Latency stabilized below {:?} after {:?}",
+                    warmup.threshold, elapsed
+                ),
+                None => eprintln!(
+                    "Latency did not stay below {:?} for {} consecutive transactions \
+                    within the first {}",
+                    warmup.threshold,
+                    STABLE_RUN,
+                    warmup.samples.len()
+                ),
+            }
+        }
+        if let Some(bursts) = &benchmark_stats.bursts {
+            eprintln!(
+                "Burst Latency (on {:?}, off {:?}):",
+                bursts.pattern.on, bursts.pattern.off
+            );
+            eprintln!("{}", bursts.to_table());
+        }
+        if !benchmark_stats.gas_balance.is_empty() {
+            eprintln!("Gas Coin Balance:");
+            eprintln!("{}", benchmark_stats.gas_balance_table());
+        }
+        if let Some(tuned_in_flight) = benchmark_stats.tuned_in_flight {
+            eprintln!(
+                "Measured with the in-flight window converged to {} transactions",
+                tuned_in_flight
+            );
+        }
+        if !benchmark_stats.inflight_full.is_zero() {
+            eprintln!(
+                "Workers spent {:?} in total with a full in-flight window",
+                benchmark_stats.inflight_full
+            );
+        }
+        for (elapsed, epoch) in benchmark_stats.epoch_changes.iter() {
+            eprintln!(
+                "Epoch changed to {} after {:?}, measurements around it span an epoch boundary",
+                epoch, elapsed
+            );
+        }
+        for window in benchmark_stats.epoch_spanning_windows.iter() {
+            eprintln!(
+                "Measurements from {:?} to {:?} span epochs {} to {}",
+                window.from, window.to, window.epochs.0, window.epochs.1
+            );
+        }
 
         if stress_stat_collection {
             eprintln!("Stress Performance Report:");
             let stress_stats_table = stress_stats.to_table();
             eprintln!("{}", stress_stats_table);
+            if stress_stats.client_cpu_saturated() {
+                eprintln!(
+                    "Warning: the benchmark client saturated its CPU, the measured \
+                    throughput may be limited by the client rather than the network"
+                );
+            }
         }
 
         if !prev_benchmark_stats_path.is_empty() {
@@ -148,6 +665,30 @@ async fn main() -> Result<()> {
                 new: &benchmark_stats,
                 old: &prev_stats,
             };
+            if let (Some(new), Some(old)) =
+                (&benchmark_stats.run_metadata, &prev_stats.run_metadata)
+            {
+                if new.version != old.version {
+                    eprintln!(
+                        "Benchmark versions differ, results may not be comparable: {} vs {}",
+                        new.version, old.version
+                    );
+                }
+                if new.effects_wait != old.effects_wait {
+                    eprintln!(
+                        "Effects waits differ, latencies are not comparable: {:?} vs {:?}",
+                        new.effects_wait, old.effects_wait
+                    );
+                }
+            }
+            if let (Some(new), Some(old)) = (&benchmark_stats.committee, &prev_stats.committee) {
+                if !new.same_topology(old) {
+                    eprintln!(
+                        "Committees differ, results may not be comparable: {} vs {}",
+                        new, old
+                    );
+                }
+            }
             let cmp_table = cmp.to_table();
             eprintln!(
                 "Benchmark Comparison Report[{}]:",
@@ -159,6 +700,42 @@ async fn main() -> Result<()> {
             let serialized = serde_json::to_string(&benchmark_stats)?;
             std::fs::write(curr_benchmark_stats_path, serialized)?;
         }
+        if let Some(histogram_export_path) = histogram_export_path {
+            benchmark_stats.latency_ms.export(&histogram_export_path)?;
+        }
+        if let Some(hgrm_output_dir) = hgrm_output_dir {
+            benchmark_stats.export_hgrm(Path::new(&hgrm_output_dir))?;
+        }
+        if !smoke_workloads.is_empty() {
+            // A workload passes when at least one of its transactions
+            // succeeded and none failed its postcondition
+            eprintln!("Smoke Test:");
+            let mut failed = vec![];
+            for workload in smoke_workloads.iter() {
+                let name = workload.to_string();
+                let succeeded = benchmark_stats
+                    .outcome_latency_ms
+                    .get(&name)
+                    .map_or(0, |outcome| outcome.succeeded());
+                let postcondition_failures = benchmark_stats
+                    .postcondition_failures
+                    .get(&name)
+                    .copied()
+                    .unwrap_or(0);
+                if succeeded > 0 && postcondition_failures == 0 {
+                    eprintln!("PASS {}: {} transactions succeeded", name, succeeded);
+                } else {
+                    eprintln!(
+                        "FAIL {}: {} transactions succeeded, {} failed their postcondition",
+                        name, succeeded, postcondition_failures
+                    );
+                    failed.push(name);
+                }
+            }
+            if !failed.is_empty() {
+                return Err(anyhow!("Smoke test failed for {}", failed.join(", ")));
+            }
+        }
         Ok(())
     }
 }
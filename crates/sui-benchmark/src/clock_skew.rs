@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::HistogramWrapper;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
+
+/// How much longer than measured locally a latency measured against the
+/// network clock may be before the clocks are considered skewed. The
+/// checkpoint is timestamped before the client sees it, so with synced
+/// clocks it is never longer
+pub const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_millis(500);
+
+/// Finality latency measured from the client's submission time to the
+/// timestamp of the checkpoint including the transaction, next to the same
+/// latency measured on the client clock alone. Skewed clocks make the
+/// former negative or longer than the latter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockSkew {
+    // Negative latencies are recorded as zero
+    pub checkpoint_latency_ms: HistogramWrapper,
+    pub measurements: u64,
+    // Checkpoint timestamped before the transaction was submitted
+    pub negative: u64,
+    // Checkpoint latency longer than the local one by more than
+    // CLOCK_SKEW_TOLERANCE
+    pub too_long: u64,
+    // Largest difference between the checkpoint and local latencies,
+    // positive when the client clock is behind
+    pub max_skew_ms: i64,
+}
+
+impl ClockSkew {
+    pub fn new() -> Self {
+        Self {
+            checkpoint_latency_ms: HistogramWrapper::new(),
+            measurements: 0,
+            negative: 0,
+            too_long: 0,
+            max_skew_ms: 0,
+        }
+    }
+
+    /// Records the latency from submission at `submitted_ms` to a checkpoint
+    /// timestamped `checkpoint_ms`, both milliseconds since the Unix epoch,
+    /// against the `local` latency of the same transaction
+    pub fn record(&mut self, submitted_ms: u64, checkpoint_ms: u64, local: Duration) {
+        let latency_ms = checkpoint_ms as i64 - submitted_ms as i64;
+        let skew_ms = latency_ms - local.as_millis() as i64;
+        self.measurements += 1;
+        if skew_ms.abs() > self.max_skew_ms.abs() {
+            self.max_skew_ms = skew_ms;
+        }
+        if latency_ms < 0 {
+            self.negative += 1;
+        } else if skew_ms > CLOCK_SKEW_TOLERANCE.as_millis() as i64 {
+            self.too_long += 1;
+        }
+        if latency_ms < 0 || skew_ms > CLOCK_SKEW_TOLERANCE.as_millis() as i64 {
+            warn!(
+                "Implausible checkpoint latency of {}ms against {}ms measured locally, \
+                client and network clocks may be skewed",
+                latency_ms,
+                local.as_millis()
+            );
+        }
+        self.checkpoint_latency_ms
+            .record(Duration::from_millis(latency_ms.max(0) as u64));
+    }
+
+    /// Measurements whose checkpoint latency is implausible
+    pub fn affected(&self) -> u64 {
+        self.negative + self.too_long
+    }
+}
+
+impl Default for ClockSkew {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_skew() {
+        let mut skew = ClockSkew::new();
+        // Checkpointed 400ms after submission, seen locally after 450ms
+        skew.record(10_000, 10_400, Duration::from_millis(450));
+        assert_eq!(skew.affected(), 0);
+        // Client clock 2s ahead of the network
+        skew.record(12_000, 10_400, Duration::from_millis(450));
+        // Client clock 2s behind the network
+        skew.record(10_000, 12_400, Duration::from_millis(450));
+        assert_eq!((skew.negative, skew.too_long, skew.affected()), (1, 1, 2));
+        assert_eq!(skew.measurements, 3);
+        assert_eq!(skew.max_skew_ms, -2050);
+        assert_eq!(skew.checkpoint_latency_ms.count(), 3);
+        // The negative latency is clamped to zero
+        assert_eq!(skew.checkpoint_latency_ms.min_ms(), 0);
+    }
+}
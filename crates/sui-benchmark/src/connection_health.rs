@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::warn;
+
+/// Transactions a connection has to fail in a row before it is excluded
+pub const CONNECTION_FAILURE_THRESHOLD: u64 = 10;
+
+/// A connection taken out of the round robin of its proxy
+#[derive(Debug, Clone)]
+pub struct ConnectionExclusion {
+    pub connection: usize,
+    pub at: Instant,
+    // Error of the failure that excluded the connection
+    pub error: String,
+}
+
+/// Health of the connections a proxy round-robins over, shared by its clones.
+/// A connection failing `threshold` transactions in a row is excluded for the
+/// rest of the run and its share goes to the others. The last healthy
+/// connection is never excluded
+pub struct ConnectionHealth {
+    consecutive_failures: Vec<AtomicU64>,
+    excluded: Vec<AtomicBool>,
+    exclusions: Mutex<Vec<ConnectionExclusion>>,
+    threshold: u64,
+}
+
+impl ConnectionHealth {
+    pub fn new(num_connections: usize, threshold: u64) -> Self {
+        Self {
+            consecutive_failures: (0..num_connections).map(|_| AtomicU64::new(0)).collect(),
+            excluded: (0..num_connections)
+                .map(|_| AtomicBool::new(false))
+                .collect(),
+            exclusions: Mutex::new(vec![]),
+            threshold,
+        }
+    }
+
+    /// First connection at or after `connection` in round-robin order that
+    /// is not excluded
+    pub fn next_healthy(&self, connection: usize) -> usize {
+        let n = self.excluded.len();
+        (0..n)
+            .map(|offset| (connection + offset) % n)
+            .find(|i| !self.excluded[*i].load(Ordering::Relaxed))
+            .unwrap_or(connection % n)
+    }
+
+    pub fn record_success(&self, connection: usize) {
+        self.consecutive_failures[connection].store(0, Ordering::Relaxed);
+    }
+
+    pub fn record_failure(&self, connection: usize, error: &anyhow::Error) {
+        let failures = self.consecutive_failures[connection].fetch_add(1, Ordering::Relaxed) + 1;
+        if failures < self.threshold || self.excluded[connection].load(Ordering::Relaxed) {
+            return;
+        }
+        let mut exclusions = self.exclusions.lock().unwrap();
+        let healthy = self
+            .excluded
+            .iter()
+            .filter(|excluded| !excluded.load(Ordering::Relaxed))
+            .count();
+        if self.excluded[connection].load(Ordering::Relaxed) || healthy <= 1 {
+            return;
+        }
+        self.excluded[connection].store(true, Ordering::Relaxed);
+        warn!(
+            "Excluding connection {} after {} failed transactions in a row, {} connection(s) left: {}",
+            connection,
+            failures,
+            healthy - 1,
+            error
+        );
+        exclusions.push(ConnectionExclusion {
+            connection,
+            at: Instant::now(),
+            error: error.to_string(),
+        });
+    }
+
+    pub fn exclusions(&self) -> Vec<ConnectionExclusion> {
+        self.exclusions.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failing_connection_is_excluded() {
+        let health = ConnectionHealth::new(2, 3);
+        let err = anyhow::anyhow!("connection refused");
+        health.record_failure(1, &err);
+        health.record_failure(1, &err);
+        // A success resets the count
+        health.record_success(1);
+        health.record_failure(1, &err);
+        health.record_failure(1, &err);
+        assert_eq!(health.next_healthy(1), 1);
+        health.record_failure(1, &err);
+        assert_eq!(health.next_healthy(1), 0);
+        assert_eq!(health.exclusions().len(), 1);
+        assert_eq!(health.exclusions()[0].connection, 1);
+
+        // The last healthy connection stays
+        for _ in 0..5 {
+            health.record_failure(0, &err);
+        }
+        assert_eq!(health.next_healthy(1), 0);
+        assert_eq!(health.exclusions().len(), 1);
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::retrying_proxy::RetryStats;
+use crate::rng::bench_rng;
+use crate::{EffectsWait, ExecutionEffects, ObjectQuery, ValidatorProxy};
+use async_trait::async_trait;
+use rand::Rng;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+
+/// Delay added to each call, parsed from "fixed:<ms>" or
+/// "uniform:<min_ms>:<max_ms>"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum ProxyDelay {
+    Fixed(Duration),
+    Uniform(Duration, Duration),
+}
+
+impl ProxyDelay {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        match self {
+            ProxyDelay::Fixed(delay) => *delay,
+            ProxyDelay::Uniform(min, max) => rng.gen_range(*min..=*max),
+        }
+    }
+}
+
+impl FromStr for ProxyDelay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_ms = |ms: &str| -> Result<Duration, String> {
+            Ok(Duration::from_millis(
+                ms.parse().map_err(|e| format!("{e}"))?,
+            ))
+        };
+        let parts: Vec<&str> = s.split(':').collect();
+        match parts.as_slice() {
+            ["fixed", delay] => Ok(ProxyDelay::Fixed(parse_ms(delay)?)),
+            ["uniform", min, max] => {
+                let (min, max) = (parse_ms(min)?, parse_ms(max)?);
+                if min > max {
+                    return Err("Minimum delay must not exceed the maximum".to_string());
+                }
+                Ok(ProxyDelay::Uniform(min, max))
+            }
+            _ => Err(
+                "Required delay of the form fixed:<ms> or uniform:<min_ms>:<max_ms>".to_string(),
+            ),
+        }
+    }
+}
+
+/// Wraps any proxy and sleeps for a sampled delay before forwarding each
+/// network call, simulating network latency without a network
+pub struct DelayingProxy {
+    inner: Arc<dyn ValidatorProxy + Send + Sync>,
+    delay: ProxyDelay,
+}
+
+impl DelayingProxy {
+    pub fn new(inner: Arc<dyn ValidatorProxy + Send + Sync>, delay: ProxyDelay) -> Self {
+        Self { inner, delay }
+    }
+
+    async fn delay(&self) {
+        let delay = self.delay.sample(&mut bench_rng());
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for DelayingProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        self.delay().await;
+        self.inner.get_object(object_id).await
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        self.delay().await;
+        self.inner.execute_transaction(tx).await
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.inner.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.inner.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            inner: Arc::from(self.inner.clone_new()),
+            delay: self.delay,
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        self.delay().await;
+        self.inner.get_validators().await
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.inner.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.inner.excluded_connections()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        self.inner.clock_skew()
+    }
+
+    fn retry_stats(&self) -> Option<RetryStats> {
+        self.inner.retry_stats()
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            inner: Arc::from(self.inner.with_effects_wait(wait)?),
+            delay: self.delay,
+        }))
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        self.delay().await;
+        self.inner.query_object(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording_proxy::{RecordedCall, ReplayProxy};
+    use tokio::time::Instant;
+
+    #[tokio::test]
+    async fn test_delaying_proxy() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let object_id = ObjectID::random();
+        let object = Object::immutable_with_id_for_testing(object_id);
+        let replay = ReplayProxy::from_calls(vec![
+            RecordedCall::Committee(committee),
+            RecordedCall::GetObject(object_id, Ok(object.clone())),
+        ])
+        .unwrap();
+
+        let delay = ProxyDelay::from_str("fixed:50").unwrap();
+        let proxy = DelayingProxy::new(Arc::new(replay), delay);
+        let start = Instant::now();
+        assert_eq!(proxy.get_object(object_id).await.unwrap(), object);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let uniform = ProxyDelay::from_str("uniform:10:20").unwrap();
+        for _ in 0..100 {
+            let delay = uniform.sample(&mut rand::thread_rng());
+            assert!(delay >= Duration::from_millis(10) && delay <= Duration::from_millis(20));
+        }
+        assert!(ProxyDelay::from_str("uniform:20:10").is_err());
+        assert!(ProxyDelay::from_str("lognormal:10:1").is_err());
+    }
+}
@@ -5,54 +5,97 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::future::try_join_all;
 use futures::future::BoxFuture;
-use futures::FutureExt;
 use futures::{stream::FuturesUnordered, StreamExt};
+use futures::{Future, FutureExt};
 use indicatif::ProgressBar;
 use indicatif::ProgressStyle;
 use prometheus::register_gauge_vec_with_registry;
+use prometheus::register_gauge_with_registry;
 use prometheus::register_histogram_vec_with_registry;
 use prometheus::register_int_counter_vec_with_registry;
+use prometheus::register_int_gauge_with_registry;
+use prometheus::Gauge;
 use prometheus::GaugeVec;
 use prometheus::HistogramVec;
 use prometheus::IntCounterVec;
+use prometheus::IntGauge;
 use prometheus::Registry;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::OnceCell;
 use tokio_util::sync::CancellationToken;
 
+use crate::drivers::burst::{BurstPattern, Bursts};
 use crate::drivers::driver::Driver;
-use crate::drivers::HistogramWrapper;
+use crate::drivers::inflight_tuner::{inflight_window, InflightTuner, FULL_WINDOW};
+use crate::drivers::influx::{InfluxOutput, IntervalMeasurement};
+use crate::drivers::progress::ProgressFile;
+use crate::drivers::ramp_up::{RampPacer, RampUp};
+use crate::drivers::timeseries::{spawn_timeseries, TimeseriesRecorder};
+use crate::drivers::warmup::{Warmup, WarmupBarrier};
+use crate::drivers::{
+    projected_depletion, BuildLatency, DuplicateDetector, ExcludedConnection, GasBalanceSample,
+    GasUsage, HistogramWrapper, Label, OrderingTracker, OutcomeLatency, SlowTransaction,
+    SlowestTransactions, SpendBudget,
+};
+use crate::options::{Opts, RunSpec};
 use crate::system_state_observer::SystemStateObserver;
 use crate::workloads::payload::Payload;
-use crate::workloads::workload::WorkloadInfo;
-use crate::ValidatorProxy;
-use std::collections::{BTreeMap, VecDeque};
-use std::sync::Arc;
+use crate::workloads::workload::{WorkloadInfo, WorkloadType};
+use crate::{ExecutionEffects, ValidatorProxy};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+use sui_types::committee::{Committee, EpochId};
 use sui_types::crypto::AuthorityStrongQuorumSignInfo;
-use sui_types::messages::VerifiedTransaction;
-use sysinfo::{CpuExt, System, SystemExt};
+use sui_types::gas_coin::GasCoin;
+use sui_types::messages::{InputObjectKind, TransactionData, VerifiedTransaction};
+use sui_types::object::Owner;
+use sysinfo::{CpuExt, ProcessExt, System, SystemExt};
 use tokio::sync::Barrier;
 use tokio::{time, time::Instant};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use super::Interval;
 use super::{BenchmarkStats, StressStats};
 pub struct BenchMetrics {
     pub num_success: IntCounterVec,
     pub num_error: IntCounterVec,
+    pub num_timeout: IntCounterVec,
+    pub num_version_violations: IntCounterVec,
+    pub num_invalid_effects_signatures: IntCounterVec,
+    pub num_objects_over_max_version: IntCounterVec,
+    pub num_duplicate: IntCounterVec,
+    pub num_postcondition_failed: IntCounterVec,
     pub num_submitted: IntCounterVec,
     pub num_in_flight: GaugeVec,
     pub latency_s: HistogramVec,
+    pub computation_cost: HistogramVec,
     pub validators_in_tx_cert: IntCounterVec,
     pub validators_in_effects_cert: IntCounterVec,
     pub cpu_usage: GaugeVec,
+    pub client_cpu_usage: Gauge,
+    pub qps: GaugeVec,
+    pub reference_gas_price: IntGauge,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.01, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10., 20., 30., 60., 90.,
 ];
 
+// Computation cost of a transaction in gas units
+const COMPUTATION_COST_BUCKETS: &[f64] = &[
+    100., 500., 1_000., 5_000., 10_000., 50_000., 100_000., 500_000., 1_000_000., 5_000_000.,
+];
+
+// Wake-up period of an idle closed-loop worker, its transactions are
+// otherwise issued as responses free up its window
+const CLOSED_LOOP_POLL_MICROS: u64 = 10_000;
+
+// Gas depletion is warned about this far ahead when the run has no set end
+const GAS_DEPLETION_HORIZON: Duration = Duration::from_secs(3600);
+
 impl BenchMetrics {
     fn new(registry: &Registry) -> Self {
         BenchMetrics {
@@ -70,6 +113,48 @@ impl BenchMetrics {
                 registry,
             )
             .unwrap(),
+            num_timeout: register_int_counter_vec_with_registry!(
+                "num_timeout",
+                "Total number of transactions that did not resolve before their deadline",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
+            num_version_violations: register_int_counter_vec_with_registry!(
+                "num_version_violations",
+                "Total number of mutated objects whose new version does not follow the input versions",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
+            num_objects_over_max_version: register_int_counter_vec_with_registry!(
+                "num_objects_over_max_version",
+                "Number of objects mutated past --max-object-version",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
+            num_invalid_effects_signatures: register_int_counter_vec_with_registry!(
+                "num_invalid_effects_signatures",
+                "Total number of effects whose validator signatures failed verification",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
+            num_duplicate: register_int_counter_vec_with_registry!(
+                "num_duplicate",
+                "Total number of transactions built more than once",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
+            num_postcondition_failed: register_int_counter_vec_with_registry!(
+                "num_postcondition_failed",
+                "Total number of executed transactions that failed their workload postcondition",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
             num_submitted: register_int_counter_vec_with_registry!(
                 "num_submitted",
                 "Total number of transaction submitted to sui",
@@ -92,6 +177,14 @@ impl BenchMetrics {
                 registry,
             )
             .unwrap(),
+            computation_cost: register_histogram_vec_with_registry!(
+                "computation_cost",
+                "Computation cost in gas units of successful transactions",
+                &["workload"],
+                COMPUTATION_COST_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             validators_in_tx_cert: register_int_counter_vec_with_registry!(
                 "validators_in_tx_cert",
                 "Number of times a validator was included in tx cert",
@@ -113,22 +206,72 @@ impl BenchMetrics {
                 registry,
             )
             .unwrap(),
+            client_cpu_usage: register_gauge_with_registry!(
+                "client_cpu_usage",
+                "CPU usage of the benchmark client process as a share of all cores",
+                registry,
+            )
+            .unwrap(),
+            qps: register_gauge_vec_with_registry!(
+                "qps",
+                "Transactions succeeded per second over the last stat interval",
+                &["workload"],
+                registry,
+            )
+            .unwrap(),
+            reference_gas_price: register_int_gauge_with_registry!(
+                "reference_gas_price",
+                "Reference gas price of the current epoch",
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
 
 struct Stats {
     pub id: usize,
+    pub workload: String,
     pub num_no_gas: u64,
     pub num_submitted: u64,
     pub num_in_flight: u64,
+    // Epochs at the start and end of the measurement window
+    pub epochs: (EpochId, EpochId),
+    // False for the warmup of --warmup-secs, which is left out of the results
+    pub measured: bool,
     pub bench_stats: BenchmarkStats,
 }
 
-type RetryType = Box<(VerifiedTransaction, Box<dyn Payload>)>;
+// The transaction, its payload, the time of its first attempt, the time
+// it took to build and the number of its failed attempts
+type RetryType = Box<(
+    VerifiedTransaction,
+    Box<dyn Payload>,
+    Instant,
+    Duration,
+    u64,
+)>;
 enum NextOp {
-    Response(Option<(Duration, Box<dyn Payload>)>),
-    Retry(RetryType),
+    // Latency of the successful attempt, latency since the first attempt and
+    // number of attempts if the transaction was retried, time it took to
+    // build the transaction (None for queries), the next payload and the
+    // workload of the completed one, which differs from the next payload's
+    // in a combination
+    Response(
+        Option<(
+            Duration,
+            Option<(Duration, u64)>,
+            Option<Duration>,
+            Box<dyn Payload>,
+            WorkloadType,
+        )>,
+    ),
+    // Latency of the failed attempt
+    Retry(RetryType, Duration),
+    // The transaction did not resolve before its deadline
+    Timeout(RetryType, Duration),
+    // Failed read-only queries are not retried
+    QueryFailure(Box<dyn Payload>),
 }
 
 async fn print_and_start_benchmark() -> &'static Instant {
@@ -144,20 +287,174 @@ async fn print_and_start_benchmark() -> &'static Instant {
 pub struct BenchWorker {
     pub target_qps: u64,
     pub payload: Vec<Box<dyn Payload>>,
+    pub workload_type: WorkloadType,
+    // Delay after the benchmark start before this worker issues transactions
+    pub start_offset: Duration,
 }
 
-pub struct BenchDriver {
+/// Settings of a bench run, read from the options of the stress binary by
+/// `BenchDriverConfig::from_opts`
+#[derive(Debug, Clone)]
+pub struct BenchDriverConfig {
     pub stat_collection_interval: u64,
     pub stress_stat_collection: bool,
+    // How long workers stop issuing new transactions after an epoch change
+    pub epoch_change_pause: Duration,
+    // Transactions not resolved within this deadline are counted as timed
+    // out, releasing their in-flight slot and retiring their payload
+    pub tx_deadline: Option<Duration>,
+    // Delay between the start of consecutive workloads
+    pub workload_stagger: Duration,
+    // Verify the validator signatures on every effects received
+    pub verify_effects_signatures: bool,
+    // How long workers sleep before trying again when the in-flight window is full
+    pub inflight_full_backoff: Option<Duration>,
+    // File or Influx write endpoint receiving per-interval measurements
+    pub influx_output: Option<String>,
+    // Objects mutated past this version are reported as likely hotspots
+    pub max_object_version: Option<SequenceNumber>,
+    // Narrow the in-flight window until the p99 latency meets this target
+    pub target_p99_ms: Option<u64>,
+    // Number of slowest transactions kept to be dumped after the run
+    pub dump_slowest: Option<usize>,
+    // Records the latency of the first transactions of the run
+    pub measure_warmup: Option<Warmup>,
+    // Run the workloads one after the other, each for the whole run duration
+    pub sequential_workloads: bool,
+    // How often the balance of the most spent payload gas coin is checked
+    pub gas_monitor_interval: Option<Duration>,
+    // Also measure latency from the start of building each transaction
+    pub measure_build_latency: bool,
+    // Issue transactions in bursts separated by idle windows
+    pub burst: Option<BurstPattern>,
+    // Run labels added as tags to every influx measurement
+    pub labels: Vec<Label>,
+    // Flag transactions built more than once during the run
+    pub detect_duplicates: bool,
+    // File receiving per-second qps, in-flight and latency of each workload
+    pub timeseries_csv: Option<String>,
+    // Stop the run once the gas spent, net of rebates, reaches this many MIST
+    pub max_total_mist_spend: Option<u64>,
+    // Check the postcondition of every successful transaction of a workload
+    pub check_postconditions: bool,
+    // File the progress of the run is saved to, for --resume
+    pub progress_file: Option<ProgressFile>,
+    // Replace every completed transaction right away instead of issuing at
+    // the target qps, keeping the in-flight window full
+    pub closed_loop: bool,
+    // Warmup left out of the results, measuring starts once its
+    // transactions in flight completed
+    pub warmup_duration: Option<Duration>,
+    // Compare the submit order of shared object transactions to the order
+    // consensus committed them in
+    pub track_ordering: bool,
+    // Stop issuing at the end of a timed run, then let the transactions in
+    // flight complete instead of abandoning them
+    pub drain_in_flight: bool,
+    // Climb to the target qps and in-flight window from a lower start
+    pub ramp_up: Option<RampUp>,
+    // Time the first Ctrl-C leaves the transactions in flight to complete
+    pub drain_timeout: Duration,
+}
+
+impl BenchDriverConfig {
+    /// Reads the settings of `opts`. `progress_file` holds the progress of
+    /// --resume loaded for this session and `drain_in_flight` is set when
+    /// the run ends after a set duration, see `Opts::apply_duration`
+    pub fn from_opts(
+        opts: &Opts,
+        progress_file: Option<ProgressFile>,
+        drain_in_flight: bool,
+    ) -> Self {
+        let target_p99_ms = match opts.run_spec {
+            RunSpec::Bench { target_p99_ms, .. } => target_p99_ms,
+            _ => None,
+        };
+        Self {
+            stat_collection_interval: opts.stat_collection_interval,
+            stress_stat_collection: opts.stress_stat_collection,
+            epoch_change_pause: Duration::from_millis(opts.epoch_change_pause_ms),
+            tx_deadline: opts.tx_deadline_ms.map(Duration::from_millis),
+            workload_stagger: Duration::from_secs(opts.workload_stagger_secs),
+            verify_effects_signatures: opts.verify_effects_signatures,
+            inflight_full_backoff: opts.inflight_full_backoff_us.map(Duration::from_micros),
+            influx_output: opts.output_influx.clone(),
+            max_object_version: opts.max_object_version.map(SequenceNumber::from),
+            target_p99_ms,
+            dump_slowest: opts.dump_slowest,
+            measure_warmup: opts.measure_warmup.then(|| {
+                Warmup::new(
+                    opts.warmup_samples,
+                    Duration::from_millis(opts.warmup_threshold_ms),
+                )
+            }),
+            sequential_workloads: opts.sequential_workloads,
+            gas_monitor_interval: opts.gas_monitor_secs.map(Duration::from_secs),
+            measure_build_latency: opts.measure_build_latency,
+            burst: opts.burst,
+            labels: opts.labels.clone(),
+            detect_duplicates: opts.detect_duplicate_transactions,
+            timeseries_csv: opts.timeseries_csv.clone(),
+            max_total_mist_spend: opts.max_total_mist_spend,
+            check_postconditions: opts.check_postconditions,
+            progress_file,
+            closed_loop: opts.closed_loop,
+            warmup_duration: opts.warmup_secs.map(Duration::from_secs),
+            track_ordering: opts.track_ordering,
+            drain_in_flight,
+            ramp_up: opts.ramp_up(),
+            drain_timeout: Duration::from_secs(opts.drain_timeout_secs),
+        }
+    }
+}
+
+impl Default for BenchDriverConfig {
+    /// Matches the defaults of the stress binary's flags: every optional
+    /// behavior off, stats every 10 seconds and a 30 second drain
+    fn default() -> Self {
+        Self {
+            stat_collection_interval: 10,
+            stress_stat_collection: false,
+            epoch_change_pause: Duration::ZERO,
+            tx_deadline: None,
+            workload_stagger: Duration::ZERO,
+            verify_effects_signatures: false,
+            inflight_full_backoff: None,
+            influx_output: None,
+            max_object_version: None,
+            target_p99_ms: None,
+            dump_slowest: None,
+            measure_warmup: None,
+            sequential_workloads: false,
+            gas_monitor_interval: None,
+            measure_build_latency: false,
+            burst: None,
+            labels: vec![],
+            detect_duplicates: false,
+            timeseries_csv: None,
+            max_total_mist_spend: None,
+            check_postconditions: false,
+            progress_file: None,
+            closed_loop: false,
+            warmup_duration: None,
+            track_ordering: false,
+            drain_in_flight: false,
+            ramp_up: None,
+            drain_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+pub struct BenchDriver {
+    pub config: BenchDriverConfig,
     pub start_time: Instant,
     pub token: CancellationToken,
 }
 
 impl BenchDriver {
-    pub fn new(stat_collection_interval: u64, stress_stat_collection: bool) -> BenchDriver {
+    pub fn new(config: BenchDriverConfig) -> BenchDriver {
         BenchDriver {
-            stat_collection_interval,
-            stress_stat_collection,
+            config,
             start_time: Instant::now(),
             token: CancellationToken::new(),
         }
@@ -216,6 +513,8 @@ impl BenchDriver {
                 workers.push(BenchWorker {
                     target_qps,
                     payload: payloads,
+                    workload_type: workload_info.workload.get_workload_type(),
+                    start_offset: Duration::ZERO,
                 });
                 payloads = remaining;
                 qps -= target_qps;
@@ -226,6 +525,255 @@ impl BenchDriver {
     }
 }
 
+/// Returns (object id, input version, output version) of every input object of an
+/// owned-object-only transaction whose mutated version is not the lamport increment
+/// of the input versions, which indicates a lost update or a fork. Transactions with
+/// shared inputs are skipped since consensus assigns their versions.
+fn version_violations(
+    tx_data: &TransactionData,
+    mutated: &[(ObjectRef, Owner)],
+) -> Vec<(ObjectID, SequenceNumber, SequenceNumber)> {
+    if tx_data.contains_shared_object() {
+        return vec![];
+    }
+    let inputs: Vec<ObjectRef> = match tx_data.input_objects() {
+        Ok(inputs) => inputs
+            .into_iter()
+            .filter_map(|kind| match kind {
+                InputObjectKind::ImmOrOwnedMoveObject(object_ref) => Some(object_ref),
+                _ => None,
+            })
+            .collect(),
+        Err(_) => return vec![],
+    };
+    let expected = SequenceNumber::lamport_increment(inputs.iter().map(|o| o.1));
+    inputs
+        .iter()
+        .filter_map(|(id, input_version, _)| {
+            mutated
+                .iter()
+                .find(|(o, _)| o.0 == *id && o.1 != expected)
+                .map(|(o, _)| (*id, *input_version, o.1))
+        })
+        .collect()
+}
+
+/// Returns (object id, new version) of the mutated objects whose version
+/// is above `max_version`
+fn objects_over_version(
+    mutated: &[(ObjectRef, Owner)],
+    max_version: SequenceNumber,
+) -> Vec<(ObjectID, SequenceNumber)> {
+    mutated
+        .iter()
+        .filter(|(o, _)| o.1 > max_version)
+        .map(|(o, _)| (o.0, o.1))
+        .collect()
+}
+
+/// Adds the budget and the gas used by a transaction, in gas units, to the
+/// gas usage of its workload
+fn record_gas_usage(
+    tx_data: &TransactionData,
+    effects: &ExecutionEffects,
+    gas_usage: &Mutex<BTreeMap<String, GasUsage>>,
+    workload_type: WorkloadType,
+) {
+    let used = effects.gas_used().gas_used() / tx_data.gas_price.max(1);
+    gas_usage
+        .lock()
+        .unwrap()
+        .entry(workload_type.to_string())
+        .or_default()
+        .record(tx_data.gas_budget, used);
+}
+
+/// Counts each validator that signed the effects certificate of a
+/// successful transaction, a validator missing from most certificates is
+/// lagging behind the rest of the committee
+fn record_effects_signers(
+    effects: &ExecutionEffects,
+    committee: &Committee,
+    metrics: &BenchMetrics,
+    effects_signers: &Mutex<BTreeMap<String, u64>>,
+) {
+    if let Some(sig_info) = effects.quorum_sig() {
+        let mut effects_signers = effects_signers.lock().unwrap();
+        sig_info.authorities(committee).for_each(|name| {
+            let name = name.unwrap().to_string();
+            metrics
+                .validators_in_effects_cert
+                .with_label_values(&[&name])
+                .inc();
+            *effects_signers.entry(name).or_default() += 1;
+        });
+    }
+}
+
+/// Adds the gas a transaction spent, net of storage rebates, to its gas coin
+fn record_gas_spent(effects: &ExecutionEffects, gas_spent: &Mutex<HashMap<ObjectID, u64>>) {
+    let spent = effects.gas_used().net_gas_usage().max(0) as u64;
+    *gas_spent
+        .lock()
+        .unwrap()
+        .entry(effects.gas_object().0 .0)
+        .or_default() += spent;
+}
+
+/// Adds the gas a transaction spent to the spend budget, and stops the run
+/// once the budget is used up
+fn record_spend(
+    effects: &ExecutionEffects,
+    spend_budget: &Option<Arc<Mutex<SpendBudget>>>,
+    token: &CancellationToken,
+) {
+    if let Some(spend_budget) = spend_budget {
+        let spent = effects.gas_used().net_gas_usage().max(0) as u64;
+        let mut spend_budget = spend_budget.lock().unwrap();
+        if spend_budget.record(spent) {
+            warn!(
+                "Spent {} MIST, reaching --max-total-mist-spend of {}, stopping the benchmark",
+                spend_budget.spent, spend_budget.cap
+            );
+            token.cancel();
+        }
+    }
+}
+
+/// Checks the postcondition of the payload a successful transaction was
+/// built from, if any, and counts the transactions failing it by workload
+async fn check_postcondition(
+    payload: &dyn Payload,
+    effects: &ExecutionEffects,
+    proxy: &Option<Arc<Box<dyn ValidatorProxy + Send + Sync>>>,
+    metrics: &BenchMetrics,
+    postcondition_failures: &Mutex<BTreeMap<String, u64>>,
+) {
+    let (proxy, postcondition) = match (proxy, payload.postcondition()) {
+        (Some(proxy), Some(postcondition)) => (proxy, postcondition),
+        _ => return,
+    };
+    if let Err(err) = postcondition.check(effects, proxy.as_ref().as_ref()).await {
+        let workload_type = payload.get_workload_type().to_string();
+        error!(
+            "Transaction of {} failed its postcondition: {}",
+            workload_type, err
+        );
+        metrics
+            .num_postcondition_failed
+            .with_label_values(&[&workload_type])
+            .inc();
+        *postcondition_failures
+            .lock()
+            .unwrap()
+            .entry(workload_type)
+            .or_default() += 1;
+    }
+}
+
+/// Keeps `tx` among the slowest transactions if its latency, counted from
+/// its first attempt, is high enough
+fn record_slow_transaction(
+    tx: &VerifiedTransaction,
+    latency: Duration,
+    last_attempt: Duration,
+    slowest: &Mutex<SlowestTransactions>,
+    workload_type: WorkloadType,
+) {
+    let mut slowest = slowest.lock().unwrap();
+    if !slowest.keeps(latency) {
+        return;
+    }
+    let inputs = tx
+        .data()
+        .intent_message
+        .value
+        .input_objects()
+        .map(|inputs| inputs.iter().map(|kind| kind.object_id()).collect())
+        .unwrap_or_default();
+    slowest.record(SlowTransaction {
+        digest: *tx.digest(),
+        workload: workload_type.to_string(),
+        latency,
+        last_attempt,
+        inputs,
+    });
+}
+
+/// Records the version each shared object mutated by `tx` was given, with
+/// the time `tx` was first submitted, for --track-ordering
+fn record_ordering(
+    tx: &VerifiedTransaction,
+    effects: &ExecutionEffects,
+    submitted: Instant,
+    ordering: &Option<Arc<Mutex<OrderingTracker>>>,
+    workload_type: WorkloadType,
+) {
+    let ordering = match ordering {
+        Some(ordering) => ordering,
+        None => return,
+    };
+    let mutated = effects.mutated();
+    let mut ordering = ordering.lock().unwrap();
+    for shared in tx.data().intent_message.value.shared_input_objects() {
+        if let Some((object_ref, _)) = mutated
+            .iter()
+            .find(|(object_ref, _)| object_ref.0 == shared.id)
+        {
+            ordering.record(
+                shared.id,
+                object_ref.1,
+                submitted,
+                &workload_type.to_string(),
+            );
+        }
+    }
+}
+
+/// Reports the objects of `effects` mutated past `max_version`, each object
+/// only the first time it is seen over the limit
+fn flag_objects_over_version(
+    effects: &ExecutionEffects,
+    max_version: Option<SequenceNumber>,
+    flagged_objects: &Mutex<HashSet<ObjectID>>,
+    metrics: &BenchMetrics,
+    workload_type: WorkloadType,
+) {
+    let max_version = match max_version {
+        Some(max_version) => max_version,
+        None => return,
+    };
+    for (id, version) in objects_over_version(&effects.mutated(), max_version) {
+        if flagged_objects.lock().unwrap().insert(id) {
+            warn!(
+                "Object {} reached version {}, above --max-object-version {}. \
+                This usually means a hotspot is serializing the workload",
+                id, version, max_version
+            );
+            metrics
+                .num_objects_over_max_version
+                .with_label_values(&[&workload_type.to_string()])
+                .inc();
+        }
+    }
+}
+
+/// Resolves right away when a closed-loop worker has a slot to refill,
+/// otherwise on the next tick of `interval`
+async fn next_request(interval: &mut time::Interval, refill: bool) {
+    if !refill {
+        interval.tick().await;
+    }
+}
+
+/// Resolves to None, dropping `fut`, if it does not complete within `deadline`
+async fn with_deadline<F: Future>(fut: F, deadline: Option<Duration>) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => time::timeout(deadline, fut).await.ok(),
+        None => Some(fut.await),
+    }
+}
+
 #[cfg(not(msim))]
 async fn ctrl_c() -> std::io::Result<()> {
     tokio::signal::ctrl_c().await
@@ -253,18 +801,61 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
         let mut tasks = Vec::new();
         let (tx, mut rx) = tokio::sync::mpsc::channel(100);
         let (stress_stat_tx, mut stress_stat_rx) = tokio::sync::mpsc::channel(100);
+        // Each workload runs alone for its window in sequential mode
+        let sequential_window = match run_duration {
+            _ if !self.config.sequential_workloads => None,
+            Interval::Time(duration) if duration != Duration::MAX => Some(duration),
+            _ => {
+                return Err(anyhow!(
+                    "--sequential-workloads runs each workload for --run-duration in turn, \
+                    which must be a bounded time"
+                ))
+            }
+        };
+        let run_duration = match sequential_window {
+            Some(window) => Interval::Time(window * workloads.len() as u32),
+            None => run_duration,
+        };
+        if let Some(warmup_duration) = self.config.warmup_duration {
+            if self.config.sequential_workloads {
+                return Err(anyhow!(
+                    "--warmup-secs drains every workload at once, it cannot be combined \
+                    with --sequential-workloads"
+                ));
+            }
+            if matches!(run_duration, Interval::Time(duration) if duration <= warmup_duration) {
+                return Err(anyhow!(
+                    "--warmup-secs must be shorter than --run-duration, which includes it"
+                ));
+            }
+        }
+        let mut workload_windows = vec![];
         let mut bench_workers = vec![];
-        for workload in workloads.iter() {
-            bench_workers.extend(
-                self.make_workers(workload, proxy.clone(), system_state_observer.clone())
-                    .await,
-            );
+        for (k, workload) in workloads.iter().enumerate() {
+            let mut workers = self
+                .make_workers(workload, proxy.clone(), system_state_observer.clone())
+                .await;
+            let start_offset = match sequential_window {
+                Some(window) => {
+                    workload_windows.push((
+                        workload.workload.get_workload_type().to_string(),
+                        window * k as u32,
+                        window * (k as u32 + 1),
+                    ));
+                    window * k as u32
+                }
+                None => self.config.workload_stagger * k as u32,
+            };
+            for worker in workers.iter_mut() {
+                worker.start_offset = start_offset;
+            }
+            bench_workers.extend(workers);
         }
         let num_workers = bench_workers.len() as u64;
         if num_workers == 0 {
             return Err(anyhow!("No workers to run benchmark!"));
         }
-        let stat_delay_micros = 1_000_000 * self.stat_collection_interval;
+        let stat_delay_micros = 1_000_000 * self.config.stat_collection_interval;
         let metrics = Arc::new(BenchMetrics::new(registry));
         let barrier = Arc::new(Barrier::new(num_workers as usize));
         info!("Setting up {:?} workers...", num_workers);
@@ -281,14 +872,106 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                     ProgressStyle::with_template("{prefix}: {wide_bar} {pos}/{len}").unwrap(),
                 ),
         });
+        let flagged_objects = Arc::new(Mutex::new(HashSet::new()));
+        let gas_usage = Arc::new(Mutex::new(BTreeMap::new()));
+        let slowest = Arc::new(Mutex::new(SlowestTransactions::new(
+            self.config.dump_slowest.unwrap_or(0),
+        )));
+        let gas_spent = Arc::new(Mutex::new(HashMap::new()));
+        let effects_signers = Arc::new(Mutex::new(BTreeMap::new()));
+        let postcondition_failures = Arc::new(Mutex::new(BTreeMap::new()));
+        let spend_budget = self
+            .config
+            .max_total_mist_spend
+            .map(|cap| Arc::new(Mutex::new(SpendBudget::new(cap))));
+        let duplicates = self
+            .config
+            .detect_duplicates
+            .then(|| Arc::new(Mutex::new(DuplicateDetector::default())));
+        let ordering = self
+            .config
+            .track_ordering
+            .then(|| Arc::new(Mutex::new(OrderingTracker::default())));
+        let timeseries = self.config.timeseries_csv.as_ref().map(|_| {
+            Arc::new(TimeseriesRecorder::new(
+                bench_workers
+                    .iter()
+                    .map(|worker| worker.workload_type.to_string()),
+            ))
+        });
+        let bursts = self
+            .config
+            .burst
+            .map(|pattern| Arc::new(Mutex::new(Bursts::new(pattern))));
+        let warmup = self
+            .config
+            .measure_warmup
+            .clone()
+            .map(|warmup| Arc::new(Mutex::new(warmup)));
+        let warmup_barrier = self
+            .config
+            .warmup_duration
+            .map(|duration| Arc::new(WarmupBarrier::new(duration, num_workers as usize)));
+        // Share of each worker's payloads allowed in flight, in permille
+        let inflight_permille = Arc::new(AtomicU64::new(FULL_WINDOW));
+        // Cancelled by the first Ctrl-C, workers stop issuing and wait for
+        // their transactions in flight
+        let draining = CancellationToken::new();
+        // Transactions still in flight when the drain timed out
+        let abandoned = Arc::new(AtomicU64::new(0));
+        let worker_payloads: Vec<u64> = bench_workers
+            .iter()
+            .map(|worker| worker.payload.len() as u64)
+            .collect();
         for (i, worker) in bench_workers.into_iter().enumerate() {
             let cloned_token = self.token.clone();
-            let request_delay_micros = 1_000_000 / worker.target_qps;
+            let draining = draining.clone();
+            let abandoned = abandoned.clone();
+            // Closed-loop workers refill their window as responses come in, their
+            // request interval only wakes them up while paused or idle
+            let closed_loop = self.config.closed_loop;
+            let request_delay_micros = if closed_loop {
+                CLOSED_LOOP_POLL_MICROS
+            } else {
+                1_000_000 / worker.target_qps
+            };
             let mut free_pool = worker.payload;
+            let num_payloads = free_pool.len() as u64;
             let progress_cloned = progress.clone();
             let tx_cloned = tx.clone();
             let cloned_barrier = barrier.clone();
             let metrics_cloned = metrics.clone();
+            let mut epoch_rx = system_state_observer.epoch.clone();
+            let epoch_change_pause = self.config.epoch_change_pause;
+            let tx_deadline = self.config.tx_deadline;
+            let start_offset = worker.start_offset;
+            let workload_stagger = self.config.workload_stagger;
+            let verify_effects_signatures = self.config.verify_effects_signatures;
+            let inflight_full_backoff = self.config.inflight_full_backoff;
+            let max_object_version = self.config.max_object_version;
+            let flagged_objects = flagged_objects.clone();
+            let gas_usage = gas_usage.clone();
+            let slowest = slowest.clone();
+            let gas_spent = gas_spent.clone();
+            let effects_signers = effects_signers.clone();
+            let postcondition_failures = postcondition_failures.clone();
+            let check_postconditions = self.config.check_postconditions;
+            let duplicates = duplicates.clone();
+            let ordering = ordering.clone();
+            let spend_budget = spend_budget.clone();
+            let timeseries = timeseries.clone();
+            let timeseries_in_flight = timeseries
+                .as_ref()
+                .map(|timeseries| timeseries.in_flight(i));
+            let warmup = warmup.clone();
+            let warmup_barrier = warmup_barrier.clone();
+            let measure_build_latency = self.config.measure_build_latency;
+            let burst = self.config.burst;
+            let bursts = bursts.clone();
+            let inflight_permille = inflight_permille.clone();
+            let drain_in_flight = self.config.drain_in_flight;
+            let ramp_up = self.config.ramp_up;
+            let workload_name = worker.workload_type.to_string();
 
             // Make a per worker proxy, otherwise they all share the same task.
             // For remote proxy, this call is a no-op
@@ -299,36 +982,92 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 let start_time = print_and_start_benchmark().await;
                 let mut num_success = 0;
                 let mut num_error = 0;
+                let mut num_timeout = 0;
                 let mut num_no_gas = 0;
                 let mut num_in_flight: u64 = 0;
                 let mut num_submitted = 0;
                 let mut latency_histogram =
                     hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap();
+                // By the workload of each payload, so the workloads of a combination
+                // are told apart
+                let mut outcome_latency: BTreeMap<String, OutcomeLatency> = BTreeMap::new();
+                let mut build_latency: Option<BTreeMap<String, BuildLatency>> =
+                    measure_build_latency.then(BTreeMap::new);
                 let mut request_interval =
                     time::interval(Duration::from_micros(request_delay_micros));
-                request_interval.set_missed_tick_behavior(time::MissedTickBehavior::Burst);
+                request_interval.set_missed_tick_behavior(if closed_loop {
+                    time::MissedTickBehavior::Delay
+                } else {
+                    time::MissedTickBehavior::Burst
+                });
+                // Set by a closed-loop worker when a response freed a slot of its window
+                let mut refill = false;
                 let mut stat_interval = time::interval(Duration::from_micros(stat_delay_micros));
                 let mut futures: FuturesUnordered<BoxFuture<NextOp>> = FuturesUnordered::new();
 
                 let mut retry_queue: VecDeque<RetryType> = VecDeque::new();
                 let mut stat_start_time: Instant = Instant::now();
+                let mut stat_start_epoch = *epoch_rx.borrow_and_update();
+                // Staggered workloads sit idle until their offset, then leave
+                // their own ramp window out of the latency histograms
+                let mut paused_until = Instant::now() + start_offset;
+                let ramp_end = paused_until + workload_stagger;
+                // Each workload ramps up from its own start
+                let ramp_up_start = paused_until;
+                let mut ramp_pacer = RampPacer::default();
+                let window_end = sequential_window.map(|window| paused_until + window);
+                // Timed out from the first submission, so gas setup is not counted
+                let run_end = match run_duration {
+                    Interval::Time(duration) if drain_in_flight && duration != Duration::MAX => {
+                        Some(*start_time + duration)
+                    }
+                    _ => None,
+                };
+                // Time the in-flight window was full, i.e. no payload was free to issue
+                let mut inflight_full = Duration::ZERO;
+                let mut inflight_full_since: Option<Instant> = None;
+                let mut backoff_until = Instant::now();
+                // Whether the warmup is over for this worker and the barrier open
+                let mut measuring = warmup_barrier.is_none();
+                let mut drained = false;
+                // Timed out transactions with the time they are resent at
+                let mut quarantine: VecDeque<(Instant, RetryType)> = VecDeque::new();
                 loop {
+                    if let Some(timeseries_in_flight) = &timeseries_in_flight {
+                        timeseries_in_flight.store(num_in_flight, Ordering::Relaxed);
+                    }
                     tokio::select! {
                         _ = cloned_token.cancelled() => {
+                            if draining.is_cancelled() {
+                                abandoned.fetch_add(num_in_flight, Ordering::Relaxed);
+                            }
                             break;
                         }
+                        Ok(()) = epoch_rx.changed() => {
+                            // Give reconfiguration a moment to settle before building
+                            // transactions against the new epoch
+                            paused_until = Instant::now() + epoch_change_pause;
+                        }
                         _ = stat_interval.tick() => {
                             if tx_cloned
                                 .try_send(Stats {
                                     id: i as usize,
+                                    workload: workload_name.clone(),
                                     num_no_gas,
                                     num_in_flight,
                                     num_submitted,
+                                    epochs: (stat_start_epoch, *epoch_rx.borrow()),
+                                    measured: measuring,
                                     bench_stats: BenchmarkStats {
                                         duration: stat_start_time.elapsed(),
                                         num_error,
                                         num_success,
+                                        num_timeout,
+                                        inflight_full,
                                         latency_ms: HistogramWrapper {histogram: latency_histogram.clone()},
+                                        outcome_latency_ms: outcome_latency.clone(),
+                                        build_latency: build_latency.clone().unwrap_or_default(),
+                                        ..Default::default()
                                     },
                                 })
                                 .is_err()
@@ -337,117 +1076,374 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                             }
                             num_success = 0;
                             num_error = 0;
+                            num_timeout = 0;
                             num_no_gas = 0;
                             num_submitted = 0;
                             stat_start_time = Instant::now();
+                            stat_start_epoch = *epoch_rx.borrow();
+                            inflight_full = Duration::ZERO;
                             latency_histogram.reset();
+                            outcome_latency.values_mut().for_each(OutcomeLatency::reset);
+                            if let Some(build_latency) = build_latency.as_mut() {
+                                build_latency.values_mut().for_each(BuildLatency::reset);
+                            }
                         }
-                        _ = request_interval.tick() => {
-
-                            // If a retry is available send that
-                            // (sending retries here subjects them to our rate limit)
-                            if let Some(b) = retry_queue.pop_front() {
-                                num_error += 1;
-                                num_submitted += 1;
-                                metrics_cloned.num_submitted.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
-                                let metrics_cloned = metrics_cloned.clone();
-                                // TODO: clone committee for each request is not ideal.
-                                let committee_cloned = Arc::new(proxy.clone_committee());
-                                let start = Arc::new(Instant::now());
-                                let res = proxy
-                                    .execute_transaction(b.0.clone().into())
-                                    .then(|res| async move  {
+                        _ = time::sleep_until(backoff_until), if Instant::now() < backoff_until => {}
+                        _ = next_request(&mut request_interval, refill), if Instant::now() >= backoff_until => {
+                            refill = false;
+                            if draining.is_cancelled() {
+                                // Interrupted, issue nothing new and stop once the
+                                // transactions in flight complete
+                                if futures.is_empty() {
+                                    break;
+                                }
+                                continue
+                            }
+                            if let Some(warmup_barrier) = warmup_barrier.as_ref().filter(|_| !measuring) {
+                                if warmup_barrier.opened_at().is_some() {
+                                    // Transactions completed while draining belong to the warmup
+                                    measuring = true;
+                                    num_success = 0;
+                                    num_error = 0;
+                                    num_timeout = 0;
+                                    num_no_gas = 0;
+                                    num_submitted = 0;
+                                    stat_start_time = Instant::now();
+                                    stat_start_epoch = *epoch_rx.borrow();
+                                    inflight_full = Duration::ZERO;
+                                    latency_histogram.reset();
+                                    outcome_latency.values_mut().for_each(OutcomeLatency::reset);
+                                    if let Some(build_latency) = build_latency.as_mut() {
+                                        build_latency.values_mut().for_each(BuildLatency::reset);
+                                    }
+                                } else if start_time.elapsed() >= warmup_barrier.duration {
+                                    // Issue nothing until every worker drained its warmup
+                                    if !drained && futures.is_empty() {
+                                        drained = true;
+                                        warmup_barrier.drained();
+                                    }
+                                    continue
+                                }
+                            }
+                            if run_end.map_or(false, |run_end| Instant::now() >= run_end) {
+                                // Issue nothing new, the run ends once the transactions
+                                // in flight complete
+                                if futures.is_empty() {
+                                    BenchDriver::update_progress(*start_time, run_duration, progress_cloned.clone());
+                                    break;
+                                }
+                                continue
+                            }
+                            if Instant::now() < paused_until {
+                                continue
+                            }
+                            if burst.map_or(false, |burst| !burst.is_on(start_time.elapsed())) {
+                                continue
+                            }
+                            if window_end.map_or(false, |window_end| Instant::now() >= window_end) {
+                                // Leave the rest of the run to the next workload once the
+                                // transactions in flight complete
+                                if futures.is_empty() {
+                                    break;
+                                }
+                                continue
+                            }
+                            let ramp_permille = ramp_up.map_or(FULL_WINDOW, |ramp_up| {
+                                ramp_up.permille(Instant::now().saturating_duration_since(ramp_up_start))
+                            });
+                            if !ramp_pacer.tick(ramp_permille) {
+                                continue
+                            }
+
+                            // Closed-loop workers fill every free slot of their window and
+                            // resend their retries at once, open-loop ones issue one
+                            // transaction per tick
+                            let window = inflight_window(num_payloads, inflight_permille.load(Ordering::Relaxed) * ramp_permille / FULL_WINDOW);
+                            // Quarantined transactions take a slot of the window back to be resent
+                            while num_in_flight < window && quarantine.front().map_or(false, |(until, _)| Instant::now() >= *until) {
+                                let (_, b) = quarantine.pop_front().unwrap();
+                                num_in_flight += 1;
+                                metrics_cloned.num_in_flight.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                retry_queue.push_back(b);
+                            }
+                            let submissions = if closed_loop {
+                                retry_queue.len() as u64 + window.saturating_sub(num_in_flight).min(free_pool.len() as u64)
+                            } else {
+                                1
+                            };
+                            for _ in 0..submissions {
+                                // If a retry is available send that
+                                // (sending retries here subjects them to our rate limit)
+                                if let Some(b) = retry_queue.pop_front() {
+                                    num_submitted += 1;
+                                    metrics_cloned.num_submitted.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                    let metrics_cloned = metrics_cloned.clone();
+                                    let flagged_objects = flagged_objects.clone();
+                                    let gas_usage = gas_usage.clone();
+                                    let slowest = slowest.clone();
+                                    let gas_spent = gas_spent.clone();
+                                    let effects_signers = effects_signers.clone();
+                                    let spend_budget = spend_budget.clone();
+                                    let spend_token = cloned_token.clone();
+                                    let postcondition_failures = postcondition_failures.clone();
+                                    let postcondition_proxy = check_postconditions.then(|| proxy.clone());
+                                    let ordering = ordering.clone();
+                                    // TODO: clone committee for each request is not ideal.
+                                    let committee_cloned = Arc::new(proxy.clone_committee());
+                                    let start = Arc::new(Instant::now());
+                                    let res = with_deadline(proxy.execute_transaction(b.0.clone().into()), tx_deadline)
+                                        .then(|res| async move  {
+                                            match res {
+                                                Some(Ok((cert, effects))) => {
+                                                    let new_version = effects.mutated().iter().find(|(object_ref, _)| {
+                                                        object_ref.0 == b.1.get_object_id()
+                                                    }).map(|x| x.0).unwrap();
+                                                    let latency = start.elapsed();
+                                                    metrics_cloned.latency_s.with_label_values(&[&b.1.get_workload_type().to_string()]).observe(latency.as_secs_f64());
+                                                    metrics_cloned.num_success.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                                    metrics_cloned.computation_cost.with_label_values(&[&b.1.get_workload_type().to_string()]).observe(effects.gas_used().computation_cost as f64);
+                                                    metrics_cloned.num_in_flight.with_label_values(&[&b.1.get_workload_type().to_string()]).dec();
+                                                    let auth_sign_info = AuthorityStrongQuorumSignInfo::try_from(&cert.auth_sign_info).unwrap();
+                                                    auth_sign_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_tx_cert.with_label_values(&[&name.unwrap().to_string()]).inc());
+                                                    record_effects_signers(&effects, &committee_cloned, &metrics_cloned, &effects_signers);
+                                                    if verify_effects_signatures {
+                                                        if let Err(err) = effects.verify_signatures(&committee_cloned) {
+                                                            error!("Invalid effects signatures for tx {:?}: {}", b.0.digest(), err);
+                                                            metrics_cloned.num_invalid_effects_signatures.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                                        }
+                                                    }
+                                                    for (id, input_version, output_version) in version_violations(&b.0.data().intent_message.value, &effects.mutated()) {
+                                                        error!("Object {} mutated from version {} to {}, expected a lamport increment of the inputs", id, input_version, output_version);
+                                                        metrics_cloned.num_version_violations.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                                    }
+                                                    flag_objects_over_version(&effects, max_object_version, &flagged_objects, &metrics_cloned, b.1.get_workload_type());
+                                                    record_gas_usage(&b.0.data().intent_message.value, &effects, &gas_usage, b.1.get_workload_type());
+                                                    record_gas_spent(&effects, &gas_spent);
+                                                    record_spend(&effects, &spend_budget, &spend_token);
+                                                    record_slow_transaction(&b.0, b.2.elapsed(), latency, &slowest, b.1.get_workload_type());
+                                                    record_ordering(&b.0, &effects, b.2, &ordering, b.1.get_workload_type());
+                                                    check_postcondition(&*b.1, &effects, &postcondition_proxy, &metrics_cloned, &postcondition_failures).await;
+                                                    let workload_type = b.1.get_workload_type();
+                                                    NextOp::Response(Some((
+                                                        latency,
+                                                        Some((b.2.elapsed(), b.4 + 1)),
+                                                        Some(b.3),
+                                                        b.1.make_new_payload(new_version, effects.gas_object().0, &effects),
+                                                        workload_type,
+                                                    ),
+                                                    ))
+                                                }
+                                                Some(Err(err)) => {
+                                                    error!("{}", err);
+                                                    metrics_cloned.num_error.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                                    NextOp::Retry(b, start.elapsed())
+                                                }
+                                                None => {
+                                                    metrics_cloned.num_timeout.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
+                                                    NextOp::Timeout(b, start.elapsed())
+                                                }
+                                            }
+                                        });
+                                    futures.push(Box::pin(res));
+                                    continue
+                                }
+
+                                // Otherwise send a fresh request
+                                if free_pool.is_empty() || num_in_flight >= window {
+                                    num_no_gas += 1;
+                                    inflight_full_since.get_or_insert_with(Instant::now);
+                                    // Stop waking up for every tick until a payload frees up
+                                    if let Some(backoff) = inflight_full_backoff {
+                                        backoff_until = Instant::now() + backoff;
+                                    }
+                                } else {
+                                    if let Some(since) = inflight_full_since.take() {
+                                        inflight_full += since.elapsed();
+                                    }
+                                    let payload = free_pool.pop().unwrap();
+                                    num_in_flight += 1;
+                                    num_submitted += 1;
+                                    metrics_cloned.num_in_flight.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
+                                    metrics_cloned.num_submitted.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
+                                    if let Some(query) = payload.make_query() {
+                                        let start = Instant::now();
+                                        let metrics_cloned = metrics_cloned.clone();
+                                        let res = proxy
+                                            .query_object(query)
+                                            .then(|res| async move {
+                                                let workload_type = payload.get_workload_type().to_string();
+                                                metrics_cloned.num_in_flight.with_label_values(&[&workload_type]).dec();
+                                                match res {
+                                                    Ok(()) => {
+                                                        let latency = start.elapsed();
+                                                        metrics_cloned.latency_s.with_label_values(&[&workload_type]).observe(latency.as_secs_f64());
+                                                        metrics_cloned.num_success.with_label_values(&[&workload_type]).inc();
+                                                        let workload_type = payload.get_workload_type();
+                                                        NextOp::Response(Some((latency, None, None, payload.make_new_query_payload(), workload_type)))
+                                                    }
+                                                    Err(err) => {
+                                                        error!("Query failed with error: {}", err);
+                                                        metrics_cloned.num_error.with_label_values(&[&workload_type]).inc();
+                                                        NextOp::QueryFailure(payload.make_new_query_payload())
+                                                    }
+                                                }
+                                            });
+                                        futures.push(Box::pin(res));
+                                        continue
+                                    }
+                                    let build_start = Instant::now();
+                                    let tx = payload.make_transaction();
+                                    let build = build_start.elapsed();
+                                    if let Some(duplicate) = duplicates.as_ref().and_then(|duplicates| duplicates.lock().unwrap().record(*tx.digest(), &workload_name)) {
+                                        error!("Transaction {} was already built by a {} payload, gas coins or objects are shared between payloads", duplicate.digest, duplicate.first_workload);
+                                        metrics_cloned.num_duplicate.with_label_values(&[&workload_name]).inc();
+                                    }
+                                    if let Some(build_latency) = build_latency.as_mut().filter(|_| Instant::now() >= ramp_end) {
+                                        build_latency.entry(payload.get_workload_type().to_string()).or_default().record_build(build);
+                                    }
+                                    let start = Arc::new(Instant::now());
+                                    let metrics_cloned = metrics_cloned.clone();
+                                    let flagged_objects = flagged_objects.clone();
+                                    let gas_usage = gas_usage.clone();
+                                    let slowest = slowest.clone();
+                                    let gas_spent = gas_spent.clone();
+                                    let effects_signers = effects_signers.clone();
+                                    let spend_budget = spend_budget.clone();
+                                    let spend_token = cloned_token.clone();
+                                    let postcondition_failures = postcondition_failures.clone();
+                                    let postcondition_proxy = check_postconditions.then(|| proxy.clone());
+                                    let ordering = ordering.clone();
+                                    // TODO: clone committee for each request is not ideal.
+                                    let committee_cloned = Arc::new(proxy.clone_committee());
+                                    let res = with_deadline(proxy.execute_transaction(tx.clone().into()), tx_deadline)
+                                    .then(|res| async move {
                                         match res {
-                                            Ok((cert, effects)) => {
+                                            Some(Ok((cert, effects))) => {
                                                 let new_version = effects.mutated().iter().find(|(object_ref, _)| {
-                                                    object_ref.0 == b.1.get_object_id()
+                                                    object_ref.0 == payload.get_object_id()
                                                 }).map(|x| x.0).unwrap();
                                                 let latency = start.elapsed();
-                                                metrics_cloned.latency_s.with_label_values(&[&b.1.get_workload_type().to_string()]).observe(latency.as_secs_f64());
-                                                metrics_cloned.num_success.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
-                                                metrics_cloned.num_in_flight.with_label_values(&[&b.1.get_workload_type().to_string()]).dec();
+                                                metrics_cloned.latency_s.with_label_values(&[&payload.get_workload_type().to_string()]).observe(latency.as_secs_f64());
+                                                metrics_cloned.num_success.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
+                                                metrics_cloned.computation_cost.with_label_values(&[&payload.get_workload_type().to_string()]).observe(effects.gas_used().computation_cost as f64);
+                                                metrics_cloned.num_in_flight.with_label_values(&[&payload.get_workload_type().to_string()]).dec();
                                                 let auth_sign_info = AuthorityStrongQuorumSignInfo::try_from(&cert.auth_sign_info).unwrap();
                                                 auth_sign_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_tx_cert.with_label_values(&[&name.unwrap().to_string()]).inc());
-                                                if let Some(sig_info) = effects.quorum_sig() {
-                                                    sig_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_effects_cert.with_label_values(&[&name.unwrap().to_string()]).inc())
+                                                record_effects_signers(&effects, &committee_cloned, &metrics_cloned, &effects_signers);
+                                                if verify_effects_signatures {
+                                                    if let Err(err) = effects.verify_signatures(&committee_cloned) {
+                                                        error!("Invalid effects signatures for tx {:?}: {}", tx.digest(), err);
+                                                        metrics_cloned.num_invalid_effects_signatures.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
+                                                    }
+                                                }
+                                                for (id, input_version, output_version) in version_violations(&tx.data().intent_message.value, &effects.mutated()) {
+                                                    error!("Object {} mutated from version {} to {}, expected a lamport increment of the inputs", id, input_version, output_version);
+                                                    metrics_cloned.num_version_violations.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
                                                 }
+                                                flag_objects_over_version(&effects, max_object_version, &flagged_objects, &metrics_cloned, payload.get_workload_type());
+                                                record_gas_usage(&tx.data().intent_message.value, &effects, &gas_usage, payload.get_workload_type());
+                                                record_gas_spent(&effects, &gas_spent);
+                                                record_spend(&effects, &spend_budget, &spend_token);
+                                                record_slow_transaction(&tx, latency, latency, &slowest, payload.get_workload_type());
+                                                record_ordering(&tx, &effects, *start, &ordering, payload.get_workload_type());
+                                                check_postcondition(&*payload, &effects, &postcondition_proxy, &metrics_cloned, &postcondition_failures).await;
+                                                let workload_type = payload.get_workload_type();
                                                 NextOp::Response(Some((
                                                     latency,
-                                                    b.1.make_new_payload(new_version, effects.gas_object().0, &effects),
-                                                ),
-                                                ))
+                                                    None,
+                                                    Some(build),
+                                                    payload.make_new_payload(new_version, effects.gas_object().0, &effects),
+                                                    workload_type,
+                                                )))
+                                            }
+                                            Some(Err(err)) => {
+                                                error!("Retry due to error: {}", err);
+                                                metrics_cloned.num_error.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
+                                                NextOp::Retry(Box::new((tx, payload, *start, build, 0)), start.elapsed())
                                             }
-                                            Err(err) => {
-                                                error!("{}", err);
-                                                metrics_cloned.num_error.with_label_values(&[&b.1.get_workload_type().to_string()]).inc();
-                                                NextOp::Retry(b)
+                                            None => {
+                                                metrics_cloned.num_timeout.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
+                                                NextOp::Timeout(Box::new((tx, payload, *start, build, 0)), start.elapsed())
                                             }
                                         }
                                     });
-                                futures.push(Box::pin(res));
-                                continue
-                            }
-
-                            // Otherwise send a fresh request
-                            if free_pool.is_empty() {
-                                num_no_gas += 1;
-                            } else {
-                                let payload = free_pool.pop().unwrap();
-                                num_in_flight += 1;
-                                num_submitted += 1;
-                                metrics_cloned.num_in_flight.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
-                                metrics_cloned.num_submitted.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
-                                let tx = payload.make_transaction();
-                                let start = Arc::new(Instant::now());
-                                let metrics_cloned = metrics_cloned.clone();
-                                // TODO: clone committee for each request is not ideal.
-                                let committee_cloned = Arc::new(proxy.clone_committee());
-                                let res = proxy
-                                    .execute_transaction(tx.clone().into())
-                                .then(|res| async move {
-                                    match res {
-                                        Ok((cert, effects)) => {
-                                            let new_version = effects.mutated().iter().find(|(object_ref, _)| {
-                                                object_ref.0 == payload.get_object_id()
-                                            }).map(|x| x.0).unwrap();
-                                            let latency = start.elapsed();
-                                            metrics_cloned.latency_s.with_label_values(&[&payload.get_workload_type().to_string()]).observe(latency.as_secs_f64());
-                                            metrics_cloned.num_success.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
-                                            metrics_cloned.num_in_flight.with_label_values(&[&payload.get_workload_type().to_string()]).dec();
-                                            let auth_sign_info = AuthorityStrongQuorumSignInfo::try_from(&cert.auth_sign_info).unwrap();
-                                            auth_sign_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_tx_cert.with_label_values(&[&name.unwrap().to_string()]).inc());
-                                            if let Some(sig_info) = effects.quorum_sig() { sig_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_effects_cert.with_label_values(&[&name.unwrap().to_string()]).inc()) }
-                                            NextOp::Response(Some((
-                                                latency,
-                                                payload.make_new_payload(new_version, effects.gas_object().0, &effects),
-                                            )))
-                                        }
-                                        Err(err) => {
-                                            error!("Retry due to error: {}", err);
-                                            metrics_cloned.num_error.with_label_values(&[&payload.get_workload_type().to_string()]).inc();
-                                            NextOp::Retry(Box::new((tx, payload)))
-                                        }
-                                    }
-                                });
-                                futures.push(Box::pin(res));
+                                    futures.push(Box::pin(res));
+                                }
                             }
                         }
                         Some(op) = futures.next() => {
+                            refill = closed_loop;
                             match op {
-                                NextOp::Retry(b) => {
+                                NextOp::Retry(mut b, latency) => {
+                                    num_error += 1;
+                                    outcome_latency.entry(b.1.get_workload_type().to_string()).or_default().failed_ms.record(latency);
+                                    b.4 += 1;
                                     retry_queue.push_back(b);
                                     BenchDriver::update_progress(*start_time, run_duration, progress_cloned.clone());
-                                    if progress_cloned.is_finished() {
+                                    if progress_cloned.is_finished() && run_end.is_none() {
                                         break;
                                     }
                                 }
-                                NextOp::Response(Some((latency, new_payload))) => {
+                                NextOp::Response(Some((latency, retried, build, new_payload, workload_type))) => {
+                                    let retried_latency = retried.map(|(retried_latency, _)| retried_latency);
                                     num_success += 1;
                                     num_in_flight -= 1;
                                     free_pool.push(new_payload);
-                                    latency_histogram.saturating_record(latency.as_millis().try_into().unwrap());
+                                    backoff_until = Instant::now();
+                                    if let Some(bursts) = &bursts {
+                                        bursts.lock().unwrap().record(start_time.elapsed(), retried_latency.unwrap_or(latency));
+                                    }
+                                    if let Some(timeseries) = &timeseries {
+                                        timeseries.record(&workload_name, retried_latency.unwrap_or(latency));
+                                    }
+                                    if let Some(warmup) = &warmup {
+                                        warmup.lock().unwrap().record(start_time.elapsed(), retried_latency.unwrap_or(latency));
+                                    }
+                                    if Instant::now() >= ramp_end {
+                                        latency_histogram.saturating_record(latency.as_millis().try_into().unwrap());
+                                        let outcome_latency = outcome_latency.entry(workload_type.to_string()).or_default();
+                                        match retried {
+                                            Some((retried_latency, attempts)) => {
+                                                outcome_latency.retried_ms.record(retried_latency);
+                                                outcome_latency.retried_attempts += attempts;
+                                            }
+                                            None => outcome_latency.success_ms.record(latency),
+                                        }
+                                        if let (Some(build_latency), Some(build)) = (build_latency.as_mut(), build) {
+                                            build_latency.entry(workload_type.to_string()).or_default().end_to_end_ms.record(build + retried_latency.unwrap_or(latency));
+                                        }
+                                    }
                                     BenchDriver::update_progress(*start_time, run_duration, progress_cloned.clone());
-                                    if progress_cloned.is_finished() {
+                                    if progress_cloned.is_finished() && run_end.is_none() {
+                                        break;
+                                    }
+                                }
+                                NextOp::Timeout(mut b, latency) => {
+                                    // The timed out request was dropped, so late effects are never
+                                    // observed. Its slot is released while the transaction sits in
+                                    // quarantine for another deadline, then the same transaction is
+                                    // resent: it returns the effects if it executed meanwhile, and
+                                    // a new transaction could not use the objects it may have locked
+                                    let workload_type = b.1.get_workload_type().to_string();
+                                    num_timeout += 1;
+                                    num_in_flight -= 1;
+                                    metrics_cloned.num_in_flight.with_label_values(&[&workload_type]).dec();
+                                    outcome_latency.entry(workload_type).or_default().failed_ms.record(latency);
+                                    b.4 += 1;
+                                    quarantine.push_back((Instant::now() + tx_deadline.unwrap_or_default(), b));
+                                    backoff_until = Instant::now();
+                                    BenchDriver::update_progress(*start_time, run_duration, progress_cloned.clone());
+                                    if progress_cloned.is_finished() && run_end.is_none() {
+                                        break;
+                                    }
+                                }
+                                NextOp::QueryFailure(payload) => {
+                                    num_error += 1;
+                                    num_in_flight -= 1;
+                                    free_pool.push(payload);
+                                    backoff_until = Instant::now();
+                                    BenchDriver::update_progress(*start_time, run_duration, progress_cloned.clone());
+                                    if progress_cloned.is_finished() && run_end.is_none() {
                                         break;
                                     }
                                 }
@@ -463,16 +1459,24 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 if tx_cloned
                     .try_send(Stats {
                         id: i as usize,
+                        workload: workload_name.clone(),
                         num_no_gas,
                         num_in_flight,
                         num_submitted,
+                        epochs: (stat_start_epoch, *epoch_rx.borrow()),
+                        measured: measuring,
                         bench_stats: BenchmarkStats {
                             duration: stat_start_time.elapsed(),
                             num_error,
                             num_success,
+                            num_timeout,
+                            inflight_full,
                             latency_ms: HistogramWrapper {
                                 histogram: latency_histogram,
                             },
+                            outcome_latency_ms: outcome_latency,
+                            build_latency: build_latency.unwrap_or_default(),
+                            ..Default::default()
                         },
                     })
                     .is_err()
@@ -483,48 +1487,120 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
             tasks.push(runner);
         }
 
+        let committee = system_state_observer.committee.borrow().clone();
+        let mut influx_output = self
+            .config
+            .influx_output
+            .as_deref()
+            .map(InfluxOutput::new)
+            .transpose()?;
+        let labels = self.config.labels.clone();
+        let mut tuner = self.config.target_p99_ms.map(InflightTuner::new);
+        let mut progress_file = self.config.progress_file.clone();
+        let health_proxy = proxy.clone();
+        let stat_metrics = metrics.clone();
+        let reference_gas_price = system_state_observer.reference_gas_price.clone();
+        let stat_warmup_barrier = warmup_barrier.clone();
         let benchmark_stat_task = tokio::spawn(async move {
             let mut benchmark_stat = BenchmarkStats {
-                duration: Duration::ZERO,
-                num_error: 0,
-                num_success: 0,
-                latency_ms: HistogramWrapper {
-                    histogram: hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap(),
-                },
+                committee: Some(committee),
+                workload_windows,
+                ..Default::default()
             };
             let mut stat_collection: BTreeMap<usize, Stats> = BTreeMap::new();
             let mut counter = 0;
             let mut stat;
             let start = Instant::now();
+            let resumed_elapsed = progress_file
+                .as_ref()
+                .map_or(Duration::ZERO, |progress_file| {
+                    progress_file.progress.elapsed
+                });
+            // Start of the measurement phase, after the in-flight window converged
+            let mut measure_start = start;
             while let Some(
                 sample_stat @ Stats {
                     id,
+                    workload: _,
                     num_no_gas: _,
                     num_in_flight: _,
                     num_submitted: _,
+                    epochs: (start_epoch, epoch),
+                    measured,
                     bench_stats: _,
                 },
             ) = rx.recv().await
             {
-                benchmark_stat.update(start.elapsed(), &sample_stat.bench_stats);
+                if start_epoch != epoch {
+                    let to = start.elapsed();
+                    benchmark_stat.record_epoch_spanning_window(
+                        to.saturating_sub(sample_stat.bench_stats.duration),
+                        to,
+                        (start_epoch, epoch),
+                    );
+                }
+                if let Some(opened_at) = stat_warmup_barrier
+                    .as_ref()
+                    .filter(|_| measured && benchmark_stat.measured_from.is_none())
+                    .and_then(|warmup_barrier| warmup_barrier.opened_at())
+                {
+                    measure_start = opened_at;
+                    benchmark_stat.measured_from = Some(opened_at.saturating_duration_since(start));
+                    if show_progress {
+                        eprintln!(
+                            "Warmup drained, measuring from {:?} into the run",
+                            opened_at.saturating_duration_since(start)
+                        );
+                    }
+                }
+                if measured {
+                    benchmark_stat.update(measure_start.elapsed(), &sample_stat.bench_stats);
+                }
+                if let Some(progress_file) = progress_file.as_mut() {
+                    progress_file.progress.record(
+                        &sample_stat.workload,
+                        sample_stat.bench_stats.num_success,
+                        sample_stat.bench_stats.num_error,
+                        sample_stat.bench_stats.num_timeout,
+                    );
+                }
+                if sample_stat.epochs.0 != epoch
+                    && !benchmark_stat
+                        .epoch_changes
+                        .iter()
+                        .any(|(_, e)| *e == epoch)
+                {
+                    benchmark_stat.epoch_changes.push((start.elapsed(), epoch));
+                    if show_progress {
+                        eprintln!("Epoch changed to {} after {:?}", epoch, start.elapsed());
+                    }
+                }
                 stat_collection.insert(id, sample_stat);
                 let mut total_qps: f32 = 0.0;
                 let mut num_success: u64 = 0;
                 let mut num_error: u64 = 0;
+                let mut num_timeout: u64 = 0;
+                let mut inflight_full = Duration::ZERO;
                 let mut latency_histogram =
                     hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap();
 
                 let mut num_in_flight: u64 = 0;
                 let mut num_submitted: u64 = 0;
                 let mut num_no_gas = 0;
+                let mut spanned_epochs = None;
                 for (_, v) in stat_collection.iter() {
                     total_qps +=
                         v.bench_stats.num_success as f32 / v.bench_stats.duration.as_secs() as f32;
                     num_success += v.bench_stats.num_success;
                     num_error += v.bench_stats.num_error;
+                    num_timeout += v.bench_stats.num_timeout;
+                    inflight_full += v.bench_stats.inflight_full;
                     num_no_gas += v.num_no_gas;
                     num_submitted += v.num_submitted;
                     num_in_flight += v.num_in_flight;
+                    if v.epochs.0 != v.epochs.1 {
+                        spanned_epochs = Some(v.epochs);
+                    }
                     latency_histogram
                         .add(&v.bench_stats.latency_ms.histogram)
                         .unwrap();
@@ -537,17 +1613,92 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 };
                 counter += 1;
                 if counter % num_workers == 0 {
-                    stat = format!("Throughput = {}, latency_ms(min/p50/p99/max) = {}/{}/{}/{}, num_success = {}, num_error = {}, no_gas = {}, submitted = {}, in_flight = {}", total_qps, latency_histogram.min(), latency_histogram.value_at_quantile(0.5), latency_histogram.value_at_quantile(0.99), latency_histogram.max(), num_success, num_error, num_no_gas, num_submitted, num_in_flight);
+                    stat = format!("Throughput = {}, latency_ms(min/p50/p99/max) = {}/{}/{}/{}, num_success = {}, num_error = {}, num_timeout = {}, no_gas = {}, inflight_full = {:?}, submitted = {}, in_flight = {}", total_qps, latency_histogram.min(), latency_histogram.value_at_quantile(0.5), latency_histogram.value_at_quantile(0.99), latency_histogram.max(), num_success, num_error, num_timeout, num_no_gas, inflight_full, num_submitted, num_in_flight);
+                    if let Some((from_epoch, to_epoch)) = spanned_epochs {
+                        stat.push_str(&format!(", spans epochs {} to {}", from_epoch, to_epoch));
+                    }
                     if show_progress {
                         eprintln!("{}", stat);
                     }
+                    if let Some(progress_file) = progress_file.as_mut() {
+                        progress_file.progress.elapsed = resumed_elapsed + start.elapsed();
+                        if let Err(err) = progress_file.progress.save(&progress_file.path) {
+                            error!("Failed to save benchmark progress: {}", err);
+                        }
+                    }
+                    let exclusions = health_proxy.excluded_connections();
+                    for exclusion in &exclusions[benchmark_stat.excluded_connections.len()..] {
+                        let excluded = ExcludedConnection::new(exclusion, start);
+                        if show_progress {
+                            eprintln!(
+                                "Connection {} excluded after {:?}, its share goes to the healthy ones: {}",
+                                excluded.connection, excluded.elapsed, excluded.error
+                            );
+                        }
+                        benchmark_stat.excluded_connections.push(excluded);
+                    }
+                    let measurements = interval_measurements(stat_collection.values());
+                    for measurement in &measurements {
+                        stat_metrics
+                            .qps
+                            .with_label_values(&[&measurement.workload])
+                            .set(measurement.qps as f64);
+                    }
+                    stat_metrics
+                        .reference_gas_price
+                        .set(*reference_gas_price.borrow() as i64);
+                    if let Some(influx_output) = influx_output.as_mut() {
+                        if let Err(err) = influx_output.write(&measurements, &labels).await {
+                            error!("Failed to write influx measurements: {}", err);
+                        }
+                    }
+                    if let Some(tuner) = tuner.as_mut().filter(|_| !latency_histogram.is_empty()) {
+                        if let Some(permille) =
+                            tuner.observe(latency_histogram.value_at_quantile(0.99))
+                        {
+                            inflight_permille.store(permille, Ordering::Relaxed);
+                            let window: u64 = worker_payloads
+                                .iter()
+                                .map(|num_payloads| inflight_window(*num_payloads, permille))
+                                .sum();
+                            if tuner.is_converged() {
+                                info!("In-flight window converged to {} transactions", window);
+                                if show_progress {
+                                    eprintln!(
+                                        "In-flight window converged to {} transactions",
+                                        window
+                                    );
+                                }
+                                // Only the converged window is measured
+                                benchmark_stat.reset_measurements();
+                                benchmark_stat.tuned_in_flight = Some(window);
+                                measure_start = Instant::now();
+                            } else {
+                                debug!("Trying an in-flight window of {} transactions", window);
+                            }
+                        }
+                    }
                 }
             }
+            if let Some(progress_file) = progress_file.as_mut() {
+                progress_file.progress.elapsed = resumed_elapsed + start.elapsed();
+                if let Err(err) = progress_file.progress.save(&progress_file.path) {
+                    error!("Failed to save benchmark progress: {}", err);
+                }
+                benchmark_stat.progress = Some(progress_file.progress.clone());
+            }
+            benchmark_stat.excluded_connections = health_proxy
+                .excluded_connections()
+                .iter()
+                .map(|exclusion| ExcludedConnection::new(exclusion, start))
+                .collect();
+            benchmark_stat.clock_skew = health_proxy.clock_skew();
+            benchmark_stat.proxy_retries = health_proxy.retry_stats();
             benchmark_stat
         });
         drop(tx);
 
-        if self.stress_stat_collection {
+        if self.config.stress_stat_collection {
             tasks.push(stress_stats_collector(
                 progress.clone(),
                 metrics.clone(),
@@ -561,25 +1712,43 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 cpu_usage: HistogramWrapper {
                     histogram: hdrhistogram::Histogram::<u64>::new_with_max(100, 3).unwrap(),
                 },
+                client_cpu_usage: HistogramWrapper {
+                    histogram: hdrhistogram::Histogram::<u64>::new_with_max(100, 3).unwrap(),
+                },
             };
             let mut stat_collection: Vec<StressStats> = Vec::new();
             let mut counter = 0;
-            while let Some(sample_stat @ StressStats { cpu_usage: _ }) = stress_stat_rx.recv().await
-            {
+            let mut warned_client_saturated = false;
+            while let Some(sample_stat) = stress_stat_rx.recv().await {
                 stress_stat.update(&sample_stat);
+                if !warned_client_saturated && sample_stat.client_cpu_saturated() {
+                    warn!(
+                        "Benchmark client is using {}% of all cores, the measured ceiling \
+                        may be the client's rather than the network's",
+                        sample_stat.client_cpu_usage.histogram.max()
+                    );
+                    warned_client_saturated = true;
+                }
                 stat_collection.push(sample_stat);
 
                 let mut cpu_usage_histogram =
                     hdrhistogram::Histogram::<u64>::new_with_max(100, 3).unwrap();
+                let mut client_cpu_usage_histogram =
+                    hdrhistogram::Histogram::<u64>::new_with_max(100, 3).unwrap();
                 for stat in stat_collection.iter() {
                     cpu_usage_histogram.add(&stat.cpu_usage.histogram).unwrap();
+                    client_cpu_usage_histogram
+                        .add(&stat.client_cpu_usage.histogram)
+                        .unwrap();
                 }
                 counter += 1;
                 if counter % num_workers == 0 {
                     let stat = format!(
-                        "cpu_usage p50 = {}, p99 = {}",
+                        "cpu_usage p50 = {}, p99 = {}, client_cpu_usage p50 = {}, p99 = {}",
                         cpu_usage_histogram.value_at_quantile(0.5),
-                        cpu_usage_histogram.value_at_quantile(0.99)
+                        cpu_usage_histogram.value_at_quantile(0.99),
+                        client_cpu_usage_histogram.value_at_quantile(0.5),
+                        client_cpu_usage_histogram.value_at_quantile(0.99),
                     );
                     if show_progress {
                         eprintln!("{}", stat);
@@ -589,20 +1758,174 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
             stress_stat
         });
 
+        let timeseries_task = match (&self.config.timeseries_csv, timeseries) {
+            (Some(path), Some(timeseries)) => Some(spawn_timeseries(path, timeseries)?),
+            _ => None,
+        };
+        let gas_balance = Arc::new(Mutex::new(vec![]));
+        let gas_monitor = self.config.gas_monitor_interval.map(|monitor_interval| {
+            let gas_spent = gas_spent.clone();
+            let gas_balance = gas_balance.clone();
+            let proxy = proxy.clone();
+            let run_end = match run_duration {
+                Interval::Time(duration) if duration != Duration::MAX => Some(duration),
+                _ => None,
+            };
+            tokio::spawn(async move {
+                let start = Instant::now();
+                let mut interval = time::interval(monitor_interval);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    let most_spent = gas_spent
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .max_by_key(|(_, spent)| **spent)
+                        .map(|(coin, spent)| (*coin, *spent));
+                    if let Some((coin, spent)) = most_spent {
+                        let balance = match proxy
+                            .get_object(coin)
+                            .await
+                            .and_then(|object| Ok(GasCoin::try_from(&object)?.value()))
+                        {
+                            Ok(balance) => balance,
+                            Err(err) => {
+                                error!("Failed to get the balance of gas coin {}: {}", coin, err);
+                                continue;
+                            }
+                        };
+                        let elapsed = start.elapsed();
+                        let depletion = projected_depletion(balance, spent, elapsed);
+                        // Runs without a set duration are warned an hour ahead
+                        let remaining = run_end.map_or(GAS_DEPLETION_HORIZON, |run_end| {
+                            run_end.saturating_sub(elapsed)
+                        });
+                        if let Some(depletion) = depletion.filter(|d| *d < remaining) {
+                            warn!(
+                                "Gas coin {} has {} left and runs out in {:?} \
+                                at its current spending",
+                                coin, balance, depletion
+                            );
+                        }
+                        gas_balance.lock().unwrap().push(GasBalanceSample {
+                            elapsed,
+                            coin,
+                            balance,
+                            spent,
+                            depletion,
+                        });
+                    }
+                }
+            })
+        });
+
         let all_tasks = try_join_all(tasks);
-        let _res = tokio::select! {
+        tokio::pin!(all_tasks);
+        tokio::select! {
             _ = ctrl_c() => {
-                self.terminate();
-                vec![]
+                eprintln!(
+                    "Interrupted, waiting up to {:.1} seconds for the transactions in flight, Ctrl-C again to exit now",
+                    self.config.drain_timeout.as_secs_f64()
+                );
+                draining.cancel();
+                tokio::select! {
+                    _ = ctrl_c() => {
+                        std::process::exit(130);
+                    }
+                    res = time::timeout(self.config.drain_timeout, &mut all_tasks) => {
+                        if res.is_err() {
+                            self.terminate();
+                            all_tasks.await.unwrap();
+                            eprintln!(
+                                "Drain timed out with {} transactions still in flight",
+                                abandoned.load(Ordering::Relaxed)
+                            );
+                        }
+                    }
+                }
+            }
+            res = &mut all_tasks => {
+                res.unwrap();
             }
-            res = all_tasks => res.unwrap().into_iter().collect()
         };
-        let benchmark_stat = benchmark_stat_task.await.unwrap();
+        let mut benchmark_stat = benchmark_stat_task.await.unwrap();
+        benchmark_stat.gas_usage = gas_usage.lock().unwrap().clone();
+        benchmark_stat.slowest = slowest.lock().unwrap().to_sorted_vec();
+        benchmark_stat.warmup = warmup.map(|warmup| warmup.lock().unwrap().clone());
+        if let Some(gas_monitor) = gas_monitor {
+            gas_monitor.abort();
+        }
+        if let Some(timeseries_task) = timeseries_task {
+            timeseries_task.abort();
+        }
+        benchmark_stat.gas_balance = gas_balance.lock().unwrap().clone();
+        benchmark_stat.effects_signers = effects_signers.lock().unwrap().clone();
+        benchmark_stat.postcondition_failures = postcondition_failures.lock().unwrap().clone();
+        benchmark_stat.bursts = bursts.map(|bursts| bursts.lock().unwrap().clone());
+        benchmark_stat.spend_budget =
+            spend_budget.map(|spend_budget| spend_budget.lock().unwrap().clone());
+        benchmark_stat.duplicate_transactions = duplicates
+            .map(|duplicates| duplicates.lock().unwrap().duplicates())
+            .unwrap_or_default();
+        benchmark_stat.ordering = ordering
+            .map(|ordering| ordering.lock().unwrap().fairness())
+            .unwrap_or_default();
+        let num_flagged_objects = flagged_objects.lock().unwrap().len();
+        if num_flagged_objects > 0 {
+            eprintln!(
+                "{} objects were mutated past --max-object-version",
+                num_flagged_objects
+            );
+        }
+        if self.config.drain_in_flight
+            && matches!(run_duration, Interval::Time(duration) if duration != Duration::MAX)
+        {
+            let started = print_and_start_benchmark().await;
+            let summary = format!(
+                "Completed {} transactions in {:.1} seconds",
+                benchmark_stat.num_success,
+                started.elapsed().as_secs_f64()
+            );
+            info!("{}", summary);
+            eprintln!("{}", summary);
+        }
         let stress_stat = stress_stat_task.await.unwrap();
+        for workload in workloads.iter() {
+            workload.workload.verify_final_state(proxy.clone()).await?;
+        }
         Ok((benchmark_stat, stress_stat))
     }
 }
 
+/// Sums the latest stats of each worker into one measurement per workload
+fn interval_measurements<'a>(stats: impl Iterator<Item = &'a Stats>) -> Vec<IntervalMeasurement> {
+    let mut measurements: BTreeMap<&str, IntervalMeasurement> = BTreeMap::new();
+    for stat in stats {
+        let measurement =
+            measurements
+                .entry(&stat.workload)
+                .or_insert_with(|| IntervalMeasurement {
+                    workload: stat.workload.clone(),
+                    qps: 0.0,
+                    num_success: 0,
+                    num_error: 0,
+                    num_timeout: 0,
+                    latency_ms: hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap(),
+                });
+        let bench_stats = &stat.bench_stats;
+        measurement.qps += bench_stats.num_success as f32 / bench_stats.duration.as_secs_f32();
+        measurement.num_success += bench_stats.num_success;
+        measurement.num_error += bench_stats.num_error;
+        measurement.num_timeout += bench_stats.num_timeout;
+        measurement
+            .latency_ms
+            .add(&bench_stats.latency_ms.histogram)
+            .unwrap();
+    }
+    measurements.into_values().collect()
+}
+
 fn stress_stats_collector(
     progress: Arc<ProgressBar>,
     metrics: Arc<BenchMetrics>,
@@ -610,8 +1933,12 @@ fn stress_stats_collector(
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let mut system = System::new_all();
+        let pid = sysinfo::get_current_pid().ok();
 
         system.refresh_cpu();
+        if let Some(pid) = pid {
+            system.refresh_process(pid);
+        }
         tokio::time::sleep(Duration::from_secs(1)).await;
 
         while !progress.is_finished() {
@@ -625,12 +1952,26 @@ fn stress_stats_collector(
                     .with_label_values(&[&format!("cpu_{i}").to_string()])
                     .set(cpu.cpu_usage().into());
             }
+            // Process usage is summed over cores, scale it to the whole machine
+            let mut client_cpu_usage_histogram =
+                hdrhistogram::Histogram::<u64>::new_with_max(100, 3).unwrap();
+            if let Some(process) = pid
+                .filter(|pid| system.refresh_process(*pid))
+                .and_then(|pid| system.process(pid))
+            {
+                let usage = process.cpu_usage() / system.cpus().len().max(1) as f32;
+                client_cpu_usage_histogram.saturating_record(usage as u64);
+                metrics.client_cpu_usage.set(usage.into());
+            }
 
             if stress_stat_tx
                 .try_send(StressStats {
                     cpu_usage: HistogramWrapper {
                         histogram: cpu_usage_histogram,
                     },
+                    client_cpu_usage: HistogramWrapper {
+                        histogram: client_cpu_usage_histogram,
+                    },
                 })
                 .is_err()
             {
@@ -646,3 +1987,192 @@ fn stress_stats_collector(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delaying_proxy::ProxyDelay;
+    use crate::mock_proxy::MockValidatorProxy;
+    use crate::workloads::send_to_object::SendToObjectWorkload;
+    use crate::workloads::WorkloadPayloadGas;
+    use clap::Parser;
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::crypto::{get_key_pair, AccountKeyPair};
+    use sui_types::object::Object;
+
+    #[test]
+    fn test_version_violations() {
+        let sender = SuiAddress::random_for_testing_only();
+        let object = random_object_ref();
+        let gas = (ObjectID::random(), SequenceNumber::from(7), object.2);
+        let tx_data = TransactionData::new_transfer(
+            SuiAddress::random_for_testing_only(),
+            object,
+            sender,
+            gas,
+            1000,
+            1,
+        );
+        let owner = Owner::AddressOwner(sender);
+        // Both objects take the lamport increment of the highest input version
+        let expected = SequenceNumber::from(8);
+        let mutated = vec![
+            ((object.0, expected, object.2), owner),
+            ((gas.0, expected, gas.2), owner),
+        ];
+        assert!(version_violations(&tx_data, &mutated).is_empty());
+        // A gap in the new version of the transferred object is reported
+        let forked = SequenceNumber::from(9);
+        let mutated = vec![
+            ((object.0, forked, object.2), owner),
+            ((gas.0, expected, gas.2), owner),
+        ];
+        assert_eq!(
+            version_violations(&tx_data, &mutated),
+            vec![(object.0, object.1, forked)]
+        );
+    }
+
+    #[test]
+    fn test_objects_over_version() {
+        let owner = Owner::AddressOwner(SuiAddress::random_for_testing_only());
+        let hot = random_object_ref();
+        let cold = random_object_ref();
+        let mutated = vec![
+            ((hot.0, SequenceNumber::from(1001), hot.2), owner),
+            ((cold.0, SequenceNumber::from(1000), cold.2), owner),
+        ];
+        assert_eq!(
+            objects_over_version(&mutated, SequenceNumber::from(1000)),
+            vec![(hot.0, SequenceNumber::from(1001))]
+        );
+        assert!(objects_over_version(&mutated, SequenceNumber::from(1001)).is_empty());
+    }
+
+    #[test]
+    fn test_bench_driver_config_from_opts() {
+        let opts = Opts::parse_from([
+            "stress",
+            "--tx-deadline-ms",
+            "50",
+            "--workload-stagger-secs",
+            "2",
+            "bench",
+            "--target-p99-ms",
+            "300",
+        ]);
+        let config = BenchDriverConfig::from_opts(&opts, None, true);
+        assert_eq!(config.tx_deadline, Some(Duration::from_millis(50)));
+        assert_eq!(config.workload_stagger, Duration::from_secs(2));
+        assert_eq!(config.target_p99_ms, Some(300));
+        assert!(config.drain_in_flight);
+        // Flags left unset keep the defaults
+        let defaults = BenchDriverConfig::default();
+        assert_eq!(
+            config.stat_collection_interval,
+            defaults.stat_collection_interval
+        );
+        assert_eq!(config.drain_timeout, defaults.drain_timeout);
+        assert!(!config.verify_effects_signatures);
+        assert!(!config.closed_loop);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_transactions_release_their_slot() {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let keypair = Arc::new(keypair);
+        let gas_objects: Vec<_> = (0..2)
+            .map(|_| Object::with_owner_for_testing(sender))
+            .collect();
+        let send_to_object_payload_gas = gas_objects
+            .iter()
+            .map(|object| {
+                (
+                    object.compute_object_reference(),
+                    Owner::AddressOwner(sender),
+                    keypair.clone(),
+                )
+            })
+            .collect();
+        let workload = WorkloadInfo {
+            target_qps: 100,
+            num_workers: 1,
+            max_in_flight_ops: 2,
+            workload: Box::new(SendToObjectWorkload {
+                destinations: vec![ObjectID::random()],
+                gas_budget: None,
+            }),
+            payload_config: WorkloadPayloadGas {
+                send_to_object_payload_gas,
+                ..Default::default()
+            },
+        };
+        // About half of the transactions resolve after the deadline
+        let deadline = Duration::from_millis(50);
+        let latency = ProxyDelay::Uniform(Duration::ZERO, deadline * 2);
+        let proxy = Arc::new(
+            MockValidatorProxy::new(gas_objects.clone(), Some(latency), 0.0)
+                .await
+                .unwrap(),
+        );
+        let driver = BenchDriver::new(BenchDriverConfig {
+            stat_collection_interval: 1,
+            tx_deadline: Some(deadline),
+            drain_in_flight: true,
+            ..Default::default()
+        });
+        let registry = Registry::new();
+        let (stats, _) = driver
+            .run(
+                vec![workload],
+                proxy.clone(),
+                Arc::new(SystemStateObserver::new_for_testing(1)),
+                &registry,
+                false,
+                Interval::Time(Duration::from_secs(2)),
+            )
+            .await
+            .unwrap();
+        // The payloads came back from their timeouts and kept issuing
+        assert!(stats.num_timeout > 2);
+        assert!(stats.num_success > 2);
+        let in_flight = registry
+            .gather()
+            .into_iter()
+            .find(|family| family.get_name() == "num_in_flight")
+            .unwrap();
+        assert_eq!(in_flight.get_metric()[0].get_gauge().get_value(), 0.0);
+        // Each transaction bumps the version of its gas coin once, so no
+        // success was counted twice for a transaction resent after a timeout
+        let mut executed = 0;
+        for object in &gas_objects {
+            let version = proxy.get_object(object.id()).await.unwrap().version();
+            executed += version.value() - object.version().value();
+        }
+        assert!(stats.num_success <= executed);
+    }
+
+    #[tokio::test]
+    async fn test_with_deadline() {
+        let deadline = Some(Duration::from_millis(50));
+        // Effects arriving in time are returned
+        let res = with_deadline(async { 1 }, deadline).await;
+        assert_eq!(res, Some(1));
+        // Delayed effects are dropped once the deadline passes
+        let delayed = async {
+            time::sleep(Duration::from_secs(5)).await;
+            1
+        };
+        let start = Instant::now();
+        let res = with_deadline(delayed, deadline).await;
+        assert_eq!(res, None);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        // No deadline waits for the effects
+        let delayed = async {
+            time::sleep(Duration::from_millis(100)).await;
+            1
+        };
+        let res = with_deadline(delayed, None).await;
+        assert_eq!(res, Some(1));
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use comfy_table::{Cell, ContentArrangement, Row, Table};
+use duration_str::parse;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Traffic issued at the target qps for `on`, then paused for `off`, over
+/// and over, parsed from "on:<duration>,off:<duration>"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BurstPattern {
+    pub on: Duration,
+    pub off: Duration,
+}
+
+impl BurstPattern {
+    pub fn period(&self) -> Duration {
+        self.on + self.off
+    }
+
+    /// Burst cycle `elapsed` into the run falls in, and how far into it
+    pub fn position(&self, elapsed: Duration) -> (usize, Duration) {
+        let period = self.period().as_nanos();
+        let elapsed = elapsed.as_nanos();
+        (
+            (elapsed / period) as usize,
+            Duration::from_nanos((elapsed % period) as u64),
+        )
+    }
+
+    /// Whether transactions are issued `elapsed` into the run
+    pub fn is_on(&self, elapsed: Duration) -> bool {
+        self.position(elapsed).1 < self.on
+    }
+}
+
+impl FromStr for BurstPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut on, mut off) = (None, None);
+        for part in s.split(',') {
+            let (key, value) = part
+                .split_once(':')
+                .ok_or_else(|| format!("Expected on:<duration> or off:<duration>, got {}", part))?;
+            let value = parse(value.trim()).map_err(|e| e.to_string())?;
+            match key.trim() {
+                "on" => on = Some(value),
+                "off" => off = Some(value),
+                key => return Err(format!("Unknown burst window {}, expected on or off", key)),
+            }
+        }
+        match (on, off) {
+            (Some(on), Some(off)) if !on.is_zero() => Ok(BurstPattern { on, off }),
+            _ => Err("Burst pattern requires a non zero on window and an off window".to_string()),
+        }
+    }
+}
+
+/// Transactions issued during one on window
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct BurstCycle {
+    pub num_success: u64,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+    // Time after the end of the on window the last of its transactions
+    // completed, i.e. how long the backlog of the burst took to drain
+    pub drain: Duration,
+}
+
+/// Latency of each burst, to see how quickly the system recovers from them
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Bursts {
+    pub pattern: BurstPattern,
+    pub cycles: Vec<BurstCycle>,
+}
+
+impl Bursts {
+    pub fn new(pattern: BurstPattern) -> Self {
+        Self {
+            pattern,
+            cycles: vec![],
+        }
+    }
+
+    /// Records a transaction completing `elapsed` into the run after
+    /// `latency`, in the cycle it was submitted in
+    pub fn record(&mut self, elapsed: Duration, latency: Duration) {
+        let submitted = elapsed.saturating_sub(latency);
+        let (index, _) = self.pattern.position(submitted);
+        if self.cycles.len() <= index {
+            self.cycles.resize(index + 1, BurstCycle::default());
+        }
+        let on_end = self.pattern.period() * index as u32 + self.pattern.on;
+        let cycle = &mut self.cycles[index];
+        cycle.num_success += 1;
+        cycle.total_latency += latency;
+        cycle.max_latency = cycle.max_latency.max(latency);
+        cycle.drain = cycle.drain.max(elapsed.saturating_sub(on_end));
+    }
+
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "burst",
+                "transactions",
+                "latency (mean)",
+                "latency (max)",
+                "drained after (ms)",
+            ]);
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(i + 1));
+            row.add_cell(Cell::new(cycle.num_success));
+            row.add_cell(Cell::new(
+                (cycle.total_latency / cycle.num_success.max(1) as u32).as_millis(),
+            ));
+            row.add_cell(Cell::new(cycle.max_latency.as_millis()));
+            row.add_cell(Cell::new(cycle.drain.as_millis()));
+            table.add_row(row);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_pattern() {
+        let pattern: BurstPattern = "on:2s,off:3s".parse().unwrap();
+        assert_eq!(pattern.on, Duration::from_secs(2));
+        assert_eq!(pattern.off, Duration::from_secs(3));
+        assert!(pattern.is_on(Duration::from_millis(1_999)));
+        assert!(!pattern.is_on(Duration::from_secs(2)));
+        assert!(pattern.is_on(Duration::from_secs(6)));
+        assert!("on:2s".parse::<BurstPattern>().is_err());
+        assert!("on:0s,off:1s".parse::<BurstPattern>().is_err());
+
+        let mut bursts = Bursts::new(pattern);
+        bursts.record(Duration::from_secs(1), Duration::from_millis(100));
+        // Submitted in the first burst, completed 500ms after it ended
+        bursts.record(Duration::from_millis(2_500), Duration::from_secs(1));
+        bursts.record(Duration::from_secs(6), Duration::from_millis(200));
+        assert_eq!(bursts.cycles.len(), 2);
+        assert_eq!(bursts.cycles[0].num_success, 2);
+        assert_eq!(bursts.cycles[0].drain, Duration::from_millis(500));
+        assert_eq!(bursts.cycles[1].drain, Duration::ZERO);
+        assert_eq!(bursts.to_table().row_iter().count(), 2);
+    }
+}
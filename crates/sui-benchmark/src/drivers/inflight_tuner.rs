@@ -0,0 +1,107 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Windows are expressed in permille of the payloads provisioned for a worker
+pub const FULL_WINDOW: u64 = 1000;
+// The search stops once the bounds are this close
+const WINDOW_TOLERANCE: u64 = 10;
+
+/// Number of transactions a worker provisioned with `num_payloads` keeps in
+/// flight at `permille` of its full window, at least one
+pub fn inflight_window(num_payloads: u64, permille: u64) -> u64 {
+    (num_payloads * permille / FULL_WINDOW).max(1)
+}
+
+/// Binary searches the in-flight window that keeps the p99 latency at or
+/// below a target. The provisioned window is the upper bound, the search
+/// starts there and only narrows it. Each step is held for two stat rounds,
+/// the first lets the new window settle and the second is measured.
+#[derive(Debug)]
+pub struct InflightTuner {
+    target_p99_ms: u64,
+    // Largest window meeting the target and smallest one missing it so far
+    low: u64,
+    high: u64,
+    current: u64,
+    settled: bool,
+    converged: bool,
+}
+
+impl InflightTuner {
+    pub fn new(target_p99_ms: u64) -> Self {
+        Self {
+            target_p99_ms,
+            low: 0,
+            high: FULL_WINDOW + 1,
+            current: FULL_WINDOW,
+            settled: false,
+            converged: false,
+        }
+    }
+
+    pub fn window(&self) -> u64 {
+        self.current
+    }
+
+    pub fn is_converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Feeds the p99 latency of a stat round, returns the next window when
+    /// it changes. Once converged the window is held for the rest of the run.
+    pub fn observe(&mut self, p99_ms: u64) -> Option<u64> {
+        if self.converged {
+            return None;
+        }
+        if !self.settled {
+            self.settled = true;
+            return None;
+        }
+        self.settled = false;
+        if p99_ms <= self.target_p99_ms {
+            self.low = self.current;
+        } else {
+            self.high = self.current;
+        }
+        if self.high - self.low <= WINDOW_TOLERANCE {
+            self.converged = true;
+            self.current = self.low;
+        } else {
+            self.current = (self.low + self.high) / 2;
+        }
+        Some(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converge(tuner: &mut InflightTuner, p99_ms: impl Fn(u64) -> u64) -> u64 {
+        while !tuner.is_converged() {
+            let p99 = p99_ms(tuner.window());
+            tuner.observe(p99);
+            tuner.observe(p99);
+        }
+        tuner.window()
+    }
+
+    #[test]
+    fn test_inflight_tuner() {
+        // Latency grows with the window, 300 permille is the last one at 100ms
+        let window = converge(&mut InflightTuner::new(100), |w| w / 3);
+        assert!((290..=300).contains(&window), "{}", window);
+        assert_eq!(inflight_window(50, window), 50 * window / 1000);
+
+        // The provisioned window already meets the target
+        let mut tuner = InflightTuner::new(100);
+        assert_eq!(tuner.observe(10), None);
+        assert_eq!(tuner.observe(10), Some(FULL_WINDOW));
+        assert!(tuner.is_converged());
+        assert_eq!(tuner.observe(1000), None);
+
+        // No window meets the target, hold a single transaction per worker
+        let window = converge(&mut InflightTuner::new(100), |_| 1000);
+        assert_eq!(inflight_window(50, window), 1);
+    }
+}
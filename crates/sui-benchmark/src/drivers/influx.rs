@@ -0,0 +1,146 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Label;
+use anyhow::Context;
+use hdrhistogram::Histogram;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MEASUREMENT: &str = "sui_benchmark";
+
+/// Stats of one workload over the last stat collection interval
+pub struct IntervalMeasurement {
+    pub workload: String,
+    pub qps: f32,
+    pub num_success: u64,
+    pub num_error: u64,
+    pub num_timeout: u64,
+    pub latency_ms: Histogram<u64>,
+}
+
+// Tag keys and values must escape commas, equal signs and spaces
+fn escape_tag(tag: &str) -> String {
+    tag.replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+impl IntervalMeasurement {
+    /// Formats the measurement as one InfluxDB line protocol point, tagged
+    /// with the run labels
+    pub fn to_line(&self, timestamp: SystemTime, labels: &[Label]) -> String {
+        let nanos = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let workload = escape_tag(&self.workload);
+        let tags: String = labels
+            .iter()
+            .map(|label| format!(",{}={}", escape_tag(&label.key), escape_tag(&label.value)))
+            .collect();
+        format!(
+            "{MEASUREMENT},workload={workload}{tags} qps={},num_success={}i,num_error={}i,num_timeout={}i,\
+            latency_p50_ms={}i,latency_p99_ms={}i,latency_max_ms={}i {nanos}",
+            self.qps,
+            self.num_success,
+            self.num_error,
+            self.num_timeout,
+            self.latency_ms.value_at_quantile(0.5),
+            self.latency_ms.value_at_quantile(0.99),
+            self.latency_ms.max(),
+        )
+    }
+}
+
+/// Destination of the interval measurements, an http(s) url is treated as
+/// an Influx write endpoint and anything else as a file path
+pub enum InfluxOutput {
+    File(BufWriter<File>),
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+}
+
+impl InfluxOutput {
+    pub fn new(target: &str) -> anyhow::Result<Self> {
+        if target.starts_with("http://") || target.starts_with("https://") {
+            Ok(InfluxOutput::Http {
+                client: reqwest::Client::new(),
+                url: target.to_string(),
+            })
+        } else {
+            let file = File::create(target)
+                .with_context(|| format!("Failed to create influx output file {}", target))?;
+            Ok(InfluxOutput::File(BufWriter::new(file)))
+        }
+    }
+
+    pub async fn write(
+        &mut self,
+        measurements: &[IntervalMeasurement],
+        labels: &[Label],
+    ) -> anyhow::Result<()> {
+        let now = SystemTime::now();
+        let lines: Vec<String> = measurements
+            .iter()
+            .map(|m| m.to_line(now, labels))
+            .collect();
+        match self {
+            InfluxOutput::File(writer) => {
+                for line in lines {
+                    writeln!(writer, "{}", line)?;
+                }
+                writer.flush()?;
+            }
+            InfluxOutput::Http { client, url } => {
+                client
+                    .post(url.as_str())
+                    .body(lines.join("\n"))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_line_protocol() {
+        let mut latency_ms = Histogram::<u64>::new_with_max(120_000, 3).unwrap();
+        latency_ms.record(100).unwrap();
+        let measurement = IntervalMeasurement {
+            workload: "shared counter".to_string(),
+            qps: 12.5,
+            num_success: 125,
+            num_error: 2,
+            num_timeout: 0,
+            latency_ms,
+        };
+        let timestamp = UNIX_EPOCH + Duration::from_secs(1);
+        assert_eq!(
+            measurement.to_line(timestamp, &[]),
+            "sui_benchmark,workload=shared\\ counter qps=12.5,num_success=125i,num_error=2i,\
+            num_timeout=0i,latency_p50_ms=100i,latency_p99_ms=100i,latency_max_ms=100i \
+            1000000000"
+        );
+
+        let labels: Vec<Label> = ["hardware=m6i.4xlarge", "note=two nodes"]
+            .iter()
+            .map(|label| label.parse().unwrap())
+            .collect();
+        assert!(measurement.to_line(timestamp, &labels).starts_with(
+            "sui_benchmark,workload=shared\\ counter,hardware=m6i.4xlarge,note=two\\ nodes qps="
+        ));
+        assert!("hardware".parse::<Label>().is_err());
+        assert!("=m6i".parse::<Label>().is_err());
+    }
+}
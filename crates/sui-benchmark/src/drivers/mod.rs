@@ -2,14 +2,41 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use duration_str::parse;
-use std::{str::FromStr, time::Duration};
+use std::cmp::{Ordering, Reverse};
+use std::io::Write;
+use std::path::Path;
+use std::{
+    collections::{BTreeMap, BinaryHeap, HashMap},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 pub mod bench_driver;
+pub mod burst;
 pub mod driver;
+pub mod inflight_tuner;
+pub mod influx;
+pub mod progress;
+pub mod ramp_up;
+pub mod timeseries;
+pub mod validator_metrics;
+pub mod warmup;
 use comfy_table::{Cell, Color, ContentArrangement, Row, Table};
 use hdrhistogram::{serialization::Serializer, Histogram};
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::committee::EpochId;
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::drivers::burst::Bursts;
+use crate::drivers::progress::BenchProgress;
+use crate::drivers::validator_metrics::ValidatorMetricsDelta;
+use crate::drivers::warmup::Warmup;
+use crate::retrying_proxy::RetryStats;
+use crate::system_state_observer::CommitteeSummary;
+use crate::EffectsWait;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Interval {
     Count(u64),
     Time(tokio::time::Duration),
@@ -39,7 +66,7 @@ impl FromStr for Interval {
 
 // wrapper which implements serde
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HistogramWrapper {
     histogram: Histogram<u64>,
 }
@@ -64,10 +91,637 @@ impl<'de> serde::Deserialize<'de> for HistogramWrapper {
     }
 }
 
+impl HistogramWrapper {
+    /// Writes the raw histogram to `path` in the hdrhistogram V2 format
+    pub fn export(&self, path: &str) -> anyhow::Result<()> {
+        let mut vec = Vec::new();
+        hdrhistogram::serialization::V2Serializer::new()
+            .serialize(&self.histogram, &mut vec)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        std::fs::write(path, vec)?;
+        Ok(())
+    }
+
+    /// Reads back a histogram written by `export`
+    pub fn import(path: &str) -> anyhow::Result<Self> {
+        let vec = std::fs::read(path)?;
+        let histogram: Histogram<u64> = hdrhistogram::serialization::Deserializer::new()
+            .deserialize(&mut &vec[..])
+            .map_err(|e| anyhow::anyhow!("Failed to read histogram from {path}: {e}"))?;
+        Ok(HistogramWrapper { histogram })
+    }
+
+    /// Combines the histograms exported by several benchmark clients
+    pub fn merge(paths: &[String]) -> anyhow::Result<Self> {
+        let mut merged = HistogramWrapper {
+            histogram: Histogram::<u64>::new_with_max(120_000, 3).unwrap(),
+        };
+        for path in paths {
+            let other = Self::import(path)?;
+            merged
+                .histogram
+                .add(&other.histogram)
+                .map_err(|e| anyhow::anyhow!("Failed to merge histogram from {path}: {e}"))?;
+        }
+        Ok(merged)
+    }
+
+    /// Writes the percentile distribution in the text .hgrm format, the
+    /// output of HdrHistogram's outputPercentileDistribution read by the
+    /// standard plotting tools
+    pub fn write_hgrm<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        let histogram = &self.histogram;
+        writeln!(
+            out,
+            "{:>12} {:>14} {:>10} {:>14}\n",
+            "Value", "Percentile", "TotalCount", "1/(1-Percentile)"
+        )?;
+        let mut total_count = 0;
+        for v in histogram.iter_quantiles(5) {
+            total_count += v.count_since_last_iteration();
+            let quantile = v.quantile_iterated_to();
+            if quantile < 1.0 {
+                writeln!(
+                    out,
+                    "{:12.3} {:2.12} {:10} {:14.2}",
+                    v.value_iterated_to() as f64,
+                    quantile,
+                    total_count,
+                    1.0 / (1.0 - quantile)
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "{:12.3} {:2.12} {:10}",
+                    v.value_iterated_to() as f64,
+                    quantile,
+                    total_count
+                )?;
+            }
+        }
+        writeln!(
+            out,
+            "#[Mean    = {:12.3}, StdDeviation   = {:12.3}]",
+            histogram.mean(),
+            histogram.stdev()
+        )?;
+        writeln!(
+            out,
+            "#[Max     = {:12.3}, Total count    = {:12}]",
+            histogram.max() as f64,
+            histogram.len()
+        )?;
+        writeln!(
+            out,
+            "#[Buckets = {:12}, SubBuckets     = {:12}]",
+            histogram.buckets(),
+            (2 * 10u32.pow(histogram.sigfig() as u32)).next_power_of_two()
+        )
+    }
+
+    fn new() -> Self {
+        HistogramWrapper {
+            histogram: Histogram::<u64>::new_with_max(120_000, 3).unwrap(),
+        }
+    }
+
+    /// Records `latency` in milliseconds, saturating at the histogram's max
+    pub fn record(&mut self, latency: Duration) {
+        self.histogram
+            .saturating_record(latency.as_millis().try_into().unwrap_or(u64::MAX));
+    }
+
+    /// Number of recorded latencies
+    pub fn count(&self) -> u64 {
+        self.histogram.len()
+    }
+
+    /// Smallest recorded latency in milliseconds
+    pub fn min_ms(&self) -> u64 {
+        self.histogram.min()
+    }
+
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "count",
+                "latency (min)",
+                "latency (p50)",
+                "latency (p90)",
+                "latency (p99)",
+                "latency (p999)",
+                "latency (max)",
+            ]);
+        let mut row = Row::new();
+        row.add_cell(Cell::new(self.histogram.len()));
+        row.add_cell(Cell::new(self.histogram.min()));
+        row.add_cell(Cell::new(self.histogram.value_at_quantile(0.5)));
+        row.add_cell(Cell::new(self.histogram.value_at_quantile(0.9)));
+        row.add_cell(Cell::new(self.histogram.value_at_quantile(0.99)));
+        row.add_cell(Cell::new(self.histogram.value_at_quantile(0.999)));
+        row.add_cell(Cell::new(self.histogram.max()));
+        table.add_row(row);
+        table
+    }
+}
+
+impl Default for HistogramWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Latency of transactions split by their final outcome
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct OutcomeLatency {
+    // Succeeded on the first attempt
+    pub success_ms: HistogramWrapper,
+    // Succeeded after one or more retries, measured from the first attempt
+    pub retried_ms: HistogramWrapper,
+    // Attempts which failed or timed out
+    pub failed_ms: HistogramWrapper,
+    // Attempts made by the retried transactions, the successful one included
+    #[serde(default)]
+    pub retried_attempts: u64,
+}
+
+impl OutcomeLatency {
+    pub fn new() -> Self {
+        OutcomeLatency {
+            success_ms: HistogramWrapper::new(),
+            retried_ms: HistogramWrapper::new(),
+            failed_ms: HistogramWrapper::new(),
+            retried_attempts: 0,
+        }
+    }
+
+    pub fn update(&mut self, sample: &OutcomeLatency) {
+        self.success_ms
+            .histogram
+            .add(&sample.success_ms.histogram)
+            .unwrap();
+        self.retried_ms
+            .histogram
+            .add(&sample.retried_ms.histogram)
+            .unwrap();
+        self.failed_ms
+            .histogram
+            .add(&sample.failed_ms.histogram)
+            .unwrap();
+        self.retried_attempts += sample.retried_attempts;
+    }
+
+    pub fn reset(&mut self) {
+        self.success_ms.histogram.reset();
+        self.retried_ms.histogram.reset();
+        self.failed_ms.histogram.reset();
+        self.retried_attempts = 0;
+    }
+
+    /// Transactions which succeeded, retried or not
+    pub fn succeeded(&self) -> u64 {
+        self.success_ms.histogram.len() + self.retried_ms.histogram.len()
+    }
+
+    /// Share of the successful transactions which needed a retry
+    pub fn retry_rate(&self) -> f64 {
+        let retried = self.retried_ms.histogram.len();
+        let total = retried + self.success_ms.histogram.len();
+        if total == 0 {
+            0.0
+        } else {
+            retried as f64 / total as f64
+        }
+    }
+
+    /// Mean latency retried transactions spent over first-attempt successes,
+    /// zero until both happened
+    pub fn mean_retry_penalty_ms(&self) -> f64 {
+        if self.retried_ms.histogram.is_empty() || self.success_ms.histogram.is_empty() {
+            0.0
+        } else {
+            self.retried_ms.histogram.mean() - self.success_ms.histogram.mean()
+        }
+    }
+}
+
+impl Default for OutcomeLatency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Client side cost of transactions, kept for --measure-build-latency
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct BuildLatency {
+    // Construction and signing of each transaction, in microseconds
+    pub build_us: HistogramWrapper,
+    // From the start of construction to the effects, in milliseconds
+    pub end_to_end_ms: HistogramWrapper,
+}
+
+impl BuildLatency {
+    pub fn new() -> Self {
+        BuildLatency {
+            build_us: HistogramWrapper {
+                histogram: Histogram::<u64>::new_with_max(10_000_000, 3).unwrap(),
+            },
+            end_to_end_ms: HistogramWrapper::new(),
+        }
+    }
+
+    pub fn record_build(&mut self, build: Duration) {
+        self.build_us
+            .histogram
+            .saturating_record(build.as_micros().try_into().unwrap_or(u64::MAX));
+    }
+
+    pub fn update(&mut self, sample: &BuildLatency) {
+        self.build_us
+            .histogram
+            .add(&sample.build_us.histogram)
+            .unwrap();
+        self.end_to_end_ms
+            .histogram
+            .add(&sample.end_to_end_ms.histogram)
+            .unwrap();
+    }
+
+    pub fn reset(&mut self) {
+        self.build_us.histogram.reset();
+        self.end_to_end_ms.histogram.reset();
+    }
+}
+
+impl Default for BuildLatency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Budgets above this multiple of the most gas a transaction used are
+/// reported as overbudgeted
+pub const OVERBUDGET_FACTOR: u64 = 10;
+
+/// Gas budget set on the transactions of a workload against the gas they
+/// actually used, both in gas units
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GasUsage {
+    pub num_txns: u64,
+    // Largest budget set on any transaction
+    pub max_budget: u64,
+    pub total_used: u64,
+    pub max_used: u64,
+}
+
+impl GasUsage {
+    pub fn record(&mut self, budget: u64, used: u64) {
+        self.num_txns += 1;
+        self.max_budget = self.max_budget.max(budget);
+        self.total_used += used;
+        self.max_used = self.max_used.max(used);
+    }
+
+    pub fn mean_used(&self) -> u64 {
+        if self.num_txns == 0 {
+            0
+        } else {
+            self.total_used / self.num_txns
+        }
+    }
+
+    /// Whether the budget exceeds even the most expensive transaction by
+    /// more than OVERBUDGET_FACTOR, which hides changes in gas costs
+    pub fn overbudgeted(&self) -> bool {
+        self.max_budget > self.max_used.max(1) * OVERBUDGET_FACTOR
+    }
+}
+
+/// Free-form key=value tag of a run, e.g. "hardware=m6i.4xlarge"
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Label {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for Label {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok(Label {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            _ => Err(format!("Expected a label as key=value, got {}", s)),
+        }
+    }
+}
+
+/// How a benchmark run was produced, embedded in its results
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RunMetadata {
+    // Crate version and git revision of the benchmark binary
+    pub version: String,
+    // Every option of the run, defaults and preset included
+    pub config: serde_json::Value,
+    // Labels given with --label, to group results across runs
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    // What the measured latencies waited for
+    #[serde(default)]
+    pub effects_wait: Option<EffectsWait>,
+}
+
+/// Balance of the most spent payload gas coin at one check of --gas-monitor-secs
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GasBalanceSample {
+    // Time into the run of the check
+    pub elapsed: Duration,
+    pub coin: ObjectID,
+    pub balance: u64,
+    // Net of storage rebates, since the start of the run
+    pub spent: u64,
+    // Time until the coin runs out at its rate of spending so far
+    pub depletion: Option<Duration>,
+}
+
+/// Time until `balance` is spent at the rate `spent` was over `elapsed`
+pub fn projected_depletion(balance: u64, spent: u64, elapsed: Duration) -> Option<Duration> {
+    if spent == 0 || elapsed.is_zero() {
+        return None;
+    }
+    Some(elapsed.mul_f64(balance as f64 / spent as f64))
+}
+
+/// Measurement window of the run over which the epoch changed, its
+/// latencies mix both epochs
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EpochSpanningWindow {
+    // Start and end of the window into the run
+    pub from: Duration,
+    pub to: Duration,
+    // Epochs at the start and end of the window
+    pub epochs: (EpochId, EpochId),
+}
+
+/// A proxy connection excluded from the run after failing persistently,
+/// the rest of the run went to the healthy connections
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExcludedConnection {
+    pub connection: usize,
+    // Time into the run of the exclusion
+    pub elapsed: Duration,
+    pub error: String,
+}
+
+impl ExcludedConnection {
+    pub fn new(exclusion: &ConnectionExclusion, start: Instant) -> Self {
+        Self {
+            connection: exclusion.connection,
+            elapsed: exclusion.at.saturating_duration_since(start),
+            error: exclusion.error.clone(),
+        }
+    }
+}
+
+/// A transaction kept by --dump-slowest
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SlowTransaction {
+    pub digest: TransactionDigest,
+    pub workload: String,
+    // From the first attempt to the effects
+    pub latency: Duration,
+    // Of the attempt that succeeded, the rest went to failed attempts and retries
+    pub last_attempt: Duration,
+    pub inputs: Vec<ObjectID>,
+}
+
+// Orders the kept transactions by latency only
+#[derive(Debug)]
+struct ByLatency(SlowTransaction);
+
+impl PartialEq for ByLatency {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ByLatency {}
+
+impl PartialOrd for ByLatency {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByLatency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.latency.cmp(&other.0.latency)
+    }
+}
+
+/// Keeps the `capacity` slowest transactions recorded
+#[derive(Debug, Default)]
+pub struct SlowestTransactions {
+    capacity: usize,
+    // Min-heap, the fastest of the kept transactions is evicted first
+    heap: BinaryHeap<Reverse<ByLatency>>,
+}
+
+impl SlowestTransactions {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity),
+        }
+    }
+
+    /// Whether a transaction this slow would be kept
+    pub fn keeps(&self, latency: Duration) -> bool {
+        self.heap.len() < self.capacity
+            || self
+                .heap
+                .peek()
+                .map_or(false, |Reverse(fastest)| fastest.0.latency < latency)
+    }
+
+    pub fn record(&mut self, transaction: SlowTransaction) {
+        if !self.keeps(transaction.latency) {
+            return;
+        }
+        if self.heap.len() == self.capacity {
+            self.heap.pop();
+        }
+        self.heap.push(Reverse(ByLatency(transaction)));
+    }
+
+    /// The kept transactions, slowest first
+    pub fn to_sorted_vec(&self) -> Vec<SlowTransaction> {
+        let mut transactions: Vec<SlowTransaction> =
+            self.heap.iter().map(|Reverse(t)| t.0.clone()).collect();
+        transactions.sort_by(|a, b| b.latency.cmp(&a.latency));
+        transactions
+    }
+}
+
+/// A transaction built more than once in a run. Only one of the copies can
+/// execute, it means two payloads were handed the same gas coin or objects
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DuplicateTransaction {
+    pub digest: TransactionDigest,
+    pub workload: String,
+    // Workload of the payload that built the transaction first
+    pub first_workload: String,
+}
+
+/// Digests of every transaction built in the run, kept for
+/// --detect-duplicate-transactions
+#[derive(Debug, Default)]
+pub struct DuplicateDetector {
+    seen: HashMap<TransactionDigest, String>,
+    duplicates: Vec<DuplicateTransaction>,
+}
+
+impl DuplicateDetector {
+    /// Records a transaction built by `workload`, returns the duplicate if
+    /// the same transaction was built before
+    pub fn record(
+        &mut self,
+        digest: TransactionDigest,
+        workload: &str,
+    ) -> Option<DuplicateTransaction> {
+        let first_workload = match self.seen.get(&digest) {
+            Some(first_workload) => first_workload.clone(),
+            None => {
+                self.seen.insert(digest, workload.to_string());
+                return None;
+            }
+        };
+        let duplicate = DuplicateTransaction {
+            digest,
+            workload: workload.to_string(),
+            first_workload,
+        };
+        self.duplicates.push(duplicate.clone());
+        Some(duplicate)
+    }
+
+    pub fn duplicates(&self) -> Vec<DuplicateTransaction> {
+        self.duplicates.clone()
+    }
+}
+
+/// How consensus ordered the transactions of a workload on shared objects
+/// against the order they were submitted in
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OrderingFairness {
+    // Commits that followed another commit to the same shared object
+    pub commits: u64,
+    // Commits submitted before the commit ordered ahead of them
+    pub overtaken: u64,
+    // Summed time the overtaken commits were submitted ahead of the commit
+    // that overtook them
+    pub overtaken_by_ms: u64,
+}
+
+impl OrderingFairness {
+    pub fn overtaken_rate(&self) -> f64 {
+        self.overtaken as f64 / self.commits.max(1) as f64
+    }
+
+    pub fn mean_overtaken_by_ms(&self) -> f64 {
+        self.overtaken_by_ms as f64 / self.overtaken.max(1) as f64
+    }
+}
+
+/// Submission time and workload of every transaction which mutated a shared
+/// object, by the version it gave the object, kept for --track-ordering.
+/// Consensus assigns the versions, so they give the commit order
+#[derive(Debug, Default)]
+pub struct OrderingTracker {
+    commits: HashMap<ObjectID, Vec<(SequenceNumber, Instant, String)>>,
+}
+
+impl OrderingTracker {
+    pub fn record(
+        &mut self,
+        object: ObjectID,
+        version: SequenceNumber,
+        submitted: Instant,
+        workload: &str,
+    ) {
+        self.commits
+            .entry(object)
+            .or_default()
+            .push((version, submitted, workload.to_string()));
+    }
+
+    /// Walks the commits to each shared object in version order, a commit
+    /// submitted before the one ahead of it was overtaken by that one
+    pub fn fairness(&self) -> BTreeMap<String, OrderingFairness> {
+        let mut fairness: BTreeMap<String, OrderingFairness> = BTreeMap::new();
+        for commits in self.commits.values() {
+            let mut commits: Vec<_> = commits.iter().collect();
+            commits.sort_by_key(|(version, _, _)| *version);
+            for pair in commits.windows(2) {
+                let (_, ahead, _) = pair[0];
+                let (_, submitted, workload) = pair[1];
+                let entry = fairness.entry(workload.clone()).or_default();
+                entry.commits += 1;
+                if submitted < ahead {
+                    entry.overtaken += 1;
+                    entry.overtaken_by_ms += (*ahead - *submitted).as_millis() as u64;
+                }
+            }
+        }
+        fairness
+    }
+}
+
+/// Net MIST spent on gas by the run against the cap of --max-total-mist-spend
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SpendBudget {
+    pub cap: u64,
+    pub spent: u64,
+    // Whether the run was stopped by reaching the cap
+    pub reached: bool,
+}
+
+impl SpendBudget {
+    pub fn new(cap: u64) -> Self {
+        Self {
+            cap,
+            spent: 0,
+            reached: false,
+        }
+    }
+
+    /// Adds the MIST spent by a transaction, returns true the first time
+    /// the cap is reached
+    pub fn record(&mut self, mist: u64) -> bool {
+        self.spent = self.spent.saturating_add(mist);
+        if self.reached || self.spent < self.cap {
+            return false;
+        }
+        self.reached = true;
+        true
+    }
+}
+
+/// Client CPU usage, in percent of all cores, above which the client is
+/// considered saturated
+pub const CLIENT_CPU_SATURATION: u64 = 90;
+
 // Stores the final stress statisicts of the test run.
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct StressStats {
     pub cpu_usage: HistogramWrapper,
+    // CPU usage of the benchmark client process in percent of all cores
+    pub client_cpu_usage: HistogramWrapper,
 }
 
 impl StressStats {
@@ -76,6 +730,17 @@ impl StressStats {
             .histogram
             .add(&sample_stat.cpu_usage.histogram)
             .unwrap();
+        self.client_cpu_usage
+            .histogram
+            .add(&sample_stat.client_cpu_usage.histogram)
+            .unwrap();
+    }
+
+    /// Whether the client process used most of the machine's CPU at its p99,
+    /// in which case throughput was likely capped by the client
+    pub fn client_cpu_saturated(&self) -> bool {
+        !self.client_cpu_usage.histogram.is_empty()
+            && self.client_cpu_usage.histogram.value_at_quantile(0.99) >= CLIENT_CPU_SATURATION
     }
 
     pub fn to_table(&self) -> Table {
@@ -90,28 +755,183 @@ impl StressStats {
         row.add_cell(Cell::new(self.cpu_usage.histogram.value_at_quantile(0.5)));
         row.add_cell(Cell::new(self.cpu_usage.histogram.value_at_quantile(0.99)));
         table.add_row(row);
+        let mut row = Row::new();
+        row.add_cell(Cell::new("client cpu usage"));
+        row.add_cell(Cell::new(
+            self.client_cpu_usage.histogram.value_at_quantile(0.5),
+        ));
+        row.add_cell(Cell::new(
+            self.client_cpu_usage.histogram.value_at_quantile(0.99),
+        ));
+        table.add_row(row);
         table
     }
 }
 
 /// Stores the final statistics of the test run.
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
 pub struct BenchmarkStats {
     pub duration: Duration,
     pub num_error: u64,
     pub num_success: u64,
+    #[serde(default)]
+    pub num_timeout: u64,
+    // Total time workers had no free payload to issue
+    #[serde(default)]
+    pub inflight_full: Duration,
     pub latency_ms: HistogramWrapper,
+    // Latency by outcome of each workload type
+    #[serde(default)]
+    pub outcome_latency_ms: BTreeMap<String, OutcomeLatency>,
+    // Time into the run and new epoch of each epoch change observed
+    #[serde(default)]
+    pub epoch_changes: Vec<(Duration, EpochId)>,
+    // Measurement windows whose transactions straddle an epoch change
+    #[serde(default)]
+    pub epoch_spanning_windows: Vec<EpochSpanningWindow>,
+    // Committee observed at the start of the run
+    #[serde(default)]
+    pub committee: Option<CommitteeSummary>,
+    // In-flight window found for --target-p99-ms
+    #[serde(default)]
+    pub tuned_in_flight: Option<u64>,
+    // Budget against actual gas of the transactions of each workload type
+    #[serde(default)]
+    pub gas_usage: BTreeMap<String, GasUsage>,
+    // Slowest transactions of the run, slowest first, kept for --dump-slowest
+    #[serde(default)]
+    pub slowest: Vec<SlowTransaction>,
+    // Latency of the first transactions of the run, kept for --measure-warmup
+    #[serde(default)]
+    pub warmup: Option<Warmup>,
+    // Workload, start and end into the run of each --sequential-workloads window
+    #[serde(default)]
+    pub workload_windows: Vec<(String, Duration, Duration)>,
+    // Balance checks of the payload gas coins, kept for --gas-monitor-secs
+    #[serde(default)]
+    pub gas_balance: Vec<GasBalanceSample>,
+    // Version and options of the run, set by the stress binary
+    #[serde(default)]
+    pub run_metadata: Option<RunMetadata>,
+    // Latency of each burst, kept for --burst
+    #[serde(default)]
+    pub bursts: Option<Bursts>,
+    // Successful transactions whose effects certificate each validator signed
+    #[serde(default)]
+    pub effects_signers: BTreeMap<String, u64>,
+    // Build and build-inclusive latency of each workload type, kept for
+    // --measure-build-latency
+    #[serde(default)]
+    pub build_latency: BTreeMap<String, BuildLatency>,
+    // Transactions built more than once, kept for
+    // --detect-duplicate-transactions
+    #[serde(default)]
+    pub duplicate_transactions: Vec<DuplicateTransaction>,
+    // Gas spent against --max-total-mist-spend
+    #[serde(default)]
+    pub spend_budget: Option<SpendBudget>,
+    // Executed transactions of each workload type that failed their
+    // postcondition, kept for --check-postconditions
+    #[serde(default)]
+    pub postcondition_failures: BTreeMap<String, u64>,
+    // Progress over every session of a --resume run, this one included
+    #[serde(default)]
+    pub progress: Option<BenchProgress>,
+    // Change of the metrics of each --validator-metrics-urls endpoint over
+    // the run, set by the stress binary
+    #[serde(default)]
+    pub validator_metrics: Vec<ValidatorMetricsDelta>,
+    // Proxy connections excluded during the run after failing persistently
+    #[serde(default)]
+    pub excluded_connections: Vec<ExcludedConnection>,
+    // Time into the run the measured phase of --warmup-secs started, after
+    // the warmup transactions drained
+    #[serde(default)]
+    pub measured_from: Option<Duration>,
+    // Submit versus commit order on shared objects by workload, with --track-ordering
+    #[serde(default)]
+    pub ordering: BTreeMap<String, OrderingFairness>,
+    // Finality latency checked against checkpoint timestamps, when the
+    // effects wait for checkpoints
+    #[serde(default)]
+    pub clock_skew: Option<ClockSkew>,
+    // Transactions the proxy resubmitted after a transient error, with
+    // --max-retries. The driver only sees their final outcome
+    #[serde(default)]
+    pub proxy_retries: Option<RetryStats>,
 }
 
 impl BenchmarkStats {
+    /// Records that the window from `from` to `to` into the run spans
+    /// `epochs`, merged with the last window spanning the same epochs
+    /// since every worker reports the change in a window of its own
+    pub fn record_epoch_spanning_window(
+        &mut self,
+        from: Duration,
+        to: Duration,
+        epochs: (EpochId, EpochId),
+    ) {
+        match self.epoch_spanning_windows.last_mut() {
+            Some(window) if window.epochs == epochs => {
+                window.from = window.from.min(from);
+                window.to = window.to.max(to);
+            }
+            _ => self
+                .epoch_spanning_windows
+                .push(EpochSpanningWindow { from, to, epochs }),
+        }
+    }
+
     pub fn update(&mut self, duration: Duration, sample_stat: &BenchmarkStats) {
         self.duration = duration;
         self.num_error += sample_stat.num_error;
         self.num_success += sample_stat.num_success;
+        self.num_timeout += sample_stat.num_timeout;
+        self.inflight_full += sample_stat.inflight_full;
         self.latency_ms
             .histogram
             .add(&sample_stat.latency_ms.histogram)
             .unwrap();
+        for (workload, outcome_latency) in sample_stat.outcome_latency_ms.iter() {
+            self.outcome_latency_ms
+                .entry(workload.clone())
+                .or_default()
+                .update(outcome_latency);
+        }
+        for (workload, build_latency) in sample_stat.build_latency.iter() {
+            self.build_latency
+                .entry(workload.clone())
+                .or_default()
+                .update(build_latency);
+        }
+    }
+    /// Writes the latency of the successful transactions of each workload,
+    /// measured from their first attempt, to `<dir>/<workload>.hgrm`
+    pub fn export_hgrm(&self, dir: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for (workload, outcome_latency) in self.outcome_latency_ms.iter() {
+            let mut latency = outcome_latency.success_ms.clone();
+            latency
+                .histogram
+                .add(&outcome_latency.retried_ms.histogram)
+                .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            let path = dir.join(format!("{}.hgrm", workload));
+            let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            latency.write_hgrm(&mut file)?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+    /// Drops the measurements so far, keeping the committee and epoch changes
+    pub fn reset_measurements(&mut self) {
+        self.duration = Duration::ZERO;
+        self.num_error = 0;
+        self.num_success = 0;
+        self.num_timeout = 0;
+        self.inflight_full = Duration::ZERO;
+        self.latency_ms.histogram.reset();
+        self.outcome_latency_ms.clear();
+        self.build_latency.clear();
     }
     pub fn to_table(&self) -> Table {
         let mut table = Table::new();
@@ -122,6 +942,7 @@ impl BenchmarkStats {
                 "duration(s)",
                 "tps",
                 "error%",
+                "timeouts",
                 "latency (min)",
                 "latency (p50)",
                 "latency (p99)",
@@ -132,12 +953,312 @@ impl BenchmarkStats {
         row.add_cell(Cell::new(
             (100 * self.num_error) as f32 / (self.num_error + self.num_success) as f32,
         ));
+        row.add_cell(Cell::new(self.num_timeout));
         row.add_cell(Cell::new(self.latency_ms.histogram.min()));
         row.add_cell(Cell::new(self.latency_ms.histogram.value_at_quantile(0.5)));
         row.add_cell(Cell::new(self.latency_ms.histogram.value_at_quantile(0.99)));
         table.add_row(row);
         table
     }
+
+    /// Latency percentiles of successful, retried and failed transactions
+    /// of each workload side by side
+    pub fn outcome_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "workload",
+                "success (count)",
+                "success (p50)",
+                "success (p99)",
+                "retried (count)",
+                "retried (p50)",
+                "retried (p99)",
+                "failed (count)",
+                "failed (p50)",
+                "failed (p99)",
+            ]);
+        for (workload, outcome_latency) in self.outcome_latency_ms.iter() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(workload));
+            for histogram in [
+                &outcome_latency.success_ms.histogram,
+                &outcome_latency.retried_ms.histogram,
+                &outcome_latency.failed_ms.histogram,
+            ] {
+                row.add_cell(Cell::new(histogram.len()));
+                row.add_cell(Cell::new(histogram.value_at_quantile(0.5)));
+                row.add_cell(Cell::new(histogram.value_at_quantile(0.99)));
+            }
+            table.add_row(row);
+        }
+        table
+    }
+    /// Retries of each workload and the latency they added to the
+    /// transactions which needed them
+    pub fn retry_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "workload",
+                "retries",
+                "retry rate (%)",
+                "attempts per retried tx",
+                "first attempt mean (ms)",
+                "retried mean (ms)",
+                "added by retries (ms)",
+            ]);
+        for (workload, outcome_latency) in self.outcome_latency_ms.iter() {
+            let retried = outcome_latency.retried_ms.histogram.len();
+            let mut row = Row::new();
+            row.add_cell(Cell::new(workload));
+            row.add_cell(Cell::new(
+                outcome_latency.retried_attempts.saturating_sub(retried),
+            ));
+            row.add_cell(Cell::new(format!(
+                "{:.2}",
+                outcome_latency.retry_rate() * 100.0
+            )));
+            row.add_cell(Cell::new(format!(
+                "{:.2}",
+                outcome_latency.retried_attempts as f64 / retried.max(1) as f64
+            )));
+            row.add_cell(Cell::new(format!(
+                "{:.1}",
+                outcome_latency.success_ms.histogram.mean()
+            )));
+            row.add_cell(Cell::new(format!(
+                "{:.1}",
+                outcome_latency.retried_ms.histogram.mean()
+            )));
+            row.add_cell(Cell::new(format!(
+                "{:.1}",
+                outcome_latency.mean_retry_penalty_ms()
+            )));
+            table.add_row(row);
+        }
+        table
+    }
+    /// Client side build time against the network-only and build-inclusive
+    /// latency of the successful transactions of each workload
+    pub fn build_latency_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "workload",
+                "build us (p50)",
+                "build us (p99)",
+                "network (p50)",
+                "network (p99)",
+                "build-inclusive (p50)",
+                "build-inclusive (p99)",
+            ]);
+        for (workload, build_latency) in self.build_latency.iter() {
+            let mut network = HistogramWrapper::new();
+            if let Some(outcome_latency) = self.outcome_latency_ms.get(workload) {
+                network
+                    .histogram
+                    .add(&outcome_latency.success_ms.histogram)
+                    .unwrap();
+                network
+                    .histogram
+                    .add(&outcome_latency.retried_ms.histogram)
+                    .unwrap();
+            }
+            let mut row = Row::new();
+            row.add_cell(Cell::new(workload));
+            for histogram in [
+                &build_latency.build_us.histogram,
+                &network.histogram,
+                &build_latency.end_to_end_ms.histogram,
+            ] {
+                row.add_cell(Cell::new(histogram.value_at_quantile(0.5)));
+                row.add_cell(Cell::new(histogram.value_at_quantile(0.99)));
+            }
+            table.add_row(row);
+        }
+        table
+    }
+    /// Share of the successful transactions each validator signed the
+    /// effects certificate of, a quorum suffices so a lagging validator
+    /// signs fewer
+    pub fn effects_signers_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec!["validator", "signed", "tps", "share%"]);
+        let mut signers: Vec<(&String, &u64)> = self.effects_signers.iter().collect();
+        signers.sort_by(|a, b| b.1.cmp(a.1));
+        // Transactions of the tuning phase are signed but not counted as successes
+        let total = signers.first().map_or(0, |(_, signed)| **signed);
+        for (validator, signed) in signers {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(validator));
+            row.add_cell(Cell::new(signed));
+            row.add_cell(Cell::new(signed / self.duration.as_secs().max(1)));
+            row.add_cell(Cell::new(format!(
+                "{:.1}",
+                100.0 * *signed as f64 / self.num_success.max(total) as f64
+            )));
+            table.add_row(row);
+        }
+        table
+    }
+    pub fn gas_balance_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "elapsed (s)",
+                "coin",
+                "balance",
+                "spent",
+                "projected depletion (s)",
+            ]);
+        for sample in self.gas_balance.iter() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(sample.elapsed.as_secs()));
+            row.add_cell(Cell::new(sample.coin));
+            row.add_cell(Cell::new(sample.balance));
+            row.add_cell(Cell::new(sample.spent));
+            row.add_cell(Cell::new(
+                sample
+                    .depletion
+                    .map_or("-".to_string(), |d| d.as_secs().to_string()),
+            ));
+            table.add_row(row);
+        }
+        table
+    }
+    pub fn timeline_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec!["workload", "start (s)", "end (s)", "tps"]);
+        for (workload, start, end) in self.workload_windows.iter() {
+            let num_success = self
+                .outcome_latency_ms
+                .get(workload)
+                .map_or(0, |outcome_latency| {
+                    outcome_latency.success_ms.histogram.len()
+                        + outcome_latency.retried_ms.histogram.len()
+                });
+            let mut row = Row::new();
+            row.add_cell(Cell::new(workload));
+            row.add_cell(Cell::new(start.as_secs()));
+            row.add_cell(Cell::new(end.as_secs()));
+            row.add_cell(Cell::new(
+                num_success / end.saturating_sub(*start).as_secs().max(1),
+            ));
+            table.add_row(row);
+        }
+        table
+    }
+    pub fn ordering_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "workload",
+                "shared commits",
+                "overtaken",
+                "overtaken (%)",
+                "overtaken by (mean ms)",
+            ]);
+        for (workload, fairness) in self.ordering.iter() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(workload));
+            row.add_cell(Cell::new(fairness.commits));
+            row.add_cell(Cell::new(fairness.overtaken));
+            row.add_cell(Cell::new(format!(
+                "{:.2}",
+                fairness.overtaken_rate() * 100.0
+            )));
+            row.add_cell(Cell::new(format!("{:.1}", fairness.mean_overtaken_by_ms())));
+            table.add_row(row);
+        }
+        table
+    }
+    pub fn slowest_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "digest",
+                "workload",
+                "latency (ms)",
+                "last attempt (ms)",
+                "retrying (ms)",
+                "inputs",
+            ]);
+        for transaction in self.slowest.iter() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(transaction.digest));
+            row.add_cell(Cell::new(&transaction.workload));
+            row.add_cell(Cell::new(transaction.latency.as_millis()));
+            row.add_cell(Cell::new(transaction.last_attempt.as_millis()));
+            row.add_cell(Cell::new(
+                transaction
+                    .latency
+                    .saturating_sub(transaction.last_attempt)
+                    .as_millis(),
+            ));
+            row.add_cell(Cell::new(
+                transaction
+                    .inputs
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ));
+            table.add_row(row);
+        }
+        table
+    }
+    pub fn gas_usage_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "workload",
+                "txns",
+                "budget",
+                "used (mean)",
+                "used (max)",
+                "budget / used (max)",
+            ]);
+        for (workload, gas_usage) in self.gas_usage.iter() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(workload));
+            row.add_cell(Cell::new(gas_usage.num_txns));
+            row.add_cell(Cell::new(gas_usage.max_budget));
+            row.add_cell(Cell::new(gas_usage.mean_used()));
+            row.add_cell(Cell::new(gas_usage.max_used));
+            let ratio = Cell::new(format!(
+                "{:.1}",
+                gas_usage.max_budget as f64 / gas_usage.max_used.max(1) as f64
+            ));
+            row.add_cell(if gas_usage.overbudgeted() {
+                ratio.fg(Color::Red)
+            } else {
+                ratio
+            });
+            table.add_row(row);
+        }
+        table
+    }
 }
 
 /// A comparison between an old and a new benchmark.
@@ -351,3 +1472,196 @@ impl BenchmarkCmp<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_spanning_windows() {
+        let mut stats = BenchmarkStats::default();
+        let secs = Duration::from_secs;
+        // Two workers reporting the change from epoch 1 to 2
+        stats.record_epoch_spanning_window(secs(10), secs(20), (1, 2));
+        stats.record_epoch_spanning_window(secs(12), secs(22), (1, 2));
+        stats.record_epoch_spanning_window(secs(50), secs(60), (2, 3));
+        assert_eq!(
+            stats.epoch_spanning_windows,
+            vec![
+                EpochSpanningWindow {
+                    from: secs(10),
+                    to: secs(22),
+                    epochs: (1, 2),
+                },
+                EpochSpanningWindow {
+                    from: secs(50),
+                    to: secs(60),
+                    epochs: (2, 3),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gas_usage() {
+        let mut gas_usage = GasUsage::default();
+        gas_usage.record(10_000, 400);
+        gas_usage.record(10_000, 600);
+        assert_eq!(gas_usage.mean_used(), 500);
+        assert_eq!(gas_usage.max_used, 600);
+        assert!(gas_usage.overbudgeted());
+        gas_usage.record(5_000, 1_000);
+        assert_eq!(gas_usage.max_budget, 10_000);
+        assert!(!gas_usage.overbudgeted());
+    }
+
+    #[test]
+    fn test_retry_rate_and_penalty() {
+        let mut outcome = OutcomeLatency::new();
+        assert_eq!(outcome.retry_rate(), 0.0);
+        for _ in 0..3 {
+            outcome.success_ms.record(Duration::from_millis(100));
+        }
+        outcome.retried_ms.record(Duration::from_millis(400));
+        outcome.retried_attempts += 3;
+        assert_eq!(outcome.retry_rate(), 0.25);
+        assert!((outcome.mean_retry_penalty_ms() - 300.0).abs() < 1.0);
+
+        let mut total = OutcomeLatency::new();
+        total.update(&outcome);
+        assert_eq!(total.retried_attempts, 3);
+        total.reset();
+        assert_eq!(total.retried_attempts, 0);
+    }
+
+    #[test]
+    fn test_ordering_fairness() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let (counter, other) = (ObjectID::random(), ObjectID::random());
+        let mut tracker = OrderingTracker::default();
+        // Submitted at 0, 10 and 30ms but committed in the order 10, 0, 30
+        tracker.record(counter, SequenceNumber::from(3), at(10), "shared_counter");
+        tracker.record(counter, SequenceNumber::from(4), at(0), "shared_counter");
+        tracker.record(counter, SequenceNumber::from(5), at(30), "shared_counter");
+        // A single commit has nothing to be ordered against
+        tracker.record(other, SequenceNumber::from(7), at(0), "shared_lifecycle");
+        let fairness = tracker.fairness();
+        assert_eq!(
+            fairness["shared_counter"],
+            OrderingFairness {
+                commits: 2,
+                overtaken: 1,
+                overtaken_by_ms: 10,
+            }
+        );
+        assert_eq!(fairness["shared_counter"].overtaken_rate(), 0.5);
+        assert!(!fairness.contains_key("shared_lifecycle"));
+    }
+
+    #[test]
+    fn test_build_latency() {
+        let mut build_latency = BuildLatency::new();
+        // Sub-millisecond builds are kept in microseconds
+        build_latency.record_build(Duration::from_micros(250));
+        assert_eq!(build_latency.build_us.histogram.max(), 250);
+        let mut sample = BuildLatency::new();
+        sample.record_build(Duration::from_micros(750));
+        sample.end_to_end_ms.record(Duration::from_millis(40));
+        build_latency.update(&sample);
+        assert_eq!(build_latency.build_us.histogram.len(), 2);
+        assert_eq!(build_latency.end_to_end_ms.histogram.len(), 1);
+        build_latency.reset();
+        assert!(build_latency.build_us.histogram.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_detector() {
+        let mut detector = DuplicateDetector::default();
+        let digest = TransactionDigest::random();
+        assert_eq!(detector.record(digest, "shared_counter"), None);
+        assert_eq!(
+            detector.record(TransactionDigest::random(), "shared_counter"),
+            None
+        );
+        let duplicate = detector.record(digest, "transfer_object").unwrap();
+        assert_eq!(duplicate.first_workload, "shared_counter");
+        assert_eq!(detector.duplicates(), vec![duplicate]);
+    }
+
+    #[test]
+    fn test_spend_budget() {
+        let mut budget = SpendBudget::new(1000);
+        assert!(!budget.record(600));
+        assert!(budget.record(600));
+        // Only the first transaction over the cap reports it
+        assert!(!budget.record(600));
+        assert_eq!(budget.spent, 1800);
+        assert!(budget.reached);
+    }
+
+    #[test]
+    fn test_projected_depletion() {
+        // 1000 spent in 10s, 5000 left lasts another 50s
+        assert_eq!(
+            projected_depletion(5000, 1000, Duration::from_secs(10)),
+            Some(Duration::from_secs(50))
+        );
+        assert_eq!(projected_depletion(5000, 0, Duration::from_secs(10)), None);
+        assert_eq!(projected_depletion(5000, 1000, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_slowest_transactions() {
+        let transaction = |latency_ms| SlowTransaction {
+            digest: TransactionDigest::random(),
+            workload: "transfer_object".to_string(),
+            latency: Duration::from_millis(latency_ms),
+            last_attempt: Duration::from_millis(latency_ms),
+            inputs: vec![ObjectID::random()],
+        };
+        let mut slowest = SlowestTransactions::new(3);
+        for latency_ms in [50, 10, 70, 30, 90, 20] {
+            slowest.record(transaction(latency_ms));
+        }
+        let latencies: Vec<u128> = slowest
+            .to_sorted_vec()
+            .iter()
+            .map(|t| t.latency.as_millis())
+            .collect();
+        assert_eq!(latencies, vec![90, 70, 50]);
+        assert!(!slowest.keeps(Duration::from_millis(50)));
+        assert!(slowest.keeps(Duration::from_millis(51)));
+
+        // Nothing is kept without --dump-slowest
+        let mut disabled = SlowestTransactions::new(0);
+        disabled.record(transaction(100));
+        assert!(disabled.to_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn test_write_hgrm() {
+        let mut latency = HistogramWrapper::new();
+        for ms in 1..=100 {
+            latency.record(Duration::from_millis(ms));
+        }
+        let mut out = vec![];
+        latency.write_hgrm(&mut out).unwrap();
+        let hgrm = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = hgrm.lines().collect();
+        assert_eq!(
+            lines[0],
+            "       Value     Percentile TotalCount 1/(1-Percentile)"
+        );
+        assert_eq!(lines[1], "");
+        // The last percentile line reaches the max and counts everything
+        let last = lines[lines.len() - 4];
+        assert!(
+            last.starts_with("     100.000 1.000000000000        100"),
+            "{}",
+            last
+        );
+        assert!(hgrm.contains("#[Max     =      100.000, Total count    =          100]"));
+        assert!(hgrm.ends_with("SubBuckets     =         2048]\n"));
+    }
+}
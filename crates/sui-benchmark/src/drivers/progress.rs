@@ -0,0 +1,127 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Interval;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Logical progress of a run over all its sessions, saved periodically to
+/// the --resume file so an interrupted run continues where it left off.
+/// Gas coins and payloads are not saved, a resumed session generates them
+/// again from the primary gas coin
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchProgress {
+    // Time spent running transactions
+    pub elapsed: Duration,
+    pub num_success: u64,
+    pub num_error: u64,
+    pub num_timeout: u64,
+    // Successful transactions of each workload type
+    pub workload_success: BTreeMap<String, u64>,
+    // Seed the sessions derive their rng seed from, when the run is seeded
+    pub seed: Option<u64>,
+    // Sessions started so far, the interrupted ones included
+    pub sessions: u64,
+}
+
+impl BenchProgress {
+    /// Progress saved at `path`, or a new run if there is no file yet
+    pub fn load_or_default(path: &str) -> anyhow::Result<Self> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read progress file {}", path))?;
+        serde_json::from_str(&data).with_context(|| format!("Invalid progress file {}", path))
+    }
+
+    /// Writes to a temporary file first, so an interruption never leaves a
+    /// truncated progress file behind
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to write progress file {}", path))
+    }
+
+    /// Starts a new session, returning the rng seed it runs with. Every
+    /// session gets its own seed so resumed sessions do not rebuild the
+    /// transactions of earlier ones
+    pub fn start_session(&mut self, seed: Option<u64>) -> Option<u64> {
+        if self.seed.is_none() {
+            self.seed = seed;
+        }
+        let session = self.sessions;
+        self.sessions += 1;
+        self.seed.map(|seed| seed.wrapping_add(session))
+    }
+
+    pub fn record(&mut self, workload: &str, num_success: u64, num_error: u64, num_timeout: u64) {
+        self.num_success += num_success;
+        self.num_error += num_error;
+        self.num_timeout += num_timeout;
+        *self
+            .workload_success
+            .entry(workload.to_string())
+            .or_default() += num_success;
+    }
+
+    /// Part of `run_duration` earlier sessions have not run yet
+    pub fn remaining(&self, run_duration: Interval) -> Interval {
+        match run_duration {
+            Interval::Count(count) => Interval::Count(
+                count.saturating_sub(self.num_success + self.num_error + self.num_timeout),
+            ),
+            Interval::Time(Duration::MAX) => run_duration,
+            Interval::Time(duration) => Interval::Time(duration.saturating_sub(self.elapsed)),
+        }
+    }
+}
+
+/// Progress file of --resume with the progress of the run when this
+/// session started
+#[derive(Debug, Clone)]
+pub struct ProgressFile {
+    pub path: String,
+    pub progress: BenchProgress,
+}
+
+/// Whether nothing of the run is left
+pub fn is_complete(remaining: Interval) -> bool {
+    match remaining {
+        Interval::Count(count) => count == 0,
+        Interval::Time(duration) => duration.is_zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+        let path = path.to_str().unwrap();
+        let mut progress = BenchProgress::load_or_default(path).unwrap();
+        assert_eq!(progress.start_session(Some(7)), Some(7));
+        progress.record("shared_counter", 80, 15, 5);
+        progress.elapsed = Duration::from_secs(40);
+        progress.save(path).unwrap();
+
+        let mut resumed = BenchProgress::load_or_default(path).unwrap();
+        assert_eq!(resumed, progress);
+        // The seed of the first session is kept, later sessions move on
+        assert_eq!(resumed.start_session(Some(9)), Some(8));
+        assert_eq!(resumed.remaining(Interval::Count(150)), Interval::Count(50));
+        assert_eq!(
+            resumed.remaining(Interval::Time(Duration::from_secs(60))),
+            Interval::Time(Duration::from_secs(20))
+        );
+        assert!(is_complete(resumed.remaining(Interval::Count(100))));
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::inflight_tuner::FULL_WINDOW;
+use std::time::Duration;
+
+/// Issue rate climbing linearly from `start_qps` to the target qps over
+/// `duration`. The in-flight window of each worker is scaled the same way,
+/// so --in-flight-ratio and --max-in-flight are only reached at the end of
+/// the ramp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RampUp {
+    // Share of the target qps issued at the start, in permille
+    start_permille: u64,
+    duration: Duration,
+}
+
+impl RampUp {
+    pub fn new(start_qps: u64, target_qps: u64, duration: Duration) -> Self {
+        Self {
+            start_permille: (start_qps * FULL_WINDOW)
+                .checked_div(target_qps)
+                .unwrap_or(FULL_WINDOW)
+                .min(FULL_WINDOW),
+            duration,
+        }
+    }
+
+    /// Share of the target qps issued `elapsed` into the run, in permille
+    pub fn permille(&self, elapsed: Duration) -> u64 {
+        if elapsed >= self.duration {
+            return FULL_WINDOW;
+        }
+        let climbed = (FULL_WINDOW - self.start_permille) as u128 * elapsed.as_millis()
+            / self.duration.as_millis().max(1);
+        self.start_permille + climbed as u64
+    }
+}
+
+/// Skips the request ticks of a worker above the ramp rate. Every tick
+/// earns the current permille and one costing FULL_WINDOW is issued
+#[derive(Debug, Default)]
+pub struct RampPacer {
+    credit: u64,
+}
+
+impl RampPacer {
+    /// Whether the tick at `permille` of the target qps issues a transaction
+    pub fn tick(&mut self, permille: u64) -> bool {
+        self.credit += permille;
+        if self.credit >= FULL_WINDOW {
+            self.credit -= FULL_WINDOW;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp_up() {
+        let ramp_up = RampUp::new(100, 1000, Duration::from_secs(10));
+        assert_eq!(ramp_up.permille(Duration::ZERO), 100);
+        assert_eq!(ramp_up.permille(Duration::from_secs(5)), 550);
+        assert_eq!(ramp_up.permille(Duration::from_secs(10)), FULL_WINDOW);
+        assert_eq!(ramp_up.permille(Duration::from_secs(60)), FULL_WINDOW);
+        // A start above the target issues the target right away
+        let ramp_up = RampUp::new(2000, 1000, Duration::from_secs(10));
+        assert_eq!(ramp_up.permille(Duration::ZERO), FULL_WINDOW);
+
+        let mut pacer = RampPacer::default();
+        let issued = (0..1000).filter(|_| pacer.tick(250)).count();
+        assert_eq!(issued, 250);
+    }
+}
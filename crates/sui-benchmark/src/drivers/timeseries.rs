@@ -0,0 +1,206 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context;
+use hdrhistogram::Histogram;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tokio::time;
+use tracing::error;
+
+pub const TIMESERIES_HEADER: &str = "timestamp_ms,workload,qps,in_flight,p50_ms,p99_ms";
+// Seconds the latency percentiles of a row are computed over
+const ROLLING_WINDOW: usize = 10;
+
+fn latency_histogram() -> Histogram<u64> {
+    Histogram::<u64>::new_with_max(120_000, 3).unwrap()
+}
+
+/// Completions of each workload over the current second, recorded by the
+/// workers, and the transactions each worker has in flight
+pub struct TimeseriesRecorder {
+    workers: Vec<(String, Arc<AtomicU64>)>,
+    second: Mutex<BTreeMap<String, Histogram<u64>>>,
+}
+
+impl TimeseriesRecorder {
+    /// `workloads` is the workload of each worker, by worker index
+    pub fn new(workloads: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            workers: workloads
+                .into_iter()
+                .map(|workload| (workload, Arc::new(AtomicU64::new(0))))
+                .collect(),
+            second: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Counter of the transactions `worker` has in flight
+    pub fn in_flight(&self, worker: usize) -> Arc<AtomicU64> {
+        self.workers[worker].1.clone()
+    }
+
+    pub fn record(&self, workload: &str, latency: Duration) {
+        self.second
+            .lock()
+            .unwrap()
+            .entry(workload.to_string())
+            .or_insert_with(latency_histogram)
+            .saturating_record(latency.as_millis().try_into().unwrap_or(u64::MAX));
+    }
+}
+
+/// One workload over one second
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeseriesRow {
+    pub timestamp_ms: u128,
+    pub workload: String,
+    pub qps: f64,
+    pub in_flight: u64,
+    // Percentiles over the last ROLLING_WINDOW seconds
+    pub p50_ms: u64,
+    pub p99_ms: u64,
+}
+
+impl TimeseriesRow {
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{:.2},{},{},{}",
+            self.timestamp_ms, self.workload, self.qps, self.in_flight, self.p50_ms, self.p99_ms
+        )
+    }
+}
+
+/// Turns the completions recorded every second into rows
+pub struct TimeseriesSampler {
+    recorder: Arc<TimeseriesRecorder>,
+    rolling: BTreeMap<String, VecDeque<Histogram<u64>>>,
+}
+
+impl TimeseriesSampler {
+    pub fn new(recorder: Arc<TimeseriesRecorder>) -> Self {
+        Self {
+            recorder,
+            rolling: BTreeMap::new(),
+        }
+    }
+
+    /// Rows of every workload for the `elapsed` since the last sample
+    pub fn sample(&mut self, elapsed: Duration, timestamp: SystemTime) -> Vec<TimeseriesRow> {
+        let mut second = std::mem::take(&mut *self.recorder.second.lock().unwrap());
+        let mut in_flight: BTreeMap<&str, u64> = BTreeMap::new();
+        for (workload, counter) in &self.recorder.workers {
+            *in_flight.entry(workload).or_default() += counter.load(Ordering::Relaxed);
+        }
+        let timestamp_ms = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        in_flight
+            .into_iter()
+            .map(|(workload, in_flight)| {
+                let latency = second.remove(workload).unwrap_or_else(latency_histogram);
+                let qps = latency.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+                let window = self.rolling.entry(workload.to_string()).or_default();
+                window.push_back(latency);
+                if window.len() > ROLLING_WINDOW {
+                    window.pop_front();
+                }
+                let mut rolling = latency_histogram();
+                for latency in window.iter() {
+                    rolling.add(latency).unwrap();
+                }
+                TimeseriesRow {
+                    timestamp_ms,
+                    workload: workload.to_string(),
+                    qps,
+                    in_flight,
+                    p50_ms: rolling.value_at_quantile(0.5),
+                    p99_ms: rolling.value_at_quantile(0.99),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Samples `recorder` every second and appends the rows to the csv file at
+/// `path`. The file is written on its own thread so slow disks never hold
+/// up the workers
+pub fn spawn_timeseries(
+    path: &str,
+    recorder: Arc<TimeseriesRecorder>,
+) -> anyhow::Result<JoinHandle<()>> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create timeseries file {}", path))?;
+    let (rows_tx, rows_rx) = mpsc::channel::<Vec<TimeseriesRow>>();
+    std::thread::spawn(move || {
+        let mut writer = BufWriter::new(file);
+        let mut result = writeln!(writer, "{}", TIMESERIES_HEADER);
+        // Dropping the receiver on error stops the sampling task
+        for rows in rows_rx {
+            result = result.and_then(|_| {
+                rows.iter()
+                    .try_for_each(|row| writeln!(writer, "{}", row.to_csv()))?;
+                writer.flush()
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+        if let Err(err) = result {
+            error!("Failed to write timeseries: {}", err);
+        }
+    });
+    Ok(tokio::spawn(async move {
+        let mut sampler = TimeseriesSampler::new(recorder);
+        let mut interval = time::interval(Duration::from_secs(1));
+        interval.tick().await;
+        let mut last_sample = Instant::now();
+        loop {
+            interval.tick().await;
+            let rows = sampler.sample(last_sample.elapsed(), SystemTime::now());
+            last_sample = Instant::now();
+            if rows_tx.send(rows).is_err() {
+                break;
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeseries_sampler() {
+        let recorder = Arc::new(TimeseriesRecorder::new(vec![
+            "shared_counter".to_string(),
+            "shared_counter".to_string(),
+            "transfer_object".to_string(),
+        ]));
+        recorder.in_flight(0).store(3, Ordering::Relaxed);
+        recorder.in_flight(1).store(2, Ordering::Relaxed);
+        for _ in 0..10 {
+            recorder.record("shared_counter", Duration::from_millis(100));
+        }
+        let mut sampler = TimeseriesSampler::new(recorder.clone());
+        let timestamp = UNIX_EPOCH + Duration::from_secs(1);
+        let rows = sampler.sample(Duration::from_secs(2), timestamp);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].to_csv(), "1000,shared_counter,5.00,5,100,100");
+        // Workloads without completions still get a row
+        assert_eq!(rows[1].to_csv(), "1000,transfer_object,0.00,0,0,0");
+
+        // Percentiles roll over the previous seconds
+        recorder.record("shared_counter", Duration::from_millis(300));
+        let rows = sampler.sample(Duration::from_secs(1), timestamp);
+        assert_eq!(rows[0].qps, 1.0);
+        assert_eq!(rows[0].p50_ms, 100);
+        assert_eq!(rows[0].p99_ms, 300);
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::future::join_all;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::warn;
+
+/// Validator metrics scraped at the start and end of the run. Each is
+/// summed over its label sets
+pub const VALIDATOR_METRICS: &[&str] = &[
+    "total_transaction_orders",
+    "total_transaction_certificates",
+    "total_transaction_effects",
+    "total_handle_certificate_attempts",
+    "num_shared_obj_tx",
+    "current_round",
+    "last_committed_round",
+];
+
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Change of the validator metrics of one endpoint over the run
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ValidatorMetricsDelta {
+    pub url: String,
+    pub deltas: BTreeMap<String, f64>,
+    // Why the endpoint could not be scraped at the start or end of the run
+    pub error: Option<String>,
+}
+
+/// Values of the metrics in `names` from a Prometheus text exposition
+pub fn parse_metrics(text: &str, names: &[&str]) -> BTreeMap<String, f64> {
+    let mut metrics = BTreeMap::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // name{labels} value [timestamp], label values may hold spaces
+        let (name, rest) = match line.find('{') {
+            Some(start) => match line.rfind('}') {
+                Some(end) if end > start => (&line[..start], &line[end + 1..]),
+                _ => continue,
+            },
+            None => match line.split_once(char::is_whitespace) {
+                Some((name, rest)) => (name, rest),
+                None => continue,
+            },
+        };
+        if !names.contains(&name) {
+            continue;
+        }
+        if let Some(Ok(value)) = rest.split_whitespace().next().map(str::parse::<f64>) {
+            *metrics.entry(name.to_string()).or_default() += value;
+        }
+    }
+    metrics
+}
+
+async fn scrape(client: &reqwest::Client, url: &str) -> anyhow::Result<BTreeMap<String, f64>> {
+    let text = client
+        .get(url)
+        .timeout(SCRAPE_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(parse_metrics(&text, VALIDATOR_METRICS))
+}
+
+/// Metrics of every endpoint, unreachable endpoints keep their error
+pub async fn scrape_validator_metrics(
+    urls: &[String],
+) -> Vec<(String, Result<BTreeMap<String, f64>, String>)> {
+    let client = reqwest::Client::new();
+    join_all(urls.iter().map(|url| {
+        let client = &client;
+        async move {
+            let res = scrape(client, url).await.map_err(|err| {
+                warn!("Failed to scrape validator metrics from {}: {}", url, err);
+                err.to_string()
+            });
+            (url.clone(), res)
+        }
+    }))
+    .await
+}
+
+/// Change of every metric between two scrapes of the same endpoints
+pub fn metrics_deltas(
+    start: &[(String, Result<BTreeMap<String, f64>, String>)],
+    end: &[(String, Result<BTreeMap<String, f64>, String>)],
+) -> Vec<ValidatorMetricsDelta> {
+    start
+        .iter()
+        .zip(end.iter())
+        .map(|((url, start), (_, end))| match (start, end) {
+            (Ok(start), Ok(end)) => ValidatorMetricsDelta {
+                url: url.clone(),
+                deltas: end
+                    .iter()
+                    .map(|(name, value)| {
+                        (
+                            name.clone(),
+                            value - start.get(name).copied().unwrap_or_default(),
+                        )
+                    })
+                    .collect(),
+                error: None,
+            },
+            (Err(err), _) | (_, Err(err)) => ValidatorMetricsDelta {
+                url: url.clone(),
+                deltas: BTreeMap::new(),
+                error: Some(err.clone()),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_metrics_deltas() {
+        let text = "# HELP total_transaction_effects Total effects\n\
+            # TYPE total_transaction_effects counter\n\
+            total_transaction_effects 120\n\
+            num_shared_obj_tx{tx_type=\"user tx\"} 10 1671000000000\n\
+            num_shared_obj_tx{tx_type=\"system\"} 2\n\
+            uptime 30\n";
+        let start = parse_metrics(text, VALIDATOR_METRICS);
+        assert_eq!(start.get("total_transaction_effects"), Some(&120.0));
+        assert_eq!(start.get("num_shared_obj_tx"), Some(&12.0));
+        assert!(!start.contains_key("uptime"));
+
+        let end = parse_metrics(
+            "total_transaction_effects 420\nnum_shared_obj_tx 12\n",
+            VALIDATOR_METRICS,
+        );
+        let url = "http://validator:9184/metrics".to_string();
+        let deltas = metrics_deltas(
+            &[
+                (url.clone(), Ok(start)),
+                ("down".to_string(), Ok(BTreeMap::new())),
+            ],
+            &[
+                (url, Ok(end)),
+                ("down".to_string(), Err("refused".to_string())),
+            ],
+        );
+        assert_eq!(
+            deltas[0].deltas.get("total_transaction_effects"),
+            Some(&300.0)
+        );
+        assert_eq!(deltas[0].deltas.get("num_shared_obj_tx"), Some(&0.0));
+        assert_eq!(deltas[1].error.as_deref(), Some("refused"));
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use comfy_table::{Cell, ContentArrangement, Row, Table};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Consecutive transactions below the threshold after which latency is stable
+pub const STABLE_RUN: usize = 20;
+// Transactions summarized by each row of the trajectory
+const TRAJECTORY_STEP: usize = 50;
+
+/// Latency of the first transactions of the run in completion order, to
+/// measure how long validators take to warm up, e.g. after a restart
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Warmup {
+    pub num_samples: usize,
+    pub threshold: Duration,
+    // Time into the run each transaction completed at, and its latency
+    pub samples: Vec<(Duration, Duration)>,
+}
+
+impl Warmup {
+    pub fn new(num_samples: usize, threshold: Duration) -> Self {
+        Self {
+            num_samples,
+            threshold,
+            samples: Vec::with_capacity(num_samples),
+        }
+    }
+
+    /// Records a transaction, once `num_samples` are recorded the rest of the
+    /// run is ignored
+    pub fn record(&mut self, elapsed: Duration, latency: Duration) {
+        if self.samples.len() < self.num_samples {
+            self.samples.push((elapsed, latency));
+        }
+    }
+
+    /// Time into the run at which the first of STABLE_RUN consecutive
+    /// transactions below the threshold completed
+    pub fn stabilized_after(&self) -> Option<Duration> {
+        let mut run = 0;
+        for (i, (_, latency)) in self.samples.iter().enumerate() {
+            if *latency < self.threshold {
+                run += 1;
+                if run == STABLE_RUN {
+                    return Some(self.samples[i + 1 - STABLE_RUN].0);
+                }
+            } else {
+                run = 0;
+            }
+        }
+        None
+    }
+
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "transactions",
+                "completed by (ms)",
+                "latency (p50)",
+                "latency (max)",
+            ]);
+        for (i, chunk) in self.samples.chunks(TRAJECTORY_STEP).enumerate() {
+            let mut latencies: Vec<u128> = chunk.iter().map(|(_, l)| l.as_millis()).collect();
+            latencies.sort_unstable();
+            let mut row = Row::new();
+            row.add_cell(Cell::new(format!(
+                "{}-{}",
+                i * TRAJECTORY_STEP + 1,
+                i * TRAJECTORY_STEP + chunk.len()
+            )));
+            row.add_cell(Cell::new(chunk.last().unwrap().0.as_millis()));
+            row.add_cell(Cell::new(latencies[latencies.len() / 2]));
+            row.add_cell(Cell::new(latencies[latencies.len() - 1]));
+            table.add_row(row);
+        }
+        table
+    }
+}
+
+/// Barrier between the discarded warmup of --warmup-secs and the measured
+/// phase. Workers stop issuing once the warmup is over, and the measured
+/// phase starts when every worker has no transaction left in flight
+pub struct WarmupBarrier {
+    pub duration: Duration,
+    num_workers: usize,
+    drained: AtomicUsize,
+    opened_at: Mutex<Option<Instant>>,
+}
+
+impl WarmupBarrier {
+    pub fn new(duration: Duration, num_workers: usize) -> Self {
+        Self {
+            duration,
+            num_workers,
+            drained: AtomicUsize::new(0),
+            opened_at: Mutex::new(None),
+        }
+    }
+
+    /// Called once by each worker when its warmup transactions completed,
+    /// the last one opens the barrier
+    pub fn drained(&self) {
+        // Hold the lock so the barrier is never seen open without its time
+        let mut opened_at = self.opened_at.lock().unwrap();
+        if self.drained.fetch_add(1, Ordering::Relaxed) + 1 == self.num_workers {
+            *opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Start of the measured phase once every worker drained
+    pub fn opened_at(&self) -> Option<Instant> {
+        *self.opened_at.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warmup_barrier_opens_once_drained() {
+        let barrier = WarmupBarrier::new(Duration::from_secs(10), 2);
+        barrier.drained();
+        assert!(barrier.opened_at().is_none());
+        barrier.drained();
+        assert!(barrier.opened_at().is_some());
+    }
+
+    #[test]
+    fn test_stabilized_after() {
+        let mut warmup = Warmup::new(100, Duration::from_millis(500));
+        // Slow transactions, then one dip below the threshold too short to count
+        for i in 0..10 {
+            warmup.record(Duration::from_secs(i), Duration::from_secs(2));
+        }
+        warmup.record(Duration::from_secs(10), Duration::from_millis(100));
+        warmup.record(Duration::from_secs(11), Duration::from_secs(1));
+        assert_eq!(warmup.stabilized_after(), None);
+        for i in 12..200 {
+            warmup.record(Duration::from_secs(i), Duration::from_millis(100));
+        }
+        assert_eq!(warmup.stabilized_after(), Some(Duration::from_secs(12)));
+        // Only the first transactions are kept
+        assert_eq!(warmup.samples.len(), 100);
+        assert_eq!(warmup.to_table().row_iter().count(), 2);
+    }
+}
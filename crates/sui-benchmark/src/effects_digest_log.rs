@@ -0,0 +1,128 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::retrying_proxy::RetryStats;
+use crate::{EffectsWait, ExecutionEffects, ObjectQuery, ValidatorProxy};
+use anyhow::Context;
+use async_trait::async_trait;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress, TransactionDigest};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+
+/// Header of the log, digests are written as Sui prints them, the
+/// transaction digest in Base58 and the effects digest in Base64
+pub const EFFECTS_DIGEST_LOG_HEADER: &str = "transaction_digest,effects_digest";
+
+/// Wraps any proxy and appends the transaction digest and effects digest of
+/// every successful execution to a CSV file, so an external tool can check
+/// the run against the chain. A retried transaction can appear more than once
+pub struct EffectsDigestLogProxy {
+    inner: Arc<dyn ValidatorProxy + Send + Sync>,
+    writer: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl EffectsDigestLogProxy {
+    pub fn new(inner: Arc<dyn ValidatorProxy + Send + Sync>, path: &Path) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create effects digest log {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", EFFECTS_DIGEST_LOG_HEADER)?;
+        Ok(Self {
+            inner,
+            writer: Arc::new(Mutex::new(writer)),
+        })
+    }
+
+    fn record(&self, digest: &TransactionDigest, effects: &ExecutionEffects) {
+        let effects_digest = match effects.digest() {
+            Some(effects_digest) => effects_digest,
+            None => {
+                tracing::error!("No effects digest to log for transaction {:?}", digest);
+                return;
+            }
+        };
+        let mut writer = self.writer.lock().unwrap();
+        // Flush every line so the log survives a crashed run
+        let res =
+            writeln!(writer, "{:?},{:?}", digest, effects_digest).and_then(|_| writer.flush());
+        if let Err(err) = res {
+            tracing::error!("Failed to log effects digest: {}", err);
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for EffectsDigestLogProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        self.inner.get_object(object_id).await
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        let digest = *tx.digest();
+        let res = self.inner.execute_transaction(tx).await;
+        if let Ok((_, effects)) = &res {
+            self.record(&digest, effects);
+        }
+        res
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.inner.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.inner.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            inner: Arc::from(self.inner.clone_new()),
+            writer: self.writer.clone(),
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        self.inner.get_validators().await
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.inner.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.inner.excluded_connections()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        self.inner.clock_skew()
+    }
+
+    fn retry_stats(&self) -> Option<RetryStats> {
+        self.inner.retry_stats()
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            inner: Arc::from(self.inner.with_effects_wait(wait)?),
+            writer: self.writer.clone(),
+        }))
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        self.inner.query_object(query).await
+    }
+}
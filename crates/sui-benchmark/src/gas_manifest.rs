@@ -0,0 +1,562 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::workloads::registry::WorkloadRegistry;
+use crate::workloads::{Gas, WorkloadInitGas, WorkloadPayloadGas};
+use crate::ValidatorProxy;
+use anyhow::{bail, Context, Result};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectRef, SuiAddress};
+use sui_types::crypto::{AccountKeyPair, KeypairTraits, SuiKeyPair};
+use sui_types::object::Owner;
+
+/// Coins looked up at once when checking or refreshing a manifest
+const MANIFEST_LOOKUP_CONCURRENCY: usize = 64;
+/// Stale coins listed in the error of a manifest that no longer matches
+const MAX_STALE_REPORTED: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ManifestCoin {
+    object_ref: ObjectRef,
+    owner: SuiAddress,
+}
+
+/// Gas coins generated for a run and the keypairs of their owners, saved
+/// to the --gas-manifest file so later runs with the same configuration
+/// reuse them instead of generating gas again
+#[derive(Serialize, Deserialize)]
+pub struct GasManifest {
+    keypairs: Vec<SuiKeyPair>,
+    // Coins by field of WorkloadInitGas and WorkloadPayloadGas, and by name
+    // for the registered workloads
+    init: BTreeMap<String, Vec<ManifestCoin>>,
+    payload: BTreeMap<String, Vec<ManifestCoin>>,
+    custom_init: BTreeMap<String, Vec<ManifestCoin>>,
+    custom_payload: BTreeMap<String, Vec<ManifestCoin>>,
+}
+
+impl GasManifest {
+    pub fn new(init_gas: &WorkloadInitGas, payload_gas: &WorkloadPayloadGas) -> Result<Self> {
+        let mut keypairs: HashMap<SuiAddress, Arc<AccountKeyPair>> = HashMap::new();
+        let mut coins = |gas: &[Gas]| -> Result<Vec<ManifestCoin>> {
+            gas.iter()
+                .map(|(object_ref, owner, keypair)| {
+                    let owner = owner.get_owner_address()?;
+                    keypairs.entry(owner).or_insert_with(|| keypair.clone());
+                    Ok(ManifestCoin {
+                        object_ref: *object_ref,
+                        owner,
+                    })
+                })
+                .collect()
+        };
+        // Destructured, so a new kind of gas cannot be left out of the manifest
+        let WorkloadInitGas {
+            shared_counter_init_gas,
+            collection_init_gas,
+            wrap_unwrap_init_gas,
+            shared_lifecycle_init_gas,
+            noop_init_gas,
+            send_to_object_init_gas,
+            object_fanout_init_gas,
+            nft_mint_init_gas,
+            batch_init_gas,
+            custom_init_gas,
+        } = init_gas;
+        let init = BTreeMap::from([
+            (
+                "shared_counter".to_string(),
+                coins(shared_counter_init_gas)?,
+            ),
+            ("collection".to_string(), coins(collection_init_gas)?),
+            ("wrap_unwrap".to_string(), coins(wrap_unwrap_init_gas)?),
+            (
+                "shared_lifecycle".to_string(),
+                coins(shared_lifecycle_init_gas)?,
+            ),
+            ("noop".to_string(), coins(noop_init_gas)?),
+            (
+                "send_to_object".to_string(),
+                coins(send_to_object_init_gas)?,
+            ),
+            ("object_fanout".to_string(), coins(object_fanout_init_gas)?),
+            ("nft_mint".to_string(), coins(nft_mint_init_gas)?),
+            ("batch".to_string(), coins(batch_init_gas)?),
+        ]);
+        let WorkloadPayloadGas {
+            transfer_tokens,
+            transfer_object_payload_gas,
+            shared_counter_payload_gas,
+            delegation_payload_gas,
+            read_payload_objects,
+            collection_payload_gas,
+            wrap_unwrap_payload_gas,
+            dependency_chain_payload_gas,
+            shared_lifecycle_payload_gas,
+            noop_payload_gas,
+            send_to_object_payload_gas,
+            object_fanout_payload_gas,
+            nft_mint_payload_gas,
+            batch_payload_gas,
+            coin_churn_payload_gas,
+            custom_payload_gas,
+        } = payload_gas;
+        let payload = BTreeMap::from([
+            ("transfer_tokens".to_string(), coins(transfer_tokens)?),
+            (
+                "transfer_object".to_string(),
+                coins(transfer_object_payload_gas)?,
+            ),
+            (
+                "shared_counter".to_string(),
+                coins(shared_counter_payload_gas)?,
+            ),
+            ("delegation".to_string(), coins(delegation_payload_gas)?),
+            ("read".to_string(), coins(read_payload_objects)?),
+            ("collection".to_string(), coins(collection_payload_gas)?),
+            ("wrap_unwrap".to_string(), coins(wrap_unwrap_payload_gas)?),
+            (
+                "dependency_chain".to_string(),
+                coins(dependency_chain_payload_gas)?,
+            ),
+            (
+                "shared_lifecycle".to_string(),
+                coins(shared_lifecycle_payload_gas)?,
+            ),
+            ("noop".to_string(), coins(noop_payload_gas)?),
+            (
+                "send_to_object".to_string(),
+                coins(send_to_object_payload_gas)?,
+            ),
+            (
+                "object_fanout".to_string(),
+                coins(object_fanout_payload_gas)?,
+            ),
+            ("nft_mint".to_string(), coins(nft_mint_payload_gas)?),
+            ("batch".to_string(), coins(batch_payload_gas)?),
+            ("coin_churn".to_string(), coins(coin_churn_payload_gas)?),
+        ]);
+        let mut custom_init = BTreeMap::new();
+        for (name, gas) in custom_init_gas {
+            custom_init.insert(name.to_string(), coins(gas)?);
+        }
+        let mut custom_payload = BTreeMap::new();
+        for (name, gas) in custom_payload_gas {
+            custom_payload.insert(name.to_string(), coins(gas)?);
+        }
+        Ok(Self {
+            keypairs: keypairs
+                .into_values()
+                .map(|keypair| SuiKeyPair::Ed25519(keypair.copy()))
+                .collect(),
+            init,
+            payload,
+            custom_init,
+            custom_payload,
+        })
+    }
+
+    /// Manifest saved at `path`, or None before the first run saved one
+    pub fn load(path: &str) -> Result<Option<Self>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read gas manifest {}", path))?;
+        serde_json::from_str(&data)
+            .map(Some)
+            .with_context(|| format!("Invalid gas manifest {}", path))
+    }
+
+    /// Writes to a temporary file first, so an interruption never leaves a
+    /// truncated manifest behind
+    pub fn save(&self, path: &str) -> Result<()> {
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_string(self)?)?;
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to write gas manifest {}", path))
+    }
+
+    fn coins(&self) -> impl Iterator<Item = &ManifestCoin> {
+        self.init
+            .values()
+            .chain(self.payload.values())
+            .chain(self.custom_init.values())
+            .chain(self.custom_payload.values())
+            .flatten()
+    }
+
+    fn coins_mut(&mut self) -> impl Iterator<Item = &mut ManifestCoin> {
+        self.init
+            .values_mut()
+            .chain(self.payload.values_mut())
+            .chain(self.custom_init.values_mut())
+            .chain(self.custom_payload.values_mut())
+            .flatten()
+    }
+
+    pub fn num_coins(&self) -> usize {
+        self.coins().count()
+    }
+
+    /// Accounts whose keypairs the manifest holds, a coin owned by any of
+    /// them can still pay for transactions
+    fn owners(&self) -> HashSet<SuiAddress> {
+        self.keypairs
+            .iter()
+            .map(|keypair| SuiAddress::from(&keypair.public()))
+            .collect()
+    }
+
+    /// Errors unless every coin still exists at the version the manifest
+    /// expects, owned by one of its accounts, i.e. nothing used them since it
+    /// was saved. Records the new owner of coins moved between its accounts
+    pub async fn verify(&mut self, proxy: &(dyn ValidatorProxy + Send + Sync)) -> Result<()> {
+        let owners = self.owners();
+        let owners = &owners;
+        let found: Vec<Result<(ObjectRef, SuiAddress), String>> = stream::iter(self.coins())
+            .map(|coin| async move {
+                let object_id = coin.object_ref.0;
+                match proxy.get_object(object_id).await {
+                    Ok(object) if object.compute_object_reference() == coin.object_ref => {
+                        match object.owner {
+                            Owner::AddressOwner(owner) if owners.contains(&owner) => {
+                                Ok((coin.object_ref, owner))
+                            }
+                            owner => Err(format!(
+                                "{} is owned by {}, not by an account of the manifest",
+                                object_id, owner
+                            )),
+                        }
+                    }
+                    Ok(object) => Err(format!(
+                        "{} is at version {} instead of {}",
+                        object_id,
+                        object.version(),
+                        coin.object_ref.1
+                    )),
+                    Err(err) => Err(format!("{} cannot be read: {}", object_id, err)),
+                }
+            })
+            .buffer_unordered(MANIFEST_LOOKUP_CONCURRENCY)
+            .collect()
+            .await;
+        let (found, stale): (Vec<_>, Vec<_>) = found.into_iter().partition(Result::is_ok);
+        if !stale.is_empty() {
+            bail!(
+                "{} of the {} coins of the gas manifest are stale, e.g. {}. Delete the \
+                manifest to generate new gas",
+                stale.len(),
+                self.num_coins(),
+                stale
+                    .into_iter()
+                    .filter_map(Result::err)
+                    .take(MAX_STALE_REPORTED)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let owner_of: HashMap<ObjectRef, SuiAddress> =
+            found.into_iter().filter_map(Result::ok).collect();
+        for coin in self.coins_mut() {
+            coin.owner = owner_of[&coin.object_ref];
+        }
+        Ok(())
+    }
+
+    /// Moves every coin to its current version and owner, so the manifest is
+    /// valid again after a run used its coins. Coins now owned by an account
+    /// the manifest holds no keypair for, or deleted, are left as they are,
+    /// making the manifest stale. Returns how many were
+    pub async fn refresh(&mut self, proxy: &(dyn ValidatorProxy + Send + Sync)) -> usize {
+        let owners = self.owners();
+        let owners = &owners;
+        let current: HashMap<ObjectRef, (ObjectRef, SuiAddress)> = stream::iter(self.coins())
+            .map(|coin| async move {
+                match proxy.get_object(coin.object_ref.0).await {
+                    Ok(object) => match object.owner {
+                        Owner::AddressOwner(owner) if owners.contains(&owner) => {
+                            Some((coin.object_ref, (object.compute_object_reference(), owner)))
+                        }
+                        _ => None,
+                    },
+                    Err(_) => None,
+                }
+            })
+            .buffer_unordered(MANIFEST_LOOKUP_CONCURRENCY)
+            .filter_map(|current| async move { current })
+            .collect()
+            .await;
+        let mut left_stale = 0;
+        for coin in self.coins_mut() {
+            match current.get(&coin.object_ref) {
+                Some((object_ref, owner)) => {
+                    coin.object_ref = *object_ref;
+                    coin.owner = *owner;
+                }
+                None => left_stale += 1,
+            }
+        }
+        left_stale
+    }
+
+    /// The gas of the manifest, to hand to the workloads as if generated
+    pub fn into_gas(
+        mut self,
+        registry: &WorkloadRegistry,
+    ) -> Result<(WorkloadInitGas, WorkloadPayloadGas)> {
+        let mut keypairs: HashMap<SuiAddress, Arc<AccountKeyPair>> = HashMap::new();
+        for keypair in self.keypairs.drain(..) {
+            match keypair {
+                SuiKeyPair::Ed25519(keypair) => {
+                    keypairs.insert(SuiAddress::from(keypair.public()), Arc::new(keypair));
+                }
+                other => bail!("Invalid key type in the gas manifest: {:?}", other),
+            }
+        }
+        let gas = |coins: Vec<ManifestCoin>| -> Result<Vec<Gas>> {
+            coins
+                .into_iter()
+                .map(|coin| match keypairs.get(&coin.owner) {
+                    Some(keypair) => Ok((
+                        coin.object_ref,
+                        Owner::AddressOwner(coin.owner),
+                        keypair.clone(),
+                    )),
+                    None => bail!("Gas manifest has no keypair for {}", coin.owner),
+                })
+                .collect()
+        };
+        let take = |coins: &mut BTreeMap<String, Vec<ManifestCoin>>, name: &str| {
+            gas(coins.remove(name).unwrap_or_default())
+        };
+        let mut init_gas = WorkloadInitGas {
+            shared_counter_init_gas: take(&mut self.init, "shared_counter")?,
+            collection_init_gas: take(&mut self.init, "collection")?,
+            wrap_unwrap_init_gas: take(&mut self.init, "wrap_unwrap")?,
+            shared_lifecycle_init_gas: take(&mut self.init, "shared_lifecycle")?,
+            noop_init_gas: take(&mut self.init, "noop")?,
+            send_to_object_init_gas: take(&mut self.init, "send_to_object")?,
+            object_fanout_init_gas: take(&mut self.init, "object_fanout")?,
+            nft_mint_init_gas: take(&mut self.init, "nft_mint")?,
+            batch_init_gas: take(&mut self.init, "batch")?,
+            custom_init_gas: HashMap::new(),
+        };
+        let mut payload_gas = WorkloadPayloadGas {
+            transfer_tokens: take(&mut self.payload, "transfer_tokens")?,
+            transfer_object_payload_gas: take(&mut self.payload, "transfer_object")?,
+            shared_counter_payload_gas: take(&mut self.payload, "shared_counter")?,
+            delegation_payload_gas: take(&mut self.payload, "delegation")?,
+            read_payload_objects: take(&mut self.payload, "read")?,
+            collection_payload_gas: take(&mut self.payload, "collection")?,
+            wrap_unwrap_payload_gas: take(&mut self.payload, "wrap_unwrap")?,
+            dependency_chain_payload_gas: take(&mut self.payload, "dependency_chain")?,
+            shared_lifecycle_payload_gas: take(&mut self.payload, "shared_lifecycle")?,
+            noop_payload_gas: take(&mut self.payload, "noop")?,
+            send_to_object_payload_gas: take(&mut self.payload, "send_to_object")?,
+            object_fanout_payload_gas: take(&mut self.payload, "object_fanout")?,
+            nft_mint_payload_gas: take(&mut self.payload, "nft_mint")?,
+            batch_payload_gas: take(&mut self.payload, "batch")?,
+            coin_churn_payload_gas: take(&mut self.payload, "coin_churn")?,
+            custom_payload_gas: HashMap::new(),
+        };
+        if let Some(name) = self.init.keys().chain(self.payload.keys()).next() {
+            bail!("Gas manifest holds coins of unknown gas {}", name);
+        }
+        // Registered workloads are looked up by name, the manifest only
+        // holds their names as strings
+        for (custom, registered) in [
+            (self.custom_init, &mut init_gas.custom_init_gas),
+            (self.custom_payload, &mut payload_gas.custom_payload_gas),
+        ] {
+            for (name, coins) in custom {
+                match registry.get(&name) {
+                    Some(factory) => {
+                        registered.insert(factory.name(), gas(coins)?);
+                    }
+                    None => bail!(
+                        "Gas manifest holds coins of workload {} which is not registered",
+                        name
+                    ),
+                }
+            }
+        }
+        Ok((init_gas, payload_gas))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::get_key_pair;
+    use crate::{EffectsWait, ExecutionEffects};
+    use anyhow::anyhow;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use sui_json_rpc_types::SuiCertifiedTransaction;
+    use sui_types::base_types::{random_object_ref, EpochId, ObjectID, SequenceNumber};
+    use sui_types::committee::Committee;
+    use sui_types::messages::Transaction;
+    use sui_types::object::Object;
+
+    /// Serves the objects it holds and nothing else
+    struct ObjectsProxy {
+        committee: Committee,
+        objects: Arc<Mutex<HashMap<ObjectID, Object>>>,
+    }
+
+    #[async_trait]
+    impl ValidatorProxy for ObjectsProxy {
+        async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+            self.objects
+                .lock()
+                .unwrap()
+                .get(&object_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("object {object_id} does not exist"))
+        }
+
+        async fn execute_transaction(
+            &self,
+            tx: Transaction,
+        ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+            Err(anyhow!(
+                "tx {:?} is not executed by this proxy",
+                tx.digest()
+            ))
+        }
+
+        fn clone_committee(&self) -> Committee {
+            self.committee.clone()
+        }
+
+        fn get_current_epoch(&self) -> EpochId {
+            0
+        }
+
+        fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+            Box::new(Self {
+                committee: self.committee.clone(),
+                objects: self.objects.clone(),
+            })
+        }
+
+        async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+            Ok(vec![])
+        }
+
+        fn effects_wait(&self) -> EffectsWait {
+            EffectsWait::Certified
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coins_moved_between_manifest_accounts() {
+        let (first, first_keypair): (_, AccountKeyPair) = get_key_pair();
+        let (second, second_keypair): (_, AccountKeyPair) = get_key_pair();
+        let second_keypair = Arc::new(second_keypair);
+        let coin = |id: ObjectID, version: u64, owner: SuiAddress| {
+            Object::with_id_owner_version_for_testing(id, SequenceNumber::from(version), owner)
+        };
+        let moved = coin(ObjectID::random(), 1, first);
+        let kept = coin(ObjectID::random(), 1, second);
+        let payload_gas = WorkloadPayloadGas {
+            noop_payload_gas: vec![
+                (
+                    moved.compute_object_reference(),
+                    moved.owner,
+                    Arc::new(first_keypair),
+                ),
+                (
+                    kept.compute_object_reference(),
+                    kept.owner,
+                    second_keypair.clone(),
+                ),
+            ],
+            ..Default::default()
+        };
+        let mut manifest = GasManifest::new(&WorkloadInitGas::default(), &payload_gas).unwrap();
+
+        // A run hands the first coin to the other account of the manifest
+        let moved = coin(moved.id(), 2, second);
+        let objects: HashMap<ObjectID, Object> = [moved.clone(), kept.clone()]
+            .into_iter()
+            .map(|object| (object.id(), object))
+            .collect();
+        let objects = Arc::new(Mutex::new(objects));
+        let proxy = ObjectsProxy {
+            committee: Committee::new_simple_test_committee().0,
+            objects: objects.clone(),
+        };
+        assert_eq!(manifest.refresh(&proxy).await, 0);
+        manifest.verify(&proxy).await.unwrap();
+        let (_, payload_gas) = manifest.into_gas(&WorkloadRegistry::default()).unwrap();
+        let (object_ref, owner, keypair) = payload_gas
+            .noop_payload_gas
+            .iter()
+            .find(|(object_ref, _, _)| object_ref.0 == moved.id())
+            .unwrap();
+        assert_eq!(*object_ref, moved.compute_object_reference());
+        assert_eq!(*owner, Owner::AddressOwner(second));
+        assert_eq!(keypair.public(), second_keypair.public());
+    }
+
+    #[tokio::test]
+    async fn test_coins_of_other_accounts_are_stale() {
+        let (owner, keypair): (_, AccountKeyPair) = get_key_pair();
+        let object = Object::with_id_owner_version_for_testing(
+            ObjectID::random(),
+            SequenceNumber::from(1),
+            owner,
+        );
+        let payload_gas = WorkloadPayloadGas {
+            noop_payload_gas: vec![(
+                object.compute_object_reference(),
+                object.owner,
+                Arc::new(keypair),
+            )],
+            ..Default::default()
+        };
+        let mut manifest = GasManifest::new(&WorkloadInitGas::default(), &payload_gas).unwrap();
+        let mut stolen = object.clone();
+        stolen.owner = Owner::AddressOwner(SuiAddress::random_for_testing_only());
+        let proxy = ObjectsProxy {
+            committee: Committee::new_simple_test_committee().0,
+            objects: Arc::new(Mutex::new([(object.id(), stolen)].into_iter().collect())),
+        };
+        assert!(manifest.verify(&proxy).await.is_err());
+        assert_eq!(manifest.refresh(&proxy).await, 1);
+    }
+
+    #[test]
+    fn test_gas_manifest_round_trip() {
+        let (owner, keypair): (_, AccountKeyPair) = get_key_pair();
+        let keypair = Arc::new(keypair);
+        let coin = (
+            random_object_ref(),
+            Owner::AddressOwner(owner),
+            keypair.clone(),
+        );
+        let mut init_gas = WorkloadInitGas::default();
+        init_gas.noop_init_gas = vec![coin.clone()];
+        let mut payload_gas = WorkloadPayloadGas::default();
+        payload_gas.coin_churn_payload_gas = vec![coin.clone(), coin.clone()];
+
+        let manifest = GasManifest::new(&init_gas, &payload_gas).unwrap();
+        assert_eq!(manifest.num_coins(), 3);
+        let manifest: GasManifest =
+            serde_json::from_str(&serde_json::to_string(&manifest).unwrap()).unwrap();
+        let (init_gas, payload_gas) = manifest.into_gas(&WorkloadRegistry::default()).unwrap();
+        assert_eq!(init_gas.noop_init_gas.len(), 1);
+        assert!(init_gas.shared_counter_init_gas.is_empty());
+        let (object_ref, loaded_owner, loaded_keypair) = &payload_gas.coin_churn_payload_gas[1];
+        assert_eq!(*object_ref, coin.0);
+        assert_eq!(*loaded_owner, Owner::AddressOwner(owner));
+        assert_eq!(loaded_keypair.public(), keypair.public());
+    }
+}
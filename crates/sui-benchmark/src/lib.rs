@@ -2,9 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 use anyhow::bail;
 use async_trait::async_trait;
+use clock_skew::ClockSkew;
+use connection_health::{ConnectionExclusion, ConnectionHealth, CONNECTION_FAILURE_THRESHOLD};
 use embedded_reconfig_observer::EmbeddedReconfigObserver;
 use fullnode_reconfig_observer::FullNodeReconfigObserver;
 use prometheus::Registry;
+use retrying_proxy::RetryStats;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{collections::BTreeMap, sync::Arc};
 use sui_config::genesis::Genesis;
 use sui_config::NetworkConfig;
@@ -17,7 +26,8 @@ use sui_core::{
 };
 use sui_json_rpc_types::{SuiCertifiedTransaction, SuiObjectRead, SuiTransactionEffects};
 use sui_sdk::{SuiClient, SuiClientBuilder};
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{SuiAddress, TransactionDigest, TransactionEffectsDigest};
+use sui_types::gas::GasCostSummary;
 use sui_types::sui_system_state::SuiSystemState;
 use sui_types::{
     base_types::ObjectID,
@@ -33,17 +43,33 @@ use sui_types::{
 use tracing::{error, info};
 
 pub mod benchmark_setup;
+pub mod clock_skew;
+pub mod connection_health;
+pub mod delaying_proxy;
 pub mod drivers;
+pub mod effects_digest_log;
 pub mod embedded_reconfig_observer;
 pub mod fullnode_reconfig_observer;
+pub mod gas_manifest;
+pub mod limiting_proxy;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock_proxy;
 pub mod options;
+pub mod read_routing_proxy;
+pub mod recording_proxy;
+pub mod retrying_proxy;
+pub mod rng;
+#[cfg(msim)]
+pub mod simulator_proxy;
 pub mod system_state_observer;
+pub mod teardown;
 pub mod util;
 pub mod workloads;
 
 /// A wrapper on execution results to accommodate different types of
 /// responses from LocalValidatorAggregatorProxy and FullNodeProxy
 #[allow(clippy::large_enum_variant)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ExecutionEffects {
     CertifiedTransactionEffects(CertifiedTransactionEffects),
     SuiTransactionEffects(SuiTransactionEffects),
@@ -78,6 +104,33 @@ impl ExecutionEffects {
         }
     }
 
+    pub fn unwrapped(&self) -> Vec<(ObjectRef, Owner)> {
+        match self {
+            ExecutionEffects::CertifiedTransactionEffects(certified_effects) => {
+                certified_effects.data().unwrapped.clone()
+            }
+            ExecutionEffects::SuiTransactionEffects(sui_tx_effects) => sui_tx_effects
+                .unwrapped
+                .clone()
+                .into_iter()
+                .map(|refe| (refe.reference.to_object_ref(), refe.owner))
+                .collect(),
+        }
+    }
+
+    pub fn gas_used(&self) -> GasCostSummary {
+        match self {
+            ExecutionEffects::CertifiedTransactionEffects(certified_effects) => {
+                certified_effects.data().gas_used.clone()
+            }
+            ExecutionEffects::SuiTransactionEffects(sui_tx_effects) => GasCostSummary {
+                computation_cost: sui_tx_effects.gas_used.computation_cost,
+                storage_cost: sui_tx_effects.gas_used.storage_cost,
+                storage_rebate: sui_tx_effects.gas_used.storage_rebate,
+            },
+        }
+    }
+
     pub fn quorum_sig(&self) -> Option<&AuthorityStrongQuorumSignInfo> {
         match self {
             ExecutionEffects::CertifiedTransactionEffects(certified_effects) => {
@@ -87,6 +140,28 @@ impl ExecutionEffects {
         }
     }
 
+    /// Checks the quorum of validator signatures on the effects against `committee`
+    pub fn verify_signatures(&self, committee: &Committee) -> anyhow::Result<()> {
+        match self {
+            ExecutionEffects::CertifiedTransactionEffects(certified_effects) => {
+                Ok(certified_effects.verify_signature(committee)?)
+            }
+            ExecutionEffects::SuiTransactionEffects(_) => {
+                bail!("Effects returned by a fullnode are not signed")
+            }
+        }
+    }
+
+    /// Digest of the signed effects, fullnode responses do not carry them
+    pub fn digest(&self) -> Option<TransactionEffectsDigest> {
+        match self {
+            ExecutionEffects::CertifiedTransactionEffects(certified_effects) => {
+                Some(*certified_effects.digest())
+            }
+            ExecutionEffects::SuiTransactionEffects(_) => None,
+        }
+    }
+
     pub fn gas_object(&self) -> (ObjectRef, Owner) {
         match self {
             ExecutionEffects::CertifiedTransactionEffects(certified_effects) => {
@@ -100,6 +175,50 @@ impl ExecutionEffects {
     }
 }
 
+/// A read-only query issued by the read workload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ObjectQuery {
+    GetObject(ObjectID),
+    GetOwnedObjects(SuiAddress),
+}
+
+/// What the effects returned by `ValidatorProxy::execute_transaction` wait
+/// for, and so what the measured latency covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffectsWait {
+    // The transaction is executed by the node serving the request
+    Local,
+    // A quorum of validators signed the effects
+    Certified,
+    // The transaction is included in a checkpoint
+    Checkpoint,
+}
+
+impl FromStr for EffectsWait {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(EffectsWait::Local),
+            "certified" => Ok(EffectsWait::Certified),
+            "checkpoint" => Ok(EffectsWait::Checkpoint),
+            _ => Err(format!(
+                "Unknown effects wait {s}, expected local, certified or checkpoint"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EffectsWait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EffectsWait::Local => write!(f, "local"),
+            EffectsWait::Certified => write!(f, "certified"),
+            EffectsWait::Checkpoint => write!(f, "checkpoint"),
+        }
+    }
+}
+
 #[async_trait]
 pub trait ValidatorProxy {
     async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error>;
@@ -116,11 +235,60 @@ pub trait ValidatorProxy {
     fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync>;
 
     async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error>;
+
+    /// What the effects returned by `execute_transaction` wait for
+    fn effects_wait(&self) -> EffectsWait;
+
+    /// A new proxy whose effects wait for `wait`. Proxies only support the
+    /// mode they execute with unless they override this.
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        if wait != self.effects_wait() {
+            bail!(
+                "Effects wait {} is not supported by this proxy, it waits for {}",
+                wait,
+                self.effects_wait()
+            );
+        }
+        Ok(self.clone_new())
+    }
+
+    /// Issues a read-only query. Only proxies backed by a fullnode
+    /// serve queries, validator-only proxies return an error.
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        bail!(
+            "Object queries are not supported by this proxy: {:?}",
+            query
+        )
+    }
+
+    /// Connections excluded so far after failing transactions persistently
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        vec![]
+    }
+
+    /// Finality latency checked against checkpoint timestamps so far, only
+    /// kept by proxies waiting for checkpoints
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        None
+    }
+
+    /// Outcome of the transactions retried so far, only kept by proxies
+    /// retrying transient errors
+    fn retry_stats(&self) -> Option<RetryStats> {
+        None
+    }
 }
 
 pub struct LocalValidatorAggregatorProxy {
-    _qd_handler: QuorumDriverHandler<NetworkAuthorityClient>,
-    qd: Arc<QuorumDriver<NetworkAuthorityClient>>,
+    // One quorum driver per connection, each with its own channels to every validator
+    qd_handlers: Vec<QuorumDriverHandler<NetworkAuthorityClient>>,
+    qds: Vec<Arc<QuorumDriver<NetworkAuthorityClient>>>,
+    next_qd: AtomicUsize,
+    // Shared by the clones of the proxy
+    health: Arc<ConnectionHealth>,
 }
 
 impl LocalValidatorAggregatorProxy {
@@ -128,70 +296,116 @@ impl LocalValidatorAggregatorProxy {
         genesis: &Genesis,
         registry: &Registry,
         reconfig_fullnode_rpc_url: Option<&str>,
+        num_connections: usize,
     ) -> Self {
-        let (aggregator, _) = AuthorityAggregatorBuilder::from_genesis(genesis)
-            .with_registry(registry)
-            .build()
-            .unwrap();
-
-        Self::new_impl(aggregator, registry, reconfig_fullnode_rpc_url).await
+        let mut aggregators = vec![];
+        for i in 0..num_connections.max(1) {
+            let builder = AuthorityAggregatorBuilder::from_genesis(genesis);
+            // Metrics can only be registered once, so only the first connection reports them
+            let builder = if i == 0 {
+                builder.with_registry(registry)
+            } else {
+                builder
+            };
+            aggregators.push(builder.build().unwrap().0);
+        }
+        Self::new_impl(aggregators, registry, reconfig_fullnode_rpc_url).await
     }
 
     pub async fn from_network_config(
         configs: &NetworkConfig,
         registry: &Registry,
         reconfig_fullnode_rpc_url: Option<&str>,
+        num_connections: usize,
     ) -> Self {
-        let (aggregator, _) = AuthorityAggregatorBuilder::from_network_config(configs)
-            .with_registry(registry)
-            .build()
-            .unwrap();
-        Self::new_impl(aggregator, registry, reconfig_fullnode_rpc_url).await
+        let mut aggregators = vec![];
+        for i in 0..num_connections.max(1) {
+            let builder = AuthorityAggregatorBuilder::from_network_config(configs);
+            // Metrics can only be registered once, so only the first connection reports them
+            let builder = if i == 0 {
+                builder.with_registry(registry)
+            } else {
+                builder
+            };
+            aggregators.push(builder.build().unwrap().0);
+        }
+        Self::new_impl(aggregators, registry, reconfig_fullnode_rpc_url).await
     }
 
     async fn new_impl(
-        aggregator: AuthorityAggregator<NetworkAuthorityClient>,
+        aggregators: Vec<AuthorityAggregator<NetworkAuthorityClient>>,
         registry: &Registry,
         reconfig_fullnode_rpc_url: Option<&str>,
     ) -> Self {
         let quorum_driver_metrics = Arc::new(QuorumDriverMetrics::new(registry));
-        let qd_handler_builder =
-            QuorumDriverHandlerBuilder::new(Arc::new(aggregator.clone()), quorum_driver_metrics);
-
-        let qd_handler = (if let Some(reconfig_fullnode_rpc_url) = reconfig_fullnode_rpc_url {
+        if let Some(reconfig_fullnode_rpc_url) = reconfig_fullnode_rpc_url {
             info!(
                 "Using FullNodeReconfigObserver: {:?}",
                 reconfig_fullnode_rpc_url
             );
-            let committee_store = aggregator.clone_committee_store();
-            let reconfig_observer = Arc::new(
-                FullNodeReconfigObserver::new(
-                    reconfig_fullnode_rpc_url,
-                    committee_store,
-                    aggregator.safe_client_metrics_base.clone(),
-                    aggregator.metrics.clone(),
-                )
-                .await,
-            );
-            qd_handler_builder.with_reconfig_observer(reconfig_observer)
         } else {
             info!("Using EmbeddedReconfigObserver");
-            qd_handler_builder.with_reconfig_observer(Arc::new(EmbeddedReconfigObserver::new()))
-        })
-        .start();
+        }
+        info!("Using {} connection(s) per validator", aggregators.len());
+        let mut qd_handlers = vec![];
+        for aggregator in aggregators {
+            let qd_handler_builder = QuorumDriverHandlerBuilder::new(
+                Arc::new(aggregator.clone()),
+                quorum_driver_metrics.clone(),
+            );
+            let qd_handler = (if let Some(reconfig_fullnode_rpc_url) = reconfig_fullnode_rpc_url {
+                let committee_store = aggregator.clone_committee_store();
+                let reconfig_observer = Arc::new(
+                    FullNodeReconfigObserver::new(
+                        reconfig_fullnode_rpc_url,
+                        committee_store,
+                        aggregator.safe_client_metrics_base.clone(),
+                        aggregator.metrics.clone(),
+                    )
+                    .await,
+                );
+                qd_handler_builder.with_reconfig_observer(reconfig_observer)
+            } else {
+                qd_handler_builder.with_reconfig_observer(Arc::new(EmbeddedReconfigObserver::new()))
+            })
+            .start();
+            qd_handlers.push(qd_handler);
+        }
+        let health = Arc::new(ConnectionHealth::new(
+            qd_handlers.len(),
+            CONNECTION_FAILURE_THRESHOLD,
+        ));
+        Self::from_handlers(qd_handlers, health)
+    }
 
-        let qd = qd_handler.clone_quorum_driver();
+    fn from_handlers(
+        qd_handlers: Vec<QuorumDriverHandler<NetworkAuthorityClient>>,
+        health: Arc<ConnectionHealth>,
+    ) -> Self {
+        let qds = qd_handlers
+            .iter()
+            .map(|qd_handler| qd_handler.clone_quorum_driver())
+            .collect();
         Self {
-            _qd_handler: qd_handler,
-            qd,
+            qd_handlers,
+            qds,
+            next_qd: AtomicUsize::new(0),
+            health,
         }
     }
+
+    /// Quorum driver of the next healthy connection in round-robin order
+    fn next_qd(&self) -> (usize, &Arc<QuorumDriver<NetworkAuthorityClient>>) {
+        let i = self.next_qd.fetch_add(1, Ordering::Relaxed);
+        let i = self.health.next_healthy(i % self.qds.len());
+        (i, &self.qds[i])
+    }
 }
 
 #[async_trait]
 impl ValidatorProxy for LocalValidatorAggregatorProxy {
     async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
-        let auth_agg = self.qd.authority_aggregator().load();
+        let auth_agg = self.qds[0].authority_aggregator().load();
         match auth_agg.get_object_info_execute(object_id).await? {
             ObjectRead::Exists(_, object, _) => Ok(object),
             other => bail!("object {object_id} does not exist: {:?}", other),
@@ -204,48 +418,58 @@ impl ValidatorProxy for LocalValidatorAggregatorProxy {
     ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
         let tx_digest = *tx.digest();
         let tx = tx.verify()?;
-        let mut retry_cnt = 0;
-        while retry_cnt < 3 {
-            let ticket = self.qd.submit_transaction(tx.clone()).await?;
-            // The ticket only times out when QuorumDriver exceeds the retry times
-            match ticket.await {
-                Ok(resp) => {
-                    let QuorumDriverResponse {
-                        tx_cert,
-                        effects_cert,
-                    } = resp;
-                    return Ok((
-                        tx_cert.try_into().unwrap(),
-                        ExecutionEffects::CertifiedTransactionEffects(effects_cert.into()),
-                    ));
-                }
-                Err(err) => {
-                    error!(
-                        ?tx_digest,
-                        retry_cnt, "Transaction failed with err: {:?}", err
-                    );
-                    retry_cnt += 1;
+        let (connection, qd) = self.next_qd();
+        let res: anyhow::Result<_> = async {
+            let mut retry_cnt = 0;
+            while retry_cnt < 3 {
+                let ticket = qd.submit_transaction(tx.clone()).await?;
+                // The ticket only times out when QuorumDriver exceeds the retry times
+                match ticket.await {
+                    Ok(resp) => {
+                        let QuorumDriverResponse {
+                            tx_cert,
+                            effects_cert,
+                        } = resp;
+                        return Ok((
+                            tx_cert.try_into().unwrap(),
+                            ExecutionEffects::CertifiedTransactionEffects(effects_cert.into()),
+                        ));
+                    }
+                    Err(err) => {
+                        error!(
+                            ?tx_digest,
+                            retry_cnt, "Transaction failed with err: {:?}", err
+                        );
+                        retry_cnt += 1;
+                    }
                 }
             }
+            bail!("Transaction {:?} failed for {retry_cnt} times", tx_digest);
+        }
+        .await;
+        match &res {
+            Ok(_) => self.health.record_success(connection),
+            Err(err) => self.health.record_failure(connection, err),
         }
-        bail!("Transaction {:?} failed for {retry_cnt} times", tx_digest);
+        res
     }
 
     fn clone_committee(&self) -> Committee {
-        self.qd.clone_committee()
+        self.qds[0].clone_committee()
     }
 
     fn get_current_epoch(&self) -> EpochId {
-        self.qd.current_epoch()
+        self.qds[0].current_epoch()
     }
 
     fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
-        let qdh = self._qd_handler.clone_new();
-        let qd = qdh.clone_quorum_driver();
-        Box::new(Self {
-            _qd_handler: qdh,
-            qd,
-        })
+        Box::new(Self::from_handlers(
+            self.qd_handlers
+                .iter()
+                .map(|qd_handler| qd_handler.clone_new())
+                .collect(),
+            self.health.clone(),
+        ))
     }
 
     async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
@@ -259,20 +483,45 @@ impl ValidatorProxy for LocalValidatorAggregatorProxy {
             .map(|v| v.metadata.sui_address)
             .collect())
     }
+
+    fn effects_wait(&self) -> EffectsWait {
+        // The quorum driver returns once the effects are certified
+        EffectsWait::Certified
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.health.exclusions()
+    }
 }
 
+// How long a fullnode proxy waits for a transaction to be checkpointed
+const CHECKPOINT_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+const CHECKPOINT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 pub struct FullNodeProxy {
-    sui_client: SuiClient,
+    // One client per connection to the fullnode
+    sui_clients: Vec<SuiClient>,
+    next_client: AtomicUsize,
     committee: Committee,
+    effects_wait: EffectsWait,
+    // Shared by the clones of the proxy
+    health: Arc<ConnectionHealth>,
+    clock_skew: Arc<Mutex<ClockSkew>>,
 }
 
 impl FullNodeProxy {
-    pub async fn from_url(http_url: &str) -> Result<Self, anyhow::Error> {
-        // Each request times out after 60s (default value)
-        let sui_client = SuiClientBuilder::default()
-            .max_concurrent_requests(500_000)
-            .build(http_url)
-            .await?;
+    pub async fn from_url(http_url: &str, num_connections: usize) -> Result<Self, anyhow::Error> {
+        let mut sui_clients = vec![];
+        for _ in 0..num_connections.max(1) {
+            // Each request times out after 60s (default value)
+            sui_clients.push(
+                SuiClientBuilder::default()
+                    .max_concurrent_requests(500_000)
+                    .build(http_url)
+                    .await?,
+            );
+        }
+        let sui_client = &sui_clients[0];
 
         let resp = sui_client.read_api().get_committee_info(None).await?;
         let epoch = resp.epoch;
@@ -287,17 +536,101 @@ impl FullNodeProxy {
             )
         };
 
+        let health = Arc::new(ConnectionHealth::new(
+            sui_clients.len(),
+            CONNECTION_FAILURE_THRESHOLD,
+        ));
         Ok(Self {
-            sui_client,
+            sui_clients,
+            next_client: AtomicUsize::new(0),
             committee,
+            // The fullnode executes the transaction before responding
+            effects_wait: EffectsWait::Local,
+            health,
+            clock_skew: Arc::new(Mutex::new(ClockSkew::new())),
         })
     }
+
+    /// Client of the next healthy connection in round-robin order
+    fn sui_client(&self) -> &SuiClient {
+        &self.sui_clients[self.next_connection()]
+    }
+
+    fn next_connection(&self) -> usize {
+        let i = self.next_client.fetch_add(1, Ordering::Relaxed);
+        self.health.next_healthy(i % self.sui_clients.len())
+    }
+
+    /// Polls the checkpoints from `next_checkpoint` on until one includes
+    /// `tx_digest`, returns its sequence number
+    async fn wait_for_checkpoint(
+        sui_client: &SuiClient,
+        tx_digest: TransactionDigest,
+        mut next_checkpoint: u64,
+    ) -> Result<u64, anyhow::Error> {
+        let start = Instant::now();
+        while start.elapsed() < CHECKPOINT_WAIT_TIMEOUT {
+            let latest = sui_client
+                .read_api()
+                .get_latest_checkpoint_sequence_number()
+                .await?;
+            while next_checkpoint <= latest {
+                let contents = sui_client
+                    .read_api()
+                    .get_checkpoint_contents(next_checkpoint)
+                    .await?;
+                if contents.iter().any(|d| d.transaction == tx_digest) {
+                    return Ok(next_checkpoint);
+                }
+                next_checkpoint += 1;
+            }
+            tokio::time::sleep(CHECKPOINT_POLL_INTERVAL).await;
+        }
+        bail!(
+            "Transaction {:?} was not checkpointed within {:?}",
+            tx_digest,
+            CHECKPOINT_WAIT_TIMEOUT
+        );
+    }
+
+    /// Checks the `local` latency of a transaction submitted at `submitted`
+    /// against the timestamp of `checkpoint`, which includes it
+    async fn check_clock_skew(
+        &self,
+        sui_client: &SuiClient,
+        checkpoint: u64,
+        submitted: SystemTime,
+        local: Duration,
+    ) {
+        let summary = match sui_client
+            .read_api()
+            .get_checkpoint_summary(checkpoint)
+            .await
+        {
+            Ok(summary) => summary,
+            Err(err) => {
+                error!(
+                    "Failed to get the summary of checkpoint {}: {}",
+                    checkpoint, err
+                );
+                return;
+            }
+        };
+        let submitted_ms = submitted
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.clock_skew
+            .lock()
+            .unwrap()
+            .record(submitted_ms, summary.timestamp_ms, local);
+    }
 }
 
 #[async_trait]
 impl ValidatorProxy for FullNodeProxy {
     async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
-        match self.sui_client.read_api().get_object(object_id).await? {
+        match self.sui_client().read_api().get_object(object_id).await? {
             SuiObjectRead::Exists(sui_obj) => sui_obj.try_into(),
             other => bail!("object {object_id} does not exist: {:?}", other),
         }
@@ -309,35 +642,70 @@ impl ValidatorProxy for FullNodeProxy {
     ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
         let tx_digest = *tx.digest();
         let tx = tx.verify()?;
-        let mut retry_cnt = 0;
-        while retry_cnt < 10 {
-            // Fullnode could time out after WAIT_FOR_FINALITY_TIMEOUT (30s) in TransactionOrchestrator
-            // SuiClient times out after 60s
-            match self
-                .sui_client
-                .quorum_driver()
-                .execute_transaction(
-                    tx.clone(),
-                    // We need to use WaitForLocalExecution to make sure objects are updated on FN
-                    Some(ExecuteTransactionRequestType::WaitForLocalExecution),
-                )
-                .await
-            {
-                Ok(resp) => {
-                    let tx_cert = resp.tx_cert.unwrap();
-                    let effects = ExecutionEffects::SuiTransactionEffects(resp.effects.unwrap());
-                    return Ok((tx_cert, effects));
+        let connection = self.next_connection();
+        let sui_client = &self.sui_clients[connection];
+        let res: anyhow::Result<_> = async {
+            let submitted = SystemTime::now();
+            let start = Instant::now();
+            // The transaction can only land in checkpoints after the latest one
+            // at submission
+            let next_checkpoint = match self.effects_wait {
+                EffectsWait::Checkpoint => Some(
+                    sui_client
+                        .read_api()
+                        .get_latest_checkpoint_sequence_number()
+                        .await?
+                        + 1,
+                ),
+                _ => None,
+            };
+            let request_type = match self.effects_wait {
+                EffectsWait::Certified => ExecuteTransactionRequestType::WaitForEffectsCert,
+                // We need to use WaitForLocalExecution to make sure objects are updated on FN
+                EffectsWait::Local | EffectsWait::Checkpoint => {
+                    ExecuteTransactionRequestType::WaitForLocalExecution
                 }
-                Err(err) => {
-                    error!(
-                        ?tx_digest,
-                        retry_cnt, "Transaction failed with err: {:?}", err
-                    );
-                    retry_cnt += 1;
+            };
+            let mut retry_cnt = 0;
+            while retry_cnt < 10 {
+                // Fullnode could time out after WAIT_FOR_FINALITY_TIMEOUT (30s) in TransactionOrchestrator
+                // SuiClient times out after 60s
+                match sui_client
+                    .quorum_driver()
+                    .execute_transaction(tx.clone(), Some(request_type.clone()))
+                    .await
+                {
+                    Ok(resp) => {
+                        let tx_cert = resp.tx_cert.unwrap();
+                        let effects =
+                            ExecutionEffects::SuiTransactionEffects(resp.effects.unwrap());
+                        if let Some(next_checkpoint) = next_checkpoint {
+                            let checkpoint =
+                                Self::wait_for_checkpoint(sui_client, tx_digest, next_checkpoint)
+                                    .await?;
+                            let local = start.elapsed();
+                            self.check_clock_skew(sui_client, checkpoint, submitted, local)
+                                .await;
+                        }
+                        return Ok((tx_cert, effects));
+                    }
+                    Err(err) => {
+                        error!(
+                            ?tx_digest,
+                            retry_cnt, "Transaction failed with err: {:?}", err
+                        );
+                        retry_cnt += 1;
+                    }
                 }
             }
+            bail!("Transaction {:?} failed for {retry_cnt} times", tx_digest);
+        }
+        .await;
+        match &res {
+            Ok(_) => self.health.record_success(connection),
+            Err(err) => self.health.record_failure(connection, err),
         }
-        bail!("Transaction {:?} failed for {retry_cnt} times", tx_digest);
+        res
     }
 
     fn clone_committee(&self) -> Committee {
@@ -350,13 +718,59 @@ impl ValidatorProxy for FullNodeProxy {
 
     fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
         Box::new(Self {
-            sui_client: self.sui_client.clone(),
+            sui_clients: self.sui_clients.clone(),
+            next_client: AtomicUsize::new(0),
             committee: self.clone_committee(),
+            effects_wait: self.effects_wait,
+            health: self.health.clone(),
+            clock_skew: self.clock_skew.clone(),
         })
     }
 
     async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
-        let validators = self.sui_client.governance_api().get_validators().await?;
+        let validators = self.sui_client().governance_api().get_validators().await?;
         Ok(validators.into_iter().map(|v| v.sui_address).collect())
     }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.effects_wait
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            sui_clients: self.sui_clients.clone(),
+            next_client: AtomicUsize::new(0),
+            committee: self.clone_committee(),
+            effects_wait: wait,
+            health: self.health.clone(),
+            clock_skew: self.clock_skew.clone(),
+        }))
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.health.exclusions()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        let clock_skew = self.clock_skew.lock().unwrap();
+        (clock_skew.measurements > 0).then(|| clock_skew.clone())
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        match query {
+            ObjectQuery::GetObject(object_id) => {
+                self.sui_client().read_api().get_object(object_id).await?;
+            }
+            ObjectQuery::GetOwnedObjects(address) => {
+                self.sui_client()
+                    .read_api()
+                    .get_objects_owned_by_address(address)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
 }
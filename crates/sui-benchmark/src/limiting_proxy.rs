@@ -0,0 +1,134 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::retrying_proxy::RetryStats;
+use crate::{EffectsWait, ExecutionEffects, ObjectQuery, ValidatorProxy};
+use async_trait::async_trait;
+use std::sync::Arc;
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+use tokio::sync::Semaphore;
+
+/// Wraps any proxy and lets at most `limit` network calls through at once,
+/// shared by all its clones. Keeps workload init from flooding a fragile
+/// network with transactions
+pub struct LimitingProxy {
+    inner: Arc<dyn ValidatorProxy + Send + Sync>,
+    permits: Arc<Semaphore>,
+}
+
+impl LimitingProxy {
+    pub fn new(inner: Arc<dyn ValidatorProxy + Send + Sync>, limit: usize) -> Self {
+        Self {
+            inner,
+            permits: Arc::new(Semaphore::new(limit)),
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for LimitingProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        let _permit = self.permits.acquire().await?;
+        self.inner.get_object(object_id).await
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        let _permit = self.permits.acquire().await?;
+        self.inner.execute_transaction(tx).await
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.inner.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.inner.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            inner: Arc::from(self.inner.clone_new()),
+            permits: self.permits.clone(),
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        let _permit = self.permits.acquire().await?;
+        self.inner.get_validators().await
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.inner.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.inner.excluded_connections()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        self.inner.clock_skew()
+    }
+
+    fn retry_stats(&self) -> Option<RetryStats> {
+        self.inner.retry_stats()
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            inner: Arc::from(self.inner.with_effects_wait(wait)?),
+            permits: self.permits.clone(),
+        }))
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        let _permit = self.permits.acquire().await?;
+        self.inner.query_object(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delaying_proxy::{DelayingProxy, ProxyDelay};
+    use crate::recording_proxy::{RecordedCall, ReplayProxy};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[tokio::test]
+    async fn test_limiting_proxy() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let object_id = ObjectID::random();
+        let object = Object::immutable_with_id_for_testing(object_id);
+        let replay = ReplayProxy::from_calls(vec![
+            RecordedCall::Committee(committee),
+            RecordedCall::GetObject(object_id, Ok(object.clone())),
+        ])
+        .unwrap();
+        let delayed = DelayingProxy::new(
+            Arc::new(replay),
+            ProxyDelay::Fixed(Duration::from_millis(50)),
+        );
+        let proxy = LimitingProxy::new(Arc::new(delayed), 1);
+        let clone = proxy.clone_new();
+
+        // The clone shares the single permit, so the calls run one at a time
+        let start = Instant::now();
+        let (first, second) =
+            tokio::join!(proxy.get_object(object_id), clone.get_object(object_id));
+        assert_eq!(first.unwrap(), object);
+        assert_eq!(second.unwrap(), object);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::delaying_proxy::ProxyDelay;
+use crate::rng::bench_rng;
+use crate::{EffectsWait, ExecutionEffects, ValidatorProxy};
+use anyhow::bail;
+use async_trait::async_trait;
+use rand::Rng;
+use std::sync::Arc;
+use sui_core::authority_aggregator::AuthorityAggregator;
+use sui_core::test_authority_clients::LocalAuthorityClient;
+use sui_core::test_utils::init_local_authorities;
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::{Object, ObjectRead};
+use sui_types::sui_system_state::SuiSystemState;
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+
+// Validators of the in-memory committee, enough for a quorum to tolerate a fault
+const MOCK_COMMITTEE_SIZE: usize = 4;
+
+/// Executes transactions on a committee of in-memory authorities instead
+/// of a network, for tests of workloads and the driver. Effects are those
+/// of real execution, so they only depend on the transactions issued
+pub struct MockValidatorProxy {
+    aggregator: Arc<AuthorityAggregator<LocalAuthorityClient>>,
+    // Synthetic latency added to each transaction
+    latency: Option<ProxyDelay>,
+    // Probability with which a transaction fails without being executed
+    failure_rate: f64,
+}
+
+impl MockValidatorProxy {
+    /// Starts the authorities from a genesis holding `genesis_objects`,
+    /// e.g. the gas coins of the benchmark
+    pub async fn new(
+        genesis_objects: Vec<Object>,
+        latency: Option<ProxyDelay>,
+        failure_rate: f64,
+    ) -> anyhow::Result<Self> {
+        if !(0.0..=1.0).contains(&failure_rate) {
+            bail!("Failure rate {failure_rate} must be between 0 and 1");
+        }
+        let (aggregator, _, _, _) =
+            init_local_authorities(MOCK_COMMITTEE_SIZE, genesis_objects).await;
+        Ok(Self {
+            aggregator: Arc::new(aggregator),
+            latency,
+            failure_rate,
+        })
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for MockValidatorProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        match self.aggregator.get_object_info_execute(object_id).await? {
+            ObjectRead::Exists(_, object, _) => Ok(object),
+            other => bail!("object {object_id} does not exist: {:?}", other),
+        }
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        // Drawn before sleeping, so the draws follow the submission order
+        let (latency, fail) = {
+            let mut rng = bench_rng();
+            let latency = self.latency.map(|latency| latency.sample(&mut rng));
+            (latency, rng.gen_bool(self.failure_rate))
+        };
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        if fail {
+            bail!("Injected failure for tx {:?}", tx.digest());
+        }
+        let tx = tx.verify()?;
+        let (tx_cert, effects_cert) = self.aggregator.execute_transaction(&tx).await?;
+        Ok((
+            tx_cert.try_into().unwrap(),
+            ExecutionEffects::CertifiedTransactionEffects(effects_cert.into()),
+        ))
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.aggregator.committee.clone()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.aggregator.committee.epoch
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        // Clones share the authorities, so they see each other's transactions
+        Box::new(Self {
+            aggregator: self.aggregator.clone(),
+            latency: self.latency,
+            failure_rate: self.failure_rate,
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        let system_state = self.get_object(SUI_SYSTEM_STATE_OBJECT_ID).await?;
+        let move_obj = system_state.data.try_as_move().unwrap();
+        let result = bcs::from_bytes::<SuiSystemState>(move_obj.contents())?;
+        Ok(result
+            .validators
+            .active_validators
+            .into_iter()
+            .map(|v| v.metadata.sui_address)
+            .collect())
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        // Executed through the aggregator, which returns certified effects
+        EffectsWait::Certified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::test_utils::make_transfer_sui_transaction;
+    use sui_types::crypto::{get_key_pair, AccountKeyPair};
+
+    #[tokio::test]
+    async fn test_mock_validator_proxy() {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let gas = Object::with_owner_for_testing(sender);
+        let gas_id = gas.id();
+        let proxy = MockValidatorProxy::new(vec![gas], None, 0.0).await.unwrap();
+        assert_eq!(
+            proxy.get_validators().await.unwrap().len(),
+            MOCK_COMMITTEE_SIZE
+        );
+
+        let gas_ref = proxy
+            .get_object(gas_id)
+            .await
+            .unwrap()
+            .compute_object_reference();
+        let recipient = SuiAddress::random_for_testing_only();
+        let tx = make_transfer_sui_transaction(gas_ref, recipient, Some(1), sender, &keypair, None);
+        let (_, effects) = proxy.execute_transaction(tx.into_inner()).await.unwrap();
+        assert_eq!(effects.created().len(), 1);
+
+        // Failures are injected before execution, so they need no gas
+        let failing = MockValidatorProxy::new(vec![], None, 1.0).await.unwrap();
+        let tx = make_transfer_sui_transaction(gas_ref, recipient, Some(1), sender, &keypair, None);
+        assert!(failing.execute_transaction(tx.into_inner()).await.is_err());
+        assert!(MockValidatorProxy::new(vec![], None, 1.5).await.is_err());
+    }
+}
@@ -1,13 +1,27 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{bail, Context};
 use clap::*;
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-use strum_macros::EnumString;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumString};
 
+use crate::delaying_proxy::ProxyDelay;
+use crate::drivers::burst::BurstPattern;
+use crate::drivers::ramp_up::RampUp;
 use crate::drivers::Interval;
+use crate::drivers::Label;
+use crate::retrying_proxy::{RetryConfig, TransientError};
+use crate::util::AmountDistribution;
+use crate::workloads::custom_call::CustomCalls;
+use crate::workloads::delegation::DelegationTarget;
+use crate::workloads::workload::WorkloadType;
+use crate::EffectsWait;
 
-#[derive(Parser)]
+#[derive(Parser, serde::Serialize)]
 #[clap(name = "Stress Testing Framework")]
 pub struct Opts {
     /// Si&ze of the Sui committee.
@@ -61,6 +75,11 @@ pub struct Opts {
     /// Otherwise use EmbeddedReconfigObserver,
     #[clap(long, parse(try_from_str), default_value = "false", global = true)]
     pub use_fullnode_for_reconfig: bool,
+    /// Whether to serve the read workload's queries from the fullnode while
+    /// transactions execute through the validators. Only matters when
+    /// use_fullnode_for_execution is false
+    #[clap(long, parse(try_from_str), default_value = "false", global = true)]
+    pub use_fullnode_for_reads: bool,
     /// Default workload is 100% transfer object
     #[clap(subcommand)]
     pub run_spec: RunSpec,
@@ -68,8 +87,11 @@ pub struct Opts {
     pub server_metric_port: u16,
     #[clap(long, default_value = "127.0.0.1", global = true)]
     pub client_metric_host: String,
-    #[clap(long, default_value = "8081", global = true)]
-    pub client_metric_port: u16,
+    /// Port of the client's Prometheus /metrics endpoint, with per-workload
+    /// counters, latencies, qps and the reference gas price. No endpoint is
+    /// served unless set
+    #[clap(long, alias = "metrics-port", global = true)]
+    pub client_metric_port: Option<u16>,
     /// Whether or no to download TXes during follow
     #[clap(long, global = true)]
     pub download_txes: bool,
@@ -79,6 +101,35 @@ pub struct Opts {
     /// one (or some) is slow.
     #[clap(long, parse(try_from_str), default_value = "true", global = true)]
     pub disjoint_mode: bool,
+    /// Run the enabled workloads one after the other, each alone at the full
+    /// target qps for --run-duration, instead of concurrently. Implies
+    /// disjoint mode
+    #[clap(long, global = true)]
+    pub sequential_workloads: bool,
+    /// Issue transactions in bursts at the target qps, pausing in between,
+    /// e.g. "on:2s,off:3s". The in-flight window and gas coins are sized for
+    /// the target qps, i.e. the burst rate
+    #[clap(long, global = true)]
+    pub burst: Option<BurstPattern>,
+    /// Seconds between checks of the balance of the most spent payload gas
+    /// coin, which warn when it would run out before the end of the run
+    #[clap(long, global = true)]
+    pub gas_monitor_secs: Option<u64>,
+    /// Stop the benchmark once the gas spent by its transactions, net of
+    /// storage rebates, reaches this many MIST. Transactions still in flight
+    /// at that point may add to the spend.
+    #[clap(long, global = true)]
+    pub max_total_mist_spend: Option<u64>,
+    /// Check that successful transactions also achieved what their workload
+    /// meant, e.g. that a delegation recorded the stake, and count the ones
+    /// that did not separately
+    #[clap(long, action, global = true)]
+    pub check_postconditions: bool,
+    /// Comma separated Prometheus endpoints of the validators, scraped at
+    /// the start and end of the run to report the change of server-side
+    /// counters next to the client measurements
+    #[clap(long, use_value_delimiter = true, global = true)]
+    pub validator_metrics_urls: Vec<String>,
     /// Number of transactions or duration to
     /// run the benchmark for. Default set to
     /// "unbounded" i.e. benchmark runs forever
@@ -102,12 +153,1028 @@ pub struct Opts {
     // Enable stress stat collection. When enabled the sysinfo crate will be used
     // to gather system information. For example cpu usage will be polled every
     // 1 second and the P50/P99 usage statistics will be outputted either at
-    // the end of the benchmark or periodically during a continuous run.
+    // the end of the benchmark or periodically during a continuous run. The
+    // cpu usage of the client process itself is reported as well, with a
+    // warning when it saturates.
     #[clap(long, action, global = true)]
     pub stress_stat_collection: bool,
+    /// Abort workload init with an error if it has not completed
+    /// within this many seconds. Unset means no timeout.
+    #[clap(long, global = true)]
+    pub init_timeout_secs: Option<u64>,
+    /// Calls to the network workload init, gas generation included, may
+    /// have in flight at once. Unset means no limit.
+    #[clap(long, global = true)]
+    pub init_concurrency: Option<usize>,
+    /// Directory of prebuilt workload packages to publish instead of
+    /// compiling their Move source, <package>.json being the output of
+    /// `sui move build --dump-bytecode-as-base64` for basics, collection,
+    /// wrap_unwrap, noop or object_fanout. Packages missing are compiled
+    #[clap(long, global = true)]
+    pub package_bytecode: Option<String>,
+    /// After the run, send the SUI left in the payload coins back to the
+    /// primary gas owner, so repeated runs on a persistent network do not
+    /// strand funds. Shared counters, published packages and other created
+    /// objects cannot be deleted and stay
+    #[clap(long, action, global = true)]
+    pub teardown: bool,
+    /// Print the gas coins and MIST each workload needs, then exit before
+    /// generating any of them or submitting transactions
+    #[clap(long, action, global = true)]
+    pub dry_run: bool,
+    /// File the generated gas coins are saved to, and loaded from by later
+    /// runs with the same configuration instead of generating gas again.
+    /// Coins are checked to be at their saved versions before use
+    #[clap(long, global = true, conflicts_with = "teardown")]
+    pub gas_manifest: Option<String>,
+    /// Distribution of amounts to pay in the transfer object workload,
+    /// e.g. "const:1000" or "lognormal:1000:0.5" (median:sigma).
+    /// When unset whole objects are transferred. A sampled amount larger
+    /// than the remaining balance transfers the whole object instead.
+    #[clap(long, global = true)]
+    pub pay_amount_dist: Option<AmountDistribution>,
+    /// Path where the raw latency histogram is exported so results
+    /// from several benchmark clients can be combined with
+    /// `merge-histograms`
+    #[clap(long, global = true)]
+    pub histogram_export: Option<String>,
+    /// Directory receiving one .hgrm percentile distribution per workload,
+    /// the text format read by the standard HdrHistogram plotting tools.
+    /// The workloads of a combination get a file each
+    #[clap(long, global = true, alias = "latency-histogram-dir")]
+    pub hgrm_output: Option<String>,
+    /// Print the N slowest transactions after the run, with their digest,
+    /// workload, latency and input objects
+    #[clap(long, global = true)]
+    pub dump_slowest: Option<usize>,
+    /// Keep the digest of every transaction built during the run and flag
+    /// any built twice, which means payloads were handed the same gas coin
+    /// or objects when the gas configs were split
+    #[clap(long, global = true)]
+    pub detect_duplicate_transactions: bool,
+    /// Compare the order transactions on shared objects were submitted in
+    /// to the order consensus committed them in, reporting how often each
+    /// workload was overtaken. Keeps an entry per shared object transaction
+    #[clap(long, global = true)]
+    pub track_ordering: bool,
+    /// Milliseconds workers pause issuing new transactions after
+    /// observing an epoch change, to let reconfiguration settle
+    #[clap(long, default_value = "0", global = true)]
+    pub epoch_change_pause_ms: u64,
+    /// Transactions that have not resolved within this many milliseconds
+    /// are counted as timed out, freeing their in-flight slot. The same
+    /// transaction is resent after another deadline, as its objects may
+    /// still be locked by it. Unset means no deadline.
+    #[clap(long, global = true)]
+    pub tx_deadline_ms: Option<u64>,
+    /// Seconds the first Ctrl-C waits for the transactions in flight to
+    /// complete before stopping the run. A second Ctrl-C exits right away
+    #[clap(long, default_value = "30", global = true)]
+    pub drain_timeout_secs: u64,
+    /// Gas budget of the transactions issued by all workloads. Unset keeps
+    /// the default budget of each transaction kind.
+    #[clap(long, global = true)]
+    pub gas_budget: Option<u64>,
+    /// Distribution the gas budget of each transaction of all workloads is
+    /// sampled from, e.g. "const:2000" or "lognormal:2000:0.5"
+    /// (median:sigma). Budgets are capped at what a gas coin can pay
+    #[clap(long, global = true, conflicts_with = "gas_budget")]
+    pub gas_budget_dist: Option<AmountDistribution>,
+    /// Per-workload --<workload>-gas-budget, overriding --gas-budget for
+    /// the transactions of that workload
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub gas_budgets: GasBudgets,
+    /// Validators receiving stake from the delegation workload: random,
+    /// highest-stake, lowest-stake or a comma separated list of addresses
+    #[clap(long, default_value = "random", global = true)]
+    pub delegation_target: DelegationTarget,
+    /// Entries the collection workload inserts into (and removes from)
+    /// its vector and table in each transaction
+    #[clap(long, default_value = "10", global = true)]
+    pub collection_ops_per_tx: u64,
+    /// Transactions in each chain of the dependency chain workload, each
+    /// one spends the coin created by the one before
+    #[clap(long, default_value = "10", global = true)]
+    pub dependency_chain_length: u64,
+    /// Percentage of shared lifecycle transactions creating a new shared
+    /// counter, the others increment counters created earlier in the run
+    #[clap(long, default_value = "20", global = true)]
+    pub shared_lifecycle_create_percent: u32,
+    /// Increments each counter created by the shared lifecycle workload
+    /// receives before the workload moves on to newer counters
+    #[clap(long, default_value = "10", global = true)]
+    pub shared_lifecycle_mutations: u64,
+    /// Number of objects the send_to_object workload sends coins to
+    #[clap(long, default_value = "10", global = true)]
+    pub send_to_object_destinations: u64,
+    /// Number of new objects each object fanout transaction creates
+    #[clap(long, default_value = "10", global = true)]
+    pub create_fanout: u64,
+    /// Length in bytes of the name of each minted NFT
+    #[clap(long, default_value = "32", global = true)]
+    pub nft_name_length: u64,
+    /// Length in bytes of the url of each minted NFT
+    #[clap(long, default_value = "64", global = true)]
+    pub nft_url_length: u64,
+    /// Length in bytes of the description of each minted NFT
+    #[clap(long, default_value = "256", global = true)]
+    pub nft_description_length: u64,
+    /// Number of Move calls in each batch transaction. Their total budget
+    /// must stay within the maximum gas budget of a transaction
+    #[clap(long, default_value = "10", global = true)]
+    pub commands_per_tx: u64,
+    /// Number of coins each coin churn transaction splits a coin into
+    /// before the next merges them back
+    #[clap(long, default_value = "10", global = true)]
+    pub split_count: u64,
+    /// Move calls of the custom call workload, comma separated
+    /// <package>::<module>::<function>[:<weight>[:<gas budget>]] with a
+    /// weight of 1 and a budget of 2000 by default. Each transaction calls
+    /// one picked by weight, together they weigh the sum of their weights
+    /// against the workload weights. The packages must already be published
+    /// and the functions take no arguments but the transaction context
+    #[clap(long, global = true)]
+    pub custom_calls: Option<CustomCalls>,
+    /// Payload gas coins owned by each sender account. Every coin carries
+    /// one transaction in flight. Unset keeps a single sender per workload.
+    #[clap(long, global = true)]
+    pub gas_coins_per_sender: Option<u64>,
+    /// Keystore of existing accounts sending the payload transactions, in
+    /// turn, instead of new accounts. Useful against a persistent network
+    #[clap(long, global = true)]
+    pub sender_addresses_file: Option<String>,
+    /// Number of connections the proxy opens to each validator (or to the
+    /// fullnode), submissions are round-robined across them
+    #[clap(long, default_value = "1", global = true)]
+    pub connections_per_proxy: usize,
+    /// Seconds between the start of consecutive workloads in disjoint mode.
+    /// Latencies within the first stagger window of each workload are left
+    /// out of the histograms.
+    #[clap(long, default_value = "0", global = true)]
+    pub workload_stagger_secs: u64,
+    /// Verify the quorum of validator signatures on the effects of every
+    /// transaction and count failures. Requires executing through the
+    /// validators, fullnode responses are not signed.
+    #[clap(long, action, global = true)]
+    pub verify_effects_signatures: bool,
+    /// Transfer objects around a fixed ring of accounts and check after the
+    /// run that every object is owned by the account the ring placed it with
+    #[clap(long, action, global = true)]
+    pub transfer_ring_check: bool,
+    /// Record every call to the validator proxy and its outcome to this file,
+    /// the session can be served offline with `ReplayProxy`
+    #[clap(long, global = true)]
+    pub record_session: Option<String>,
+    /// Write the transaction digest and effects digest of every successful
+    /// transaction to this CSV file, to check the run against the chain later
+    #[clap(long, global = true)]
+    pub effects_digest_log: Option<String>,
+    /// Delay every call to the validator proxy by fixed:<ms> or a
+    /// uniform:<min_ms>:<max_ms> sample, to test timing logic
+    #[clap(long, global = true)]
+    pub proxy_delay: Option<ProxyDelay>,
+    /// Resubmit transactions failing with one of --retry-errors up to this
+    /// many times in the proxy, backing off exponentially. 0 keeps every
+    /// failure final for the attempt
+    #[clap(long, default_value = "0", global = true)]
+    pub max_retries: u32,
+    /// Milliseconds before the first retry of --max-retries, doubled on
+    /// each retry after it
+    #[clap(long, default_value = "100", global = true)]
+    pub retry_backoff_ms: u64,
+    /// Comma separated transient errors retried with --max-retries: locked,
+    /// for input objects locked by another transaction, and timeout
+    #[clap(
+        long,
+        default_value = "locked,timeout",
+        use_value_delimiter = true,
+        global = true
+    )]
+    pub retry_errors: Vec<TransientError>,
+    /// What the latency of a transaction waits for: local execution by the
+    /// node serving it, certified effects or checkpoint inclusion. Defaults
+    /// to certified through validators and local through a fullnode.
+    #[clap(long, global = true)]
+    pub effects_wait: Option<EffectsWait>,
+    /// Microseconds a worker sleeps when its in-flight window is full
+    /// instead of waking up on every request tick. Unset keeps polling.
+    #[clap(long, global = true)]
+    pub inflight_full_backoff_us: Option<u64>,
+    /// Write per-interval throughput, error and latency measurements of each
+    /// workload in InfluxDB line protocol. An http(s) url is treated as an
+    /// Influx write endpoint, anything else as a file path.
+    #[clap(long, global = true)]
+    pub output_influx: Option<String>,
+    /// Write one csv row per second and workload with its qps, transactions
+    /// in flight and p50/p99 latency over the last 10 seconds, for plotting
+    #[clap(long, global = true)]
+    pub timeseries_csv: Option<String>,
+    /// Free-form key=value label of the run, e.g. --label hardware=m6i.4xlarge,
+    /// repeatable. Embedded in the results json and added as a tag to every
+    /// influx measurement and client metric
+    #[clap(long = "label", global = true)]
+    pub labels: Vec<Label>,
+    /// Report any object mutated past this version, which usually means a
+    /// hotspot is serializing a supposedly parallel workload
+    #[clap(long, global = true)]
+    pub max_object_version: Option<u64>,
+    /// Rescale the workload weights of a bench run to sum to 100 and print
+    /// the resulting mix. Weights are relative, this only makes the mix
+    /// explicit and does not change it beyond rounding
+    #[clap(long, global = true)]
+    pub normalize_weights: bool,
+    /// Configure the run for a goal. owned-max issues only owned object
+    /// transfers, deliberately avoiding consensus, to find the peak fast
+    /// path throughput. Workload weights are overridden, other flags given
+    /// explicitly take precedence.
+    #[clap(long, global = true)]
+    pub preset: Option<Preset>,
+    /// JSON file of bench flags, named after their field, e.g.
+    /// {"shared_counter": 2, "transfer_object": 1, "target_qps": 500}, to
+    /// keep scenarios in version control. Flags given on the command line
+    /// take precedence over the file
+    #[clap(long, global = true)]
+    pub config_file: Option<String>,
+    /// Spread shared counter increments and reads over the full pool of
+    /// counters and read objects, never touching an object again until half
+    /// of the pool was touched after it, so caches warmed by earlier
+    /// transactions do not flatter the results
+    #[clap(long, action, global = true)]
+    pub randomize_inputs: bool,
+    /// Seeds every rng of the benchmark, accounts, gas budgets, object and
+    /// recipient selection and proxy delays. Gas generation, workload init
+    /// and the first transaction of each payload are reproducible. Workers
+    /// then draw from the same stream in the order the runtime schedules
+    /// them, so later transactions are only reproducible with one worker
+    #[clap(long, global = true)]
+    pub global_seed: Option<u64>,
+    /// Save the progress of the run to this file every stat interval, and
+    /// continue the run it holds if the file exists, e.g. after a restart.
+    /// A resumed run only runs what is left of --run-duration, with fresh gas
+    #[clap(long, global = true)]
+    pub resume: Option<String>,
+    /// Tag the payload transactions of the run so they can be found on-chain
+    /// later, their gas budgets end in this number (0-999). Sui transactions
+    /// carry no memo, so the tag lives in the last digits of the budget
+    #[clap(long, global = true)]
+    pub tx_tag: Option<u64>,
+    /// Seconds between polls of the system state object, which tracks the
+    /// reference gas price, epoch and committee during the run
+    #[clap(long, default_value = "60", global = true)]
+    pub system_state_poll_secs: u64,
+    /// Also time transactions from the start of their construction and
+    /// signing, and report the build time, network-only and build-inclusive
+    /// latency of each workload
+    #[clap(long, global = true)]
+    pub measure_build_latency: bool,
+    /// Report the latency trajectory of the first transactions of the run
+    /// and how long latency took to stabilize, e.g. against validators
+    /// recovering from a restart
+    #[clap(long, global = true)]
+    pub measure_warmup: bool,
+    /// Number of transactions recorded by --measure-warmup
+    #[clap(long, default_value = "300", global = true)]
+    pub warmup_samples: usize,
+    /// Latency below which --measure-warmup considers latency stable
+    #[clap(long, default_value = "1000", global = true)]
+    pub warmup_threshold_ms: u64,
+    /// Run load for this many seconds of --run-duration before measuring,
+    /// then stop issuing until the warmup transactions drained and measure
+    /// the rest of the run from fresh counters and histograms
+    #[clap(long, global = true)]
+    pub warmup_secs: Option<u64>,
+    /// Set when a closed-loop run was turned into the bench run it executes
+    #[clap(skip)]
+    pub closed_loop: bool,
+    /// Quick validation before a big run: issue about 10 transactions per
+    /// enabled workload at 1 qps each, then report pass or fail per workload.
+    /// Overrides the qps, worker, in-flight and duration flags
+    #[clap(long, action, global = true)]
+    pub smoke: bool,
+}
+
+/// Relative weight of each workload of a closed-loop run, parsed from comma
+/// separated <workload>=<weight> pairs
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct WorkloadMix(pub Vec<(WorkloadType, u32)>);
+
+impl std::str::FromStr for WorkloadMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|pair| {
+                let (name, weight) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected <workload>=<weight>, got {}", pair))?;
+                let workload = built_in_workload(name.trim())
+                    .ok_or_else(|| format!("Unknown workload {}", name))?;
+                let weight = weight.trim().parse().map_err(|e| format!("{e}"))?;
+                Ok((workload, weight))
+            })
+            .collect::<Result<_, _>>()
+            .map(WorkloadMix)
+    }
+}
+
+/// The built-in workloads a bench run weights
+fn built_in_workloads() -> impl Iterator<Item = WorkloadType> {
+    WorkloadType::iter().filter(|workload| *workload != WorkloadType::Combination)
+}
+
+/// The built-in workload named `name`, e.g. "shared_counter"
+fn built_in_workload(name: &str) -> Option<WorkloadType> {
+    built_in_workloads().find(|workload| workload.to_string() == name)
+}
+
+/// Long name of the `<workload>-<suffix>` flag of each built-in workload
+/// declaring one in `WorkloadType::flags`, or of its weight flag, declared
+/// first, when `suffix` is empty
+fn workload_flag_names(suffix: &str) -> Vec<(WorkloadType, &'static str)> {
+    built_in_workloads()
+        .filter_map(|workload| {
+            let flags = workload.flags();
+            let weight = *flags.first()?;
+            if suffix.is_empty() {
+                return Some((workload, weight));
+            }
+            let name = format!("{}-{}", weight, suffix);
+            flags
+                .iter()
+                .find(|flag| **flag == name)
+                .map(|flag| (workload, *flag))
+        })
+        .collect()
+}
+
+// Serializes the values of per-workload flags keyed the way fields of the
+// same name would be, e.g. "shared_counter" or "shared_counter_gas_budget"
+fn serialize_workload_values<S, T>(
+    serializer: S,
+    suffix: &str,
+    values: &[(WorkloadType, T)],
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: serde::Serialize,
+{
+    serializer.collect_map(values.iter().map(|(workload, value)| {
+        let key = if suffix.is_empty() {
+            workload.to_string()
+        } else {
+            format!("{}_{}", workload, suffix.replace('-', "_"))
+        };
+        (key, value)
+    }))
+}
+
+/// Relative weight of each built-in workload in a bench run, one
+/// --<workload> flag per workload declared by `WorkloadType::flags`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkloadWeights(pub Vec<(WorkloadType, u32)>);
+
+impl WorkloadWeights {
+    // Only transfer objects run unless other weights are given
+    fn default_weight(workload: WorkloadType) -> &'static str {
+        if workload == WorkloadType::TransferObject {
+            "1"
+        } else {
+            "0"
+        }
+    }
+
+    pub fn get(&self, workload: WorkloadType) -> u32 {
+        self.0
+            .iter()
+            .find(|(w, _)| *w == workload)
+            .map_or(0, |(_, weight)| *weight)
+    }
+
+    pub fn set(&mut self, workload: WorkloadType, weight: u32) {
+        match self.0.iter_mut().find(|(w, _)| *w == workload) {
+            Some((_, current)) => *current = weight,
+            None => self.0.push((workload, weight)),
+        }
+    }
+}
+
+impl Default for WorkloadWeights {
+    fn default() -> Self {
+        Self(
+            workload_flag_names("")
+                .into_iter()
+                .map(|(workload, _)| {
+                    let weight = Self::default_weight(workload).parse().unwrap();
+                    (workload, weight)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Args for WorkloadWeights {
+    fn augment_args(cmd: Command<'_>) -> Command<'_> {
+        workload_flag_names("")
+            .into_iter()
+            .fold(cmd, |cmd, (workload, name)| {
+                cmd.arg(
+                    Arg::new(name)
+                        .long(name)
+                        .takes_value(true)
+                        .value_parser(value_parser!(u32))
+                        .default_value(Self::default_weight(workload))
+                        .help("Relative weight of the workload in the benchmark"),
+                )
+            })
+    }
+
+    fn augment_args_for_update(cmd: Command<'_>) -> Command<'_> {
+        Self::augment_args(cmd)
+    }
+}
+
+impl FromArgMatches for WorkloadWeights {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, Error> {
+        let mut weights = Self(vec![]);
+        weights.update_from_arg_matches(matches)?;
+        Ok(weights)
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), Error> {
+        for (workload, name) in workload_flag_names("") {
+            if let Some(weight) = matches.get_one::<u32>(name) {
+                self.set(workload, *weight);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl serde::Serialize for WorkloadWeights {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_workload_values(serializer, "", &self.0)
+    }
+}
+
+// Per-workload overrides read from the `<workload>-<suffix>` flags, only
+// the workloads whose flag was given are listed
+fn augment_override_args<'help>(
+    cmd: Command<'help>,
+    suffix: &str,
+    help: &'static str,
+    global: bool,
+) -> Command<'help> {
+    workload_flag_names(suffix)
+        .into_iter()
+        .fold(cmd, |cmd, (_, name)| {
+            cmd.arg(
+                Arg::new(name)
+                    .long(name)
+                    .takes_value(true)
+                    .value_parser(value_parser!(u64))
+                    .global(global)
+                    .help(help),
+            )
+        })
+}
+
+fn update_overrides(overrides: &mut Vec<(WorkloadType, u64)>, matches: &ArgMatches, suffix: &str) {
+    for (workload, name) in workload_flag_names(suffix) {
+        if let Some(value) = matches.get_one::<u64>(name) {
+            overrides.retain(|(w, _)| *w != workload);
+            overrides.push((workload, *value));
+        }
+    }
+}
+
+const IN_FLIGHT_RATIO_SUFFIX: &str = "in-flight-ratio";
+const GAS_BUDGET_SUFFIX: &str = "gas-budget";
+
+/// Per-workload overrides of --in-flight-ratio, e.g. a lower one for
+/// shared counters whose contention grows with the transactions in flight.
+/// Only disjoint mode runs each workload with its own window
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InFlightRatios(pub Vec<(WorkloadType, u64)>);
+
+impl InFlightRatios {
+    /// The in-flight ratio overriding --in-flight-ratio for `workload`
+    pub fn of(&self, workload: WorkloadType) -> Option<u64> {
+        self.0
+            .iter()
+            .find(|(w, _)| *w == workload)
+            .map(|(_, ratio)| *ratio)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Args for InFlightRatios {
+    fn augment_args(cmd: Command<'_>) -> Command<'_> {
+        augment_override_args(
+            cmd,
+            IN_FLIGHT_RATIO_SUFFIX,
+            "In-flight ratio of the workload, overrides --in-flight-ratio",
+            false,
+        )
+    }
+
+    fn augment_args_for_update(cmd: Command<'_>) -> Command<'_> {
+        Self::augment_args(cmd)
+    }
+}
+
+impl FromArgMatches for InFlightRatios {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, Error> {
+        let mut ratios = Self::default();
+        ratios.update_from_arg_matches(matches)?;
+        Ok(ratios)
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), Error> {
+        update_overrides(&mut self.0, matches, IN_FLIGHT_RATIO_SUFFIX);
+        Ok(())
+    }
+}
+
+impl serde::Serialize for InFlightRatios {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_workload_values(serializer, IN_FLIGHT_RATIO_SUFFIX, &self.0)
+    }
 }
 
-#[derive(Debug, Clone, Parser, Eq, PartialEq, EnumString)]
+/// Per-workload overrides of --gas-budget. Workloads left out use
+/// --gas-budget, or the default budget of their transactions, e.g. enough
+/// gas to store the objects created by object fanout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GasBudgets(pub Vec<(WorkloadType, u64)>);
+
+impl GasBudgets {
+    /// The gas budget overriding --gas-budget for `workload`
+    pub fn of(&self, workload: WorkloadType) -> Option<u64> {
+        self.0
+            .iter()
+            .find(|(w, _)| *w == workload)
+            .map(|(_, budget)| *budget)
+    }
+}
+
+impl Args for GasBudgets {
+    fn augment_args(cmd: Command<'_>) -> Command<'_> {
+        augment_override_args(
+            cmd,
+            GAS_BUDGET_SUFFIX,
+            "Gas budget of the transactions of the workload, overrides --gas-budget",
+            true,
+        )
+    }
+
+    fn augment_args_for_update(cmd: Command<'_>) -> Command<'_> {
+        Self::augment_args(cmd)
+    }
+}
+
+impl FromArgMatches for GasBudgets {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, Error> {
+        let mut budgets = Self::default();
+        budgets.update_from_arg_matches(matches)?;
+        Ok(budgets)
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), Error> {
+        update_overrides(&mut self.0, matches, GAS_BUDGET_SUFFIX);
+        Ok(())
+    }
+}
+
+impl serde::Serialize for GasBudgets {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_workload_values(serializer, GAS_BUDGET_SUFFIX, &self.0)
+    }
+}
+
+/// Named configurations over the existing flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display, serde::Serialize)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Preset {
+    // Peak owned object throughput. Only transfer object transactions are
+    // issued, each payload owns its gas coin and the transferred object,
+    // so nothing goes through consensus. Shared counter, delegation (which
+    // mutates the shared system state object) and all other workloads
+    // are disabled. Unless given explicitly, one worker runs per client
+    // core with an in-flight ratio of 10.
+    OwnedMax,
+}
+
+/// Bench flags read from --config-file, each overriding the default of the
+/// flag of the same name
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+pub struct BenchConfig {
+    pub shared_counter_hotness_factor: Option<u32>,
+    pub contention_objects: Option<u64>,
+    pub num_transfer_accounts: Option<u64>,
+    pub target_qps: Option<u64>,
+    pub num_workers: Option<u64>,
+    pub in_flight_ratio: Option<u64>,
+    pub max_in_flight: Option<u64>,
+    pub target_p99_ms: Option<u64>,
+    pub duration_secs: Option<u64>,
+    pub ramp_up_secs: Option<u64>,
+    pub ramp_up_start_qps: Option<u64>,
+    /// Workload weights keyed by workload, e.g. "shared_counter", along
+    /// with any unknown field, rejected by `from_file`
+    #[serde(flatten)]
+    pub weights: BTreeMap<String, u32>,
+}
+
+impl BenchConfig {
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path))?;
+        // Negative weights fail here, they do not fit in a u32
+        let config: Self =
+            serde_json::from_str(&json).with_context(|| format!("Invalid config file {}", path))?;
+        if let Some(name) = config
+            .weights
+            .keys()
+            .find(|name| built_in_workload(name).is_none())
+        {
+            bail!("Invalid config file {}: unknown field {}", path, name);
+        }
+        Ok(config)
+    }
+
+    /// The workload weights of the file
+    pub fn workload_weights(&self) -> impl Iterator<Item = (WorkloadType, u32)> + '_ {
+        self.weights
+            .iter()
+            .filter_map(|(name, weight)| Some((built_in_workload(name)?, *weight)))
+    }
+}
+
+// Sets `field` to the value of the config file unless its flag was explicit
+fn merge_config<T>(explicit: bool, value: Option<T>, field: &mut T) {
+    if let Some(value) = value.filter(|_| !explicit) {
+        *field = value;
+    }
+}
+
+impl Opts {
+    /// Reads `self.config_file` into the bench flags not given explicitly,
+    /// `matches` tells which were
+    pub fn apply_config_file(&mut self, matches: &ArgMatches) -> anyhow::Result<()> {
+        let path = match &self.config_file {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+        let config = BenchConfig::from_file(&path)?;
+        let explicit = |id: &str| {
+            matches
+                .subcommand_matches("bench")
+                .map_or(false, |m| m.occurrences_of(id) > 0)
+        };
+        // A global flag, read from the subcommand when given after it
+        merge_config(
+            matches.occurrences_of("num_transfer_accounts") > 0
+                || explicit("num_transfer_accounts"),
+            config.num_transfer_accounts,
+            &mut self.num_transfer_accounts,
+        );
+        match &mut self.run_spec {
+            RunSpec::Bench {
+                weights,
+                shared_counter_hotness_factor,
+                contention_objects,
+                target_qps,
+                num_workers,
+                in_flight_ratio,
+                max_in_flight,
+                // Only read from their flags
+                in_flight_ratios: _,
+                target_p99_ms,
+                duration_secs,
+                ramp_up_secs,
+                ramp_up_start_qps,
+            } => {
+                if contention_objects.is_some() && config.shared_counter_hotness_factor.is_some() {
+                    bail!(
+                        "--contention-objects cannot be used with the \
+                        shared_counter_hotness_factor of config file {}",
+                        path
+                    );
+                }
+                if config.contention_objects.is_some()
+                    && (explicit("shared_counter_hotness_factor")
+                        || config.shared_counter_hotness_factor.is_some())
+                {
+                    bail!(
+                        "The contention_objects of config file {} cannot be used \
+                        with a shared counter hotness factor",
+                        path
+                    );
+                }
+                for (workload, weight) in config.workload_weights() {
+                    if !explicit(workload.flags()[0]) {
+                        weights.set(workload, weight);
+                    }
+                }
+                merge_config(
+                    explicit("shared_counter_hotness_factor"),
+                    config.shared_counter_hotness_factor,
+                    shared_counter_hotness_factor,
+                );
+                merge_config(
+                    explicit("contention_objects"),
+                    config.contention_objects.map(Some),
+                    contention_objects,
+                );
+                merge_config(explicit("target_qps"), config.target_qps, target_qps);
+                merge_config(explicit("num_workers"), config.num_workers, num_workers);
+                merge_config(
+                    explicit("in_flight_ratio"),
+                    config.in_flight_ratio,
+                    in_flight_ratio,
+                );
+                merge_config(
+                    explicit("max_in_flight"),
+                    config.max_in_flight.map(Some),
+                    max_in_flight,
+                );
+                merge_config(
+                    explicit("target_p99_ms"),
+                    config.target_p99_ms.map(Some),
+                    target_p99_ms,
+                );
+                merge_config(
+                    explicit("duration_secs"),
+                    config.duration_secs.map(Some),
+                    duration_secs,
+                );
+                merge_config(explicit("ramp_up_secs"), config.ramp_up_secs, ramp_up_secs);
+                merge_config(
+                    explicit("ramp_up_start_qps"),
+                    config.ramp_up_start_qps,
+                    ramp_up_start_qps,
+                );
+            }
+            _ => bail!("--config-file only configures bench runs"),
+        }
+        // The custom calls weigh against the workload weights
+        if self.custom_calls.is_none()
+            && self
+                .workload_weights()
+                .iter()
+                .all(|(_, weight)| **weight == 0)
+        {
+            bail!(
+                "Every workload weight is 0 after reading {}, at least one must be above 0",
+                path
+            );
+        }
+        Ok(())
+    }
+
+    /// Applies `self.preset` to the parsed options, `matches` tells which
+    /// flags were given explicitly
+    pub fn apply_preset(&mut self, matches: &ArgMatches) {
+        let explicit = |id: &str| {
+            matches
+                .subcommand_matches("bench")
+                .map_or(false, |m| m.occurrences_of(id) > 0)
+        };
+        match self.preset {
+            None => {}
+            Some(Preset::OwnedMax) => {
+                if let RunSpec::Bench {
+                    weights,
+                    num_workers,
+                    in_flight_ratio,
+                    max_in_flight,
+                    ..
+                } = &mut self.run_spec
+                {
+                    for (workload, weight) in &mut weights.0 {
+                        *weight = match workload {
+                            WorkloadType::TransferObject => (*weight).max(1),
+                            _ => 0,
+                        };
+                    }
+                    if !explicit("num_workers") {
+                        *num_workers = num_cpus::get() as u64;
+                    }
+                    if !explicit("in_flight_ratio") && max_in_flight.is_none() {
+                        *in_flight_ratio = 10;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Turns a closed-loop run into the bench run executing it: the mix
+    /// becomes the workload weights and the in-flight window sizes the
+    /// workloads in place of a target qps. The driver replaces completed
+    /// transactions right away when `self.closed_loop` is set
+    pub fn apply_closed_loop(&mut self) {
+        let (
+            in_flight,
+            num_workers,
+            duration,
+            mix,
+            shared_counter_hotness_factor,
+            contention_objects,
+        ) = match &self.run_spec {
+            RunSpec::ClosedLoop {
+                in_flight,
+                num_workers,
+                duration,
+                mix,
+                shared_counter_hotness_factor,
+                contention_objects,
+            } => (
+                *in_flight,
+                *num_workers,
+                *duration,
+                mix.clone(),
+                *shared_counter_hotness_factor,
+                *contention_objects,
+            ),
+            _ => return,
+        };
+        let mut weights =
+            WorkloadWeights(built_in_workloads().map(|workload| (workload, 0)).collect());
+        for (workload, weight) in mix.0 {
+            weights.set(workload, weights.get(workload) + weight);
+        }
+        self.run_spec = RunSpec::Bench {
+            weights,
+            shared_counter_hotness_factor,
+            contention_objects,
+            // Sizes the workloads, e.g. the number of shared counters, the
+            // rate itself is left to the system
+            target_qps: in_flight.max(num_workers),
+            num_workers,
+            in_flight_ratio: 1,
+            max_in_flight: Some(in_flight),
+            in_flight_ratios: InFlightRatios::default(),
+            target_p99_ms: None,
+            duration_secs: None,
+            ramp_up_secs: 0,
+            ramp_up_start_qps: 0,
+        };
+        if let Some(duration) = duration {
+            self.run_duration = duration;
+        }
+        self.closed_loop = true;
+    }
+
+    /// The weight flags of a bench run, by workload
+    fn workload_weights(&mut self) -> Vec<(WorkloadType, &mut u32)> {
+        match &mut self.run_spec {
+            RunSpec::Bench { weights, .. } => weights
+                .0
+                .iter_mut()
+                .map(|(workload, weight)| (*workload, weight))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Rescales the workload weights of a bench run to sum to 100, keeping
+    /// every enabled workload at a weight of at least 1. Returns the new
+    /// weights of the enabled workloads
+    pub fn normalize_weights(&mut self) -> Vec<(WorkloadType, u32)> {
+        let mut weights = self.workload_weights();
+        weights.retain(|(_, weight)| **weight > 0);
+        let total: u64 = weights.iter().map(|(_, weight)| **weight as u64).sum();
+        if total == 0 {
+            return vec![];
+        }
+        // Largest remainder rounding, so the percentages add up to exactly 100
+        let mut scaled: Vec<(u32, u64)> = weights
+            .iter()
+            .map(|(_, weight)| {
+                let share = **weight as u64 * 100;
+                ((share / total) as u32, share % total)
+            })
+            .collect();
+        let mut by_remainder: Vec<usize> = (0..scaled.len()).collect();
+        by_remainder.sort_by(|a, b| scaled[*b].1.cmp(&scaled[*a].1));
+        let assigned: u32 = scaled.iter().map(|(percent, _)| percent).sum();
+        for i in by_remainder.into_iter().take((100 - assigned) as usize) {
+            scaled[i].0 += 1;
+        }
+        // Workloads too small to round to 1% keep running at 1%
+        for i in 0..scaled.len() {
+            if scaled[i].0 == 0 {
+                let largest = (0..scaled.len()).max_by_key(|j| scaled[*j].0).unwrap();
+                scaled[largest].0 -= 1;
+                scaled[i].0 = 1;
+            }
+        }
+        weights
+            .into_iter()
+            .zip(scaled)
+            .map(|((workload, weight), (percent, _))| {
+                *weight = percent;
+                (workload, percent)
+            })
+            .collect()
+    }
+
+    /// Shrinks a bench run to a smoke test: every enabled workload runs
+    /// alone in its disjoint share at 1 qps with a single transaction in
+    /// flight for SMOKE_DURATION, so each issues about 10 transactions.
+    /// Returns the enabled workloads
+    pub fn apply_smoke(&mut self) -> Vec<WorkloadType> {
+        let mut workloads = vec![];
+        for (workload, weight) in self.workload_weights() {
+            if *weight > 0 {
+                *weight = 1;
+                workloads.push(workload);
+            }
+        }
+        if let RunSpec::Bench {
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            max_in_flight,
+            in_flight_ratios,
+            target_p99_ms,
+            duration_secs,
+            ramp_up_secs,
+            ..
+        } = &mut self.run_spec
+        {
+            *target_qps = workloads.len() as u64;
+            *num_workers = workloads.len() as u64;
+            *in_flight_ratio = 1;
+            *max_in_flight = None;
+            *in_flight_ratios = InFlightRatios::default();
+            *target_p99_ms = None;
+            *duration_secs = None;
+            *ramp_up_secs = 0;
+        }
+        self.disjoint_mode = true;
+        self.sequential_workloads = false;
+        self.closed_loop = false;
+        self.warmup_secs = None;
+        self.run_duration = Interval::Time(SMOKE_DURATION);
+        workloads
+    }
+
+    /// Turns --duration-secs into a timed run which drains the transactions
+    /// in flight at its end. Returns whether the run drains
+    pub fn apply_duration(&mut self) -> Result<bool, String> {
+        let duration_secs = match self.run_spec {
+            RunSpec::Bench {
+                duration_secs: Some(duration_secs),
+                ..
+            } => duration_secs,
+            _ => return Ok(false),
+        };
+        if !self.run_duration.is_unbounded() {
+            return Err("--duration-secs replaces --run-duration, set only one".to_string());
+        }
+        if duration_secs == 0 {
+            return Err("--duration-secs must be positive".to_string());
+        }
+        self.run_duration = Interval::Time(Duration::from_secs(duration_secs));
+        Ok(true)
+    }
+
+    /// Retries of transient errors in the proxy with --max-retries
+    pub fn retry_config(&self) -> Option<RetryConfig> {
+        (self.max_retries > 0).then(|| RetryConfig {
+            max_retries: self.max_retries,
+            initial_backoff: Duration::from_millis(self.retry_backoff_ms),
+            errors: self.retry_errors.clone(),
+        })
+    }
+
+    /// Ramp up of a bench run with --ramp-up-secs
+    pub fn ramp_up(&self) -> Option<RampUp> {
+        match self.run_spec {
+            RunSpec::Bench {
+                target_qps,
+                ramp_up_secs,
+                ramp_up_start_qps,
+                ..
+            } if ramp_up_secs > 0 => Some(RampUp::new(
+                ramp_up_start_qps,
+                target_qps,
+                Duration::from_secs(ramp_up_secs),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Length of a --smoke run
+pub const SMOKE_DURATION: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Parser, Eq, PartialEq, EnumString, serde::Serialize)]
 #[non_exhaustive]
 #[clap(rename_all = "kebab-case")]
 pub enum RunSpec {
@@ -122,10 +1189,12 @@ pub enum RunSpec {
     // will likely change in future to support
     // more representative workloads.
     Bench {
-        // relative weight of shared counter
-        // transaction in the benchmark workload
-        #[clap(long, default_value = "0")]
-        shared_counter: u32,
+        // relative weight of each workload in the benchmark workload, one
+        // --<workload> flag per workload, e.g. --shared-counter. Only
+        // transfer objects run by default
+        #[clap(flatten)]
+        #[serde(flatten)]
+        weights: WorkloadWeights,
         // 100 for max hotness i.e all requests target
         // just the same shared counter, 0 for no hotness
         // i.e. all requests target a different shared
@@ -134,13 +1203,11 @@ pub enum RunSpec {
         // total_shared_counters = max(1, qps * (1.0 - hotness/100.0))
         #[clap(long, default_value = "50")]
         shared_counter_hotness_factor: u32,
-        // relative weight of transfer object
-        // transactions in the benchmark workload
-        #[clap(long, default_value = "1")]
-        transfer_object: u32,
-        // relative weight of delegation transactions in the benchmark workload
-        #[clap(long, default_value = "0")]
-        delegation: u32,
+        // Number of shared counters, set directly instead of derived from
+        // --shared-counter-hotness-factor. Writers are assigned to them
+        // round-robin, 1 puts every writer on the same counter
+        #[clap(long, conflicts_with = "shared_counter_hotness_factor")]
+        contention_objects: Option<u64>,
         // Target qps
         #[clap(long, default_value = "1000", global = true)]
         target_qps: u64,
@@ -150,5 +1217,625 @@ pub enum RunSpec {
         // Max in-flight ratio
         #[clap(long, default_value = "5", global = true)]
         in_flight_ratio: u64,
+        // Absolute max number of transactions in flight, independent of
+        // the target qps. Replaces --in-flight-ratio when set
+        #[clap(long, global = true, conflicts_with = "in_flight_ratio")]
+        max_in_flight: Option<u64>,
+        // Per-workload --<workload>-in-flight-ratio, replacing the global
+        // ratio, or the share of --max-in-flight, of that workload in
+        // disjoint mode
+        #[clap(flatten)]
+        in_flight_ratios: InFlightRatios,
+        // Search for the in-flight window that keeps the p99 latency at
+        // this target, then hold it and measure. The provisioned window
+        // (--in-flight-ratio or --max-in-flight) is the upper bound
+        #[clap(long, global = true)]
+        target_p99_ms: Option<u64>,
+        // Stop issuing new transactions this many seconds after the first
+        // one was submitted, then let those in flight complete. Replaces
+        // --run-duration, which cuts the transactions in flight short
+        #[clap(long, global = true)]
+        duration_secs: Option<u64>,
+        // Climb linearly from --ramp-up-start-qps to --target-qps over this
+        // many seconds, 0 issues the target qps right away. The in-flight
+        // window climbs with the qps, --in-flight-ratio or --max-in-flight
+        // is only reached at the end of the ramp. Payload gas is provisioned
+        // for the target qps
+        #[clap(long, default_value = "0", global = true)]
+        ramp_up_secs: u64,
+        // Qps issued at the start of the ramp up
+        #[clap(long, default_value = "0", global = true)]
+        ramp_up_start_qps: u64,
     },
+    // Keep a fixed number of transactions in flight and measure the qps the
+    // system sustains, the closed-loop dual of bench. Every completed
+    // transaction is replaced right away instead of at a target rate
+    ClosedLoop {
+        // Transactions kept in flight over all workers
+        #[clap(long, default_value = "100")]
+        in_flight: u64,
+        #[clap(long, default_value = "12")]
+        num_workers: u64,
+        // Number of transactions or duration of the run, replaces
+        // --run-duration when set
+        #[clap(long)]
+        duration: Option<Interval>,
+        // Relative weight of each workload, e.g.
+        // transfer_object=50,shared_counter=50
+        #[clap(long, default_value = "transfer_object=1")]
+        mix: WorkloadMix,
+        // Hotness of the shared counters of the mix, as for bench
+        #[clap(long, default_value = "50")]
+        shared_counter_hotness_factor: u32,
+        // Number of shared counters of the mix, as for bench
+        #[clap(long, conflicts_with = "shared_counter_hotness_factor")]
+        contention_objects: Option<u64>,
+    },
+    // Combine latency histograms written with --histogram-export
+    // by several benchmark clients and print the unified percentiles
+    MergeHistograms {
+        #[clap(required = true)]
+        files: Vec<String>,
+    },
+    // Print the available workloads with their flags
+    ListWorkloads,
+}
+
+/// Describes the flags with the long names `flags`, e.g. those a workload
+/// declares, with their default and help
+pub fn describe_flags(flags: &[&str]) -> Vec<String> {
+    let command = Opts::command();
+    let bench = command
+        .get_subcommands()
+        .find(|c| c.get_name() == "bench")
+        .expect("bench subcommand");
+    flags
+        .iter()
+        .filter_map(|name| {
+            let arg = command
+                .get_arguments()
+                .chain(bench.get_arguments())
+                .find(|arg| arg.get_long() == Some(name))?;
+            let mut flag = format!("--{}", name);
+            if let Some(default) = arg.get_default_values().first() {
+                flag.push_str(&format!(" [default: {}]", default.to_string_lossy()));
+            }
+            if let Some(help) = arg.get_help() {
+                flag.push_str(&format!("  {}", help.replace('\n', " ")));
+            }
+            Some(flag)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workloads::registry::WorkloadRegistry;
+    use std::time::Duration;
+
+    fn parse_with_preset(args: &[&str]) -> Opts {
+        let matches = Opts::command().try_get_matches_from(args).unwrap();
+        let mut opts = Opts::from_arg_matches(&matches).unwrap();
+        opts.apply_preset(&matches);
+        opts
+    }
+
+    #[test]
+    fn test_owned_max_preset() {
+        let opts = parse_with_preset(&[
+            "stress",
+            "--preset",
+            "owned-max",
+            "bench",
+            "--shared-counter",
+            "50",
+            "--num-workers",
+            "3",
+        ]);
+        match opts.run_spec {
+            RunSpec::Bench {
+                weights,
+                num_workers,
+                in_flight_ratio,
+                ..
+            } => {
+                assert_eq!(weights.get(WorkloadType::SharedCounter), 0);
+                assert_eq!(weights.get(WorkloadType::TransferObject), 1);
+                // Explicit flags win over the preset defaults
+                assert_eq!(num_workers, 3);
+                assert_eq!(in_flight_ratio, 10);
+            }
+            _ => panic!("expected a bench run"),
+        }
+        let opts = parse_with_preset(&["stress", "bench", "--shared-counter", "50"]);
+        assert!(matches!(
+            &opts.run_spec,
+            RunSpec::Bench {
+                weights,
+                in_flight_ratio: 5,
+                ..
+            } if weights.get(WorkloadType::SharedCounter) == 50
+        ));
+    }
+
+    #[test]
+    fn test_normalize_weights() {
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "bench",
+            "--shared-counter",
+            "2000",
+            "--transfer-object",
+            "1000",
+            "--read",
+            "1",
+        ]);
+        let weights = opts.normalize_weights();
+        assert_eq!(
+            weights,
+            vec![
+                (WorkloadType::SharedCounter, 66),
+                (WorkloadType::TransferObject, 33),
+                (WorkloadType::Read, 1),
+            ]
+        );
+        match &opts.run_spec {
+            RunSpec::Bench { weights, .. } => {
+                assert_eq!(weights.get(WorkloadType::SharedCounter), 66);
+                assert_eq!(weights.get(WorkloadType::TransferObject), 33);
+                assert_eq!(weights.get(WorkloadType::Read), 1);
+                assert_eq!(weights.get(WorkloadType::Delegation), 0);
+            }
+            _ => panic!("expected a bench run"),
+        }
+        // Already normalized weights are left alone
+        assert_eq!(opts.normalize_weights(), weights);
+    }
+
+    #[test]
+    fn test_smoke() {
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "--smoke",
+            "--run-duration",
+            "1h",
+            "bench",
+            "--shared-counter",
+            "50",
+            "--transfer-object",
+            "30",
+            "--target-qps",
+            "5000",
+        ]);
+        let workloads = opts.apply_smoke();
+        assert_eq!(
+            workloads,
+            vec![WorkloadType::SharedCounter, WorkloadType::TransferObject]
+        );
+        match opts.run_spec {
+            RunSpec::Bench {
+                weights,
+                target_qps,
+                num_workers,
+                in_flight_ratio,
+                ..
+            } => {
+                assert_eq!(
+                    (
+                        weights.get(WorkloadType::SharedCounter),
+                        weights.get(WorkloadType::TransferObject),
+                        weights.get(WorkloadType::Delegation)
+                    ),
+                    (1, 1, 0)
+                );
+                assert_eq!((target_qps, num_workers, in_flight_ratio), (2, 2, 1));
+            }
+            _ => panic!("expected a bench run"),
+        }
+        assert!(opts.disjoint_mode);
+        assert_eq!(opts.run_duration, Interval::Time(SMOKE_DURATION));
+    }
+
+    #[test]
+    fn test_retry_config() {
+        // No retries unless asked for
+        let opts = parse_with_preset(&["stress", "bench"]);
+        assert_eq!(opts.retry_config(), None);
+        let opts = parse_with_preset(&[
+            "stress",
+            "bench",
+            "--max-retries",
+            "3",
+            "--retry-errors",
+            "locked",
+        ]);
+        assert_eq!(
+            opts.retry_config(),
+            Some(RetryConfig {
+                max_retries: 3,
+                initial_backoff: Duration::from_millis(100),
+                errors: vec![TransientError::ObjectLocked],
+            })
+        );
+    }
+
+    #[test]
+    fn test_in_flight_ratios() {
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "bench",
+            "--shared-counter",
+            "1",
+            "--shared-counter-in-flight-ratio",
+            "2",
+        ]);
+        match &opts.run_spec {
+            RunSpec::Bench {
+                in_flight_ratios, ..
+            } => {
+                assert_eq!(in_flight_ratios.of(WorkloadType::SharedCounter), Some(2));
+                // The others fall back to --in-flight-ratio
+                assert_eq!(in_flight_ratios.of(WorkloadType::TransferObject), None);
+            }
+            _ => panic!("expected a bench run"),
+        }
+        opts.apply_smoke();
+        assert!(matches!(
+            &opts.run_spec,
+            RunSpec::Bench { in_flight_ratios, .. } if in_flight_ratios.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_gas_budgets() {
+        // A global flag, given before or after the subcommand
+        for args in [
+            ["stress", "--noop-gas-budget", "5000", "bench"],
+            ["stress", "bench", "--noop-gas-budget", "5000"],
+        ] {
+            let opts = parse_with_preset(&args);
+            assert_eq!(opts.gas_budgets.of(WorkloadType::Noop), Some(5000));
+            assert_eq!(opts.gas_budgets.of(WorkloadType::Batch), None);
+        }
+        // Reads are queries, they have no gas budget
+        let args = ["stress", "bench", "--read-gas-budget", "5000"];
+        assert!(Opts::command().try_get_matches_from(args).is_err());
+
+        // Serialized as the fields of the same name
+        let opts = parse_with_preset(&[
+            "stress",
+            "bench",
+            "--noop-gas-budget",
+            "5000",
+            "--shared-counter",
+            "2",
+        ]);
+        let config = serde_json::to_value(&opts).unwrap();
+        assert_eq!(config["noop_gas_budget"], 5000);
+        assert_eq!(config["run_spec"]["Bench"]["shared_counter"], 2);
+        assert_eq!(config["run_spec"]["Bench"]["transfer_object"], 1);
+    }
+
+    #[test]
+    fn test_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scenario.json");
+        let path_str = path.to_str().unwrap();
+        std::fs::write(
+            &path,
+            r#"{"shared_counter": 2, "transfer_object": 0, "target_qps": 500, "max_in_flight": 40}"#,
+        )
+        .unwrap();
+        let args = [
+            "stress",
+            "--config-file",
+            path_str,
+            "bench",
+            "--target-qps",
+            "100",
+        ];
+        let matches = Opts::command().try_get_matches_from(args).unwrap();
+        let mut opts = Opts::from_arg_matches(&matches).unwrap();
+        opts.apply_config_file(&matches).unwrap();
+        match opts.run_spec {
+            RunSpec::Bench {
+                weights,
+                target_qps,
+                max_in_flight,
+                num_workers,
+                ..
+            } => {
+                assert_eq!(weights.get(WorkloadType::SharedCounter), 2);
+                assert_eq!(weights.get(WorkloadType::TransferObject), 0);
+                // The flag wins over the file
+                assert_eq!(target_qps, 100);
+                assert_eq!(max_in_flight, Some(40));
+                assert_eq!(num_workers, 12);
+            }
+            _ => panic!("expected a bench run"),
+        }
+
+        let args = ["stress", "--config-file", path_str, "bench"];
+        for config in [
+            r#"{"transfer_object": 0}"#,
+            r#"{"shared_counter": -1}"#,
+            r#"{"shared_countr": 1}"#,
+        ] {
+            std::fs::write(&path, config).unwrap();
+            let matches = Opts::command().try_get_matches_from(args).unwrap();
+            let mut opts = Opts::from_arg_matches(&matches).unwrap();
+            assert!(opts.apply_config_file(&matches).is_err());
+        }
+    }
+
+    #[test]
+    fn test_metrics_port() {
+        let opts = parse_with_preset(&["stress", "bench"]);
+        assert_eq!(opts.client_metric_port, None);
+        let opts = parse_with_preset(&["stress", "bench", "--metrics-port", "9184"]);
+        assert_eq!(opts.client_metric_port, Some(9184));
+    }
+
+    #[test]
+    fn test_custom_calls() {
+        let opts = parse_with_preset(&[
+            "stress",
+            "bench",
+            "--custom-calls",
+            "0x2::coin::zero:3,0x2::coin::value:1",
+        ]);
+        let calls = opts.custom_calls.unwrap();
+        assert_eq!(calls.0.len(), 2);
+        assert_eq!(calls.total_weight(), 4);
+        let args = ["stress", "bench", "--custom-calls", "0x2::coin::zero:0"];
+        assert!(Opts::command().try_get_matches_from(args).is_err());
+    }
+
+    #[test]
+    fn test_contention_objects() {
+        let opts = parse_with_preset(&["stress", "bench", "--contention-objects", "1"]);
+        assert!(matches!(
+            opts.run_spec,
+            RunSpec::Bench {
+                contention_objects: Some(1),
+                shared_counter_hotness_factor: 50,
+                ..
+            }
+        ));
+        let args = [
+            "stress",
+            "bench",
+            "--contention-objects",
+            "1",
+            "--shared-counter-hotness-factor",
+            "100",
+        ];
+        assert!(Opts::command().try_get_matches_from(args).is_err());
+
+        // Nor can the hotness factor come from a config file
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scenario.json");
+        std::fs::write(&path, r#"{"shared_counter_hotness_factor": 100}"#).unwrap();
+        let args = [
+            "stress",
+            "--config-file",
+            path.to_str().unwrap(),
+            "bench",
+            "--contention-objects",
+            "1",
+        ];
+        let matches = Opts::command().try_get_matches_from(args).unwrap();
+        let mut opts = Opts::from_arg_matches(&matches).unwrap();
+        assert!(opts.apply_config_file(&matches).is_err());
+
+        // Read from a config file, they conflict with the hotness factor
+        // the same way
+        std::fs::write(&path, r#"{"contention_objects": 4}"#).unwrap();
+        let args = ["stress", "--config-file", path.to_str().unwrap(), "bench"];
+        let matches = Opts::command().try_get_matches_from(args).unwrap();
+        let mut opts = Opts::from_arg_matches(&matches).unwrap();
+        opts.apply_config_file(&matches).unwrap();
+        assert!(matches!(
+            opts.run_spec,
+            RunSpec::Bench {
+                contention_objects: Some(4),
+                ..
+            }
+        ));
+        for (config, hotness) in [
+            (
+                r#"{"contention_objects": 4, "shared_counter_hotness_factor": 100}"#,
+                None,
+            ),
+            (r#"{"contention_objects": 4}"#, Some("100")),
+        ] {
+            std::fs::write(&path, config).unwrap();
+            let mut args = vec!["stress", "--config-file", path.to_str().unwrap(), "bench"];
+            if let Some(hotness) = hotness {
+                args.extend(["--shared-counter-hotness-factor", hotness]);
+            }
+            let matches = Opts::command().try_get_matches_from(args).unwrap();
+            let mut opts = Opts::from_arg_matches(&matches).unwrap();
+            assert!(opts.apply_config_file(&matches).is_err());
+        }
+    }
+
+    #[test]
+    fn test_config_file_num_transfer_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scenario.json");
+        let path_str = path.to_str().unwrap();
+        std::fs::write(&path, r#"{"num_transfer_accounts": 20}"#).unwrap();
+        let args = ["stress", "--config-file", path_str, "bench"];
+        let matches = Opts::command().try_get_matches_from(args).unwrap();
+        let mut opts = Opts::from_arg_matches(&matches).unwrap();
+        opts.apply_config_file(&matches).unwrap();
+        assert_eq!(opts.num_transfer_accounts, 20);
+        // The flag wins over the file, before or after the subcommand
+        for args in [
+            [
+                "stress",
+                "--config-file",
+                path_str,
+                "--num-transfer-accounts",
+                "3",
+                "bench",
+            ],
+            [
+                "stress",
+                "--config-file",
+                path_str,
+                "bench",
+                "--num-transfer-accounts",
+                "3",
+            ],
+        ] {
+            let matches = Opts::command().try_get_matches_from(args).unwrap();
+            let mut opts = Opts::from_arg_matches(&matches).unwrap();
+            opts.apply_config_file(&matches).unwrap();
+            assert_eq!(opts.num_transfer_accounts, 3);
+        }
+    }
+
+    #[test]
+    fn test_duration_secs() {
+        let mut opts = parse_with_preset(&["stress", "bench", "--duration-secs", "30"]);
+        assert_eq!(opts.apply_duration(), Ok(true));
+        assert_eq!(opts.run_duration, Interval::Time(Duration::from_secs(30)));
+
+        let mut opts = parse_with_preset(&["stress", "bench"]);
+        assert_eq!(opts.apply_duration(), Ok(false));
+        assert!(opts.run_duration.is_unbounded());
+
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "--run-duration",
+            "60s",
+            "bench",
+            "--duration-secs",
+            "30",
+        ]);
+        assert!(opts.apply_duration().is_err());
+    }
+
+    #[test]
+    fn test_closed_loop_runs_as_bench() {
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "closed-loop",
+            "--in-flight",
+            "200",
+            "--num-workers",
+            "4",
+            "--duration",
+            "30s",
+            "--mix",
+            "shared_counter=1,transfer_object=3",
+        ]);
+        opts.apply_closed_loop();
+        assert!(opts.closed_loop);
+        assert_eq!(opts.run_duration, Interval::Time(Duration::from_secs(30)));
+        match &opts.run_spec {
+            RunSpec::Bench {
+                weights,
+                shared_counter_hotness_factor,
+                num_workers,
+                max_in_flight,
+                ..
+            } => {
+                assert_eq!(weights.get(WorkloadType::SharedCounter), 1);
+                assert_eq!(weights.get(WorkloadType::TransferObject), 3);
+                assert_eq!(weights.get(WorkloadType::Noop), 0);
+                assert_eq!(*shared_counter_hotness_factor, 50);
+                assert_eq!((*num_workers, *max_in_flight), (4, Some(200)));
+            }
+            _ => panic!("expected a bench run"),
+        }
+        // The shared counter settings carry over to the bench run
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "closed-loop",
+            "--mix",
+            "shared_counter=1",
+            "--shared-counter-hotness-factor",
+            "90",
+        ]);
+        opts.apply_closed_loop();
+        assert!(matches!(
+            opts.run_spec,
+            RunSpec::Bench {
+                shared_counter_hotness_factor: 90,
+                contention_objects: None,
+                ..
+            }
+        ));
+        let mut opts = parse_with_preset(&[
+            "stress",
+            "closed-loop",
+            "--mix",
+            "shared_counter=1",
+            "--contention-objects",
+            "2",
+        ]);
+        opts.apply_closed_loop();
+        assert!(matches!(
+            opts.run_spec,
+            RunSpec::Bench {
+                contention_objects: Some(2),
+                ..
+            }
+        ));
+        assert!("collection=x".parse::<WorkloadMix>().is_err());
+        assert!("combination=1".parse::<WorkloadMix>().is_err());
+    }
+
+    #[test]
+    fn test_workload_flags() {
+        let workload_flags = WorkloadRegistry::default().workload_flags();
+        // Every declared flag exists, and the weight comes first
+        for (workload, flags) in &workload_flags {
+            let described = describe_flags(flags);
+            assert_eq!(described.len(), flags.len(), "{:?}", flags);
+            let weight = workload.to_string().replace('_', "-");
+            assert_eq!(flags.first(), Some(&weight.as_str()));
+        }
+        let listed: Vec<&str> = workload_flags
+            .iter()
+            .flat_map(|(_, flags)| flags.iter().copied())
+            .collect();
+        // Every per-workload gas budget and in-flight ratio is listed
+        let command = Opts::command();
+        let bench = command
+            .get_subcommands()
+            .find(|c| c.get_name() == "bench")
+            .unwrap();
+        for arg in command.get_arguments().chain(bench.get_arguments()) {
+            let long = match arg.get_long() {
+                Some(long) => long,
+                None => continue,
+            };
+            if (long.ends_with("-gas-budget") && long != "gas-budget")
+                || long.ends_with("-in-flight-ratio")
+            {
+                assert!(listed.contains(&long), "--{} is not listed", long);
+            }
+        }
+        // Tunables not named after their workload
+        for (workload, flag) in [
+            (WorkloadType::ObjectFanout, "create-fanout"),
+            (WorkloadType::Batch, "commands-per-tx"),
+            (WorkloadType::CoinChurn, "split-count"),
+            (WorkloadType::SharedCounter, "contention-objects"),
+            (WorkloadType::TransferObject, "num-transfer-accounts"),
+            (WorkloadType::TransferObject, "pay-amount-dist"),
+        ] {
+            assert!(
+                workload.flags().contains(&flag),
+                "{} lacks --{}",
+                workload,
+                flag
+            );
+        }
+    }
 }
@@ -0,0 +1,140 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::retrying_proxy::RetryStats;
+use crate::{EffectsWait, ExecutionEffects, ObjectQuery, ValidatorProxy};
+use async_trait::async_trait;
+use std::sync::Arc;
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+
+/// Serves read-only object queries from a read replica, typically a fullnode,
+/// and everything else, including the object reads workloads build their
+/// transactions from, from the write proxy executing transactions
+pub struct ReadRoutingProxy {
+    write: Arc<dyn ValidatorProxy + Send + Sync>,
+    read: Arc<dyn ValidatorProxy + Send + Sync>,
+}
+
+impl ReadRoutingProxy {
+    pub fn new(
+        write: Arc<dyn ValidatorProxy + Send + Sync>,
+        read: Arc<dyn ValidatorProxy + Send + Sync>,
+    ) -> Self {
+        Self { write, read }
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for ReadRoutingProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        self.write.get_object(object_id).await
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        self.write.execute_transaction(tx).await
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.write.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.write.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            write: Arc::from(self.write.clone_new()),
+            read: Arc::from(self.read.clone_new()),
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        self.write.get_validators().await
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.write.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.write.excluded_connections()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        self.write.clock_skew()
+    }
+
+    fn retry_stats(&self) -> Option<RetryStats> {
+        self.write.retry_stats()
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            write: Arc::from(self.write.with_effects_wait(wait)?),
+            read: Arc::from(self.read.clone_new()),
+        }))
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        self.read.query_object(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording_proxy::{RecordedCall, ReplayProxy};
+
+    #[tokio::test]
+    async fn test_read_routing_proxy() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let object_id = ObjectID::random();
+        let object = Object::immutable_with_id_for_testing(object_id);
+        let query = ObjectQuery::GetObject(object_id);
+        let write = ReplayProxy::from_calls(vec![
+            RecordedCall::Committee(committee.clone()),
+            RecordedCall::GetObject(object_id, Ok(object.clone())),
+            RecordedCall::QueryObject(query, Err("not a read replica".to_string())),
+        ])
+        .unwrap();
+        let read = ReplayProxy::from_calls(vec![
+            RecordedCall::Committee(committee),
+            RecordedCall::GetObject(object_id, Err("not a write proxy".to_string())),
+            RecordedCall::QueryObject(query, Ok(())),
+        ])
+        .unwrap();
+
+        let proxy = ReadRoutingProxy::new(Arc::new(write), Arc::new(read)).clone_new();
+        assert_eq!(proxy.get_object(object_id).await.unwrap(), object);
+        assert!(proxy.query_object(query).await.is_ok());
+    }
+
+    #[test]
+    fn test_effects_wait_follows_write_proxy() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let replay = || {
+            Arc::new(
+                ReplayProxy::from_calls(vec![RecordedCall::Committee(committee.clone())]).unwrap(),
+            )
+        };
+        let proxy = ReadRoutingProxy::new(replay(), replay());
+        assert_eq!(proxy.effects_wait(), EffectsWait::Certified);
+        assert!(proxy.with_effects_wait(EffectsWait::Certified).is_ok());
+        assert!(proxy.with_effects_wait(EffectsWait::Checkpoint).is_err());
+        assert_eq!("checkpoint".parse(), Ok(EffectsWait::Checkpoint));
+        assert!("quorum".parse::<EffectsWait>().is_err());
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::retrying_proxy::RetryStats;
+use crate::{EffectsWait, ExecutionEffects, ObjectQuery, ValidatorProxy};
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress, TransactionDigest};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+
+type ExecutionResult = Result<(SuiCertifiedTransaction, ExecutionEffects), String>;
+
+/// One line of a recorded session. Errors are kept as their message.
+#[derive(Serialize, Deserialize)]
+pub enum RecordedCall {
+    // Written once when recording starts
+    Committee(Committee),
+    GetObject(ObjectID, Result<Object, String>),
+    ExecuteTransaction(TransactionDigest, ExecutionResult),
+    GetValidators(Result<Vec<SuiAddress>, String>),
+    QueryObject(ObjectQuery, Result<(), String>),
+}
+
+/// Wraps any proxy and appends every call it serves, with its outcome, as a
+/// JSON line to a session file that `ReplayProxy` can serve offline
+pub struct RecordingProxy {
+    inner: Arc<dyn ValidatorProxy + Send + Sync>,
+    writer: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl RecordingProxy {
+    pub fn new(inner: Arc<dyn ValidatorProxy + Send + Sync>, path: &Path) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create session file {}", path.display()))?;
+        let proxy = Self {
+            writer: Arc::new(Mutex::new(BufWriter::new(file))),
+            inner,
+        };
+        proxy.record(&RecordedCall::Committee(proxy.inner.clone_committee()));
+        Ok(proxy)
+    }
+
+    fn record(&self, call: &RecordedCall) {
+        let mut writer = self.writer.lock().unwrap();
+        // Flush every call so the session survives a crashed run
+        let res = serde_json::to_writer(&mut *writer, call)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(writer))
+            .and_then(|_| writer.flush());
+        if let Err(err) = res {
+            tracing::error!("Failed to record proxy call: {}", err);
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for RecordingProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        let res = self.inner.get_object(object_id).await;
+        self.record(&RecordedCall::GetObject(
+            object_id,
+            res.as_ref().map(Clone::clone).map_err(ToString::to_string),
+        ));
+        res
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        let digest = *tx.digest();
+        let res = self.inner.execute_transaction(tx).await;
+        self.record(&RecordedCall::ExecuteTransaction(
+            digest,
+            res.as_ref().map(Clone::clone).map_err(ToString::to_string),
+        ));
+        res
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.inner.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.inner.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            inner: Arc::from(self.inner.clone_new()),
+            writer: self.writer.clone(),
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        let res = self.inner.get_validators().await;
+        self.record(&RecordedCall::GetValidators(
+            res.as_ref().map(Clone::clone).map_err(ToString::to_string),
+        ));
+        res
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.inner.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.inner.excluded_connections()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        self.inner.clock_skew()
+    }
+
+    fn retry_stats(&self) -> Option<RetryStats> {
+        self.inner.retry_stats()
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            inner: Arc::from(self.inner.with_effects_wait(wait)?),
+            writer: self.writer.clone(),
+        }))
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        let res = self.inner.query_object(query).await;
+        self.record(&RecordedCall::QueryObject(
+            query,
+            res.as_ref().map(|_| ()).map_err(ToString::to_string),
+        ));
+        res
+    }
+}
+
+#[derive(Default)]
+struct ReplaySession {
+    objects: HashMap<ObjectID, VecDeque<Result<Object, String>>>,
+    executions: HashMap<TransactionDigest, VecDeque<ExecutionResult>>,
+    validators: VecDeque<Result<Vec<SuiAddress>, String>>,
+    queries: HashMap<ObjectQuery, VecDeque<Result<(), String>>>,
+}
+
+/// Returns the next recorded outcome of a call, the last outcome is served
+/// again once the earlier ones are used up
+fn next_outcome<T: Clone>(outcomes: Option<&mut VecDeque<T>>) -> Option<T> {
+    let outcomes = outcomes?;
+    if outcomes.len() > 1 {
+        outcomes.pop_front()
+    } else {
+        outcomes.front().cloned()
+    }
+}
+
+/// Serves the outcomes of a session recorded by `RecordingProxy` without a
+/// network. Transactions are matched by digest, so a replayed run must issue
+/// the same transactions, and repeated calls return the outcomes in the order
+/// they were recorded. Epoch changes are not replayed.
+#[derive(Clone)]
+pub struct ReplayProxy {
+    committee: Committee,
+    session: Arc<Mutex<ReplaySession>>,
+}
+
+impl ReplayProxy {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open session file {}", path.display()))?;
+        let calls = BufReader::new(file)
+            .lines()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<anyhow::Result<Vec<RecordedCall>>>()?;
+        Self::from_calls(calls)
+    }
+
+    pub fn from_calls(calls: Vec<RecordedCall>) -> anyhow::Result<Self> {
+        let mut committee = None;
+        let mut session = ReplaySession::default();
+        for call in calls {
+            match call {
+                RecordedCall::Committee(c) => committee = Some(c),
+                RecordedCall::GetObject(id, res) => {
+                    session.objects.entry(id).or_default().push_back(res)
+                }
+                RecordedCall::ExecuteTransaction(digest, res) => {
+                    session.executions.entry(digest).or_default().push_back(res)
+                }
+                RecordedCall::GetValidators(res) => session.validators.push_back(res),
+                RecordedCall::QueryObject(query, res) => {
+                    session.queries.entry(query).or_default().push_back(res)
+                }
+            }
+        }
+        Ok(Self {
+            committee: committee.ok_or_else(|| anyhow!("Session has no committee record"))?,
+            session: Arc::new(Mutex::new(session)),
+        })
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for ReplayProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        let mut session = self.session.lock().unwrap();
+        next_outcome(session.objects.get_mut(&object_id))
+            .ok_or_else(|| anyhow!("No recorded reads of object {}", object_id))?
+            .map_err(|err| anyhow!(err))
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        let mut session = self.session.lock().unwrap();
+        next_outcome(session.executions.get_mut(tx.digest()))
+            .ok_or_else(|| anyhow!("Transaction {:?} was not recorded", tx.digest()))?
+            .map_err(|err| anyhow!(err))
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.committee.clone()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.committee.epoch
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        let mut session = self.session.lock().unwrap();
+        next_outcome(Some(&mut session.validators))
+            .ok_or_else(|| anyhow!("No recorded validator lists"))?
+            .map_err(|err| anyhow!(err))
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        // Recorded effects are served as they were returned, whatever they
+        // waited for when recorded
+        EffectsWait::Certified
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        let mut session = self.session.lock().unwrap();
+        next_outcome(session.queries.get_mut(&query))
+            .ok_or_else(|| anyhow!("Query {:?} was not recorded", query))?
+            .map_err(|err| anyhow!(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replay_recorded_session() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let object_id = ObjectID::random();
+        let object = Object::immutable_with_id_for_testing(object_id);
+        let validators = vec![SuiAddress::random_for_testing_only()];
+        let source = ReplayProxy::from_calls(vec![
+            RecordedCall::Committee(committee.clone()),
+            RecordedCall::GetObject(object_id, Err("not yet created".to_string())),
+            RecordedCall::GetObject(object_id, Ok(object.clone())),
+            RecordedCall::GetValidators(Ok(validators.clone())),
+        ])
+        .unwrap();
+
+        // Record a session against the source, then replay it from the file
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let recording = RecordingProxy::new(Arc::new(source), file.path()).unwrap();
+        assert!(recording.get_object(object_id).await.is_err());
+        assert_eq!(recording.get_object(object_id).await.unwrap(), object);
+        assert!(recording.get_object(ObjectID::random()).await.is_err());
+        assert_eq!(recording.get_validators().await.unwrap(), validators);
+
+        let replay = ReplayProxy::from_file(file.path()).unwrap();
+        assert_eq!(replay.clone_committee(), committee);
+        assert!(replay.get_object(object_id).await.is_err());
+        // The last outcome keeps being served
+        for _ in 0..2 {
+            assert_eq!(replay.get_object(object_id).await.unwrap(), object);
+        }
+        assert_eq!(replay.get_validators().await.unwrap(), validators);
+    }
+}
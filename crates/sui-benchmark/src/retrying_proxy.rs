@@ -0,0 +1,223 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::clock_skew::ClockSkew;
+use crate::connection_health::ConnectionExclusion;
+use crate::{EffectsWait, ExecutionEffects, ObjectQuery, ValidatorProxy};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+use tracing::debug;
+
+/// Kind of submission error expected to clear up by itself, told apart by
+/// the error message since proxies return them as `anyhow` errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TransientError {
+    // An input object is locked by another transaction in flight
+    ObjectLocked,
+    Timeout,
+}
+
+impl TransientError {
+    pub fn matches(&self, err: &anyhow::Error) -> bool {
+        let message = format!("{:#}", err).to_lowercase();
+        match self {
+            TransientError::ObjectLocked => message.contains("locked"),
+            TransientError::Timeout => message.contains("timed out") || message.contains("timeout"),
+        }
+    }
+}
+
+impl FromStr for TransientError {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "locked" => Ok(TransientError::ObjectLocked),
+            "timeout" => Ok(TransientError::Timeout),
+            _ => Err(format!(
+                "Unknown transient error {s}, expected locked or timeout"
+            )),
+        }
+    }
+}
+
+/// Retries of transactions failing with a transient error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    // Backoff before the first retry, doubled on each one after it
+    pub initial_backoff: Duration,
+    pub errors: Vec<TransientError>,
+}
+
+impl RetryConfig {
+    /// Backoff before retry number `retry`, counted from zero
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+    }
+
+    pub fn is_transient(&self, err: &anyhow::Error) -> bool {
+        self.errors.iter().any(|kind| kind.matches(err))
+    }
+}
+
+/// Outcome of the transactions submitted through a `RetryingProxy`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryStats {
+    pub succeeded_first_try: u64,
+    pub succeeded_after_retry: u64,
+    // Resubmissions, a transaction may be retried several times
+    pub retries: u64,
+    // Transactions still failing with a transient error after max_retries
+    pub exhausted: u64,
+}
+
+/// Wraps any proxy and resubmits transactions failing with a transient
+/// error, backing off exponentially. The driver sees one submission per
+/// transaction however many times it was retried
+pub struct RetryingProxy {
+    inner: Arc<dyn ValidatorProxy + Send + Sync>,
+    config: RetryConfig,
+    // Shared by the clones of the proxy
+    stats: Arc<Mutex<RetryStats>>,
+}
+
+impl RetryingProxy {
+    pub fn new(inner: Arc<dyn ValidatorProxy + Send + Sync>, config: RetryConfig) -> Self {
+        Self {
+            inner,
+            config,
+            stats: Arc::new(Mutex::new(RetryStats::default())),
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for RetryingProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        self.inner.get_object(object_id).await
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        let mut retry = 0;
+        loop {
+            match self.inner.execute_transaction(tx.clone()).await {
+                Ok(res) => {
+                    let mut stats = self.stats.lock().unwrap();
+                    if retry == 0 {
+                        stats.succeeded_first_try += 1;
+                    } else {
+                        stats.succeeded_after_retry += 1;
+                    }
+                    return Ok(res);
+                }
+                Err(err) if self.config.is_transient(&err) => {
+                    if retry == self.config.max_retries {
+                        self.stats.lock().unwrap().exhausted += 1;
+                        return Err(err);
+                    }
+                    let backoff = self.config.backoff(retry);
+                    debug!(
+                        "Retrying tx {:?} in {:?} after a transient error: {:#}",
+                        tx.digest(),
+                        backoff,
+                        err
+                    );
+                    self.stats.lock().unwrap().retries += 1;
+                    tokio::time::sleep(backoff).await;
+                    retry += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.inner.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.inner.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            inner: Arc::from(self.inner.clone_new()),
+            config: self.config.clone(),
+            stats: self.stats.clone(),
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        self.inner.get_validators().await
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.inner.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.inner.excluded_connections()
+    }
+
+    fn clock_skew(&self) -> Option<ClockSkew> {
+        self.inner.clock_skew()
+    }
+
+    fn retry_stats(&self) -> Option<RetryStats> {
+        Some(self.stats.lock().unwrap().clone())
+    }
+
+    fn with_effects_wait(
+        &self,
+        wait: EffectsWait,
+    ) -> Result<Box<dyn ValidatorProxy + Send + Sync>, anyhow::Error> {
+        Ok(Box::new(Self {
+            inner: Arc::from(self.inner.with_effects_wait(wait)?),
+            config: self.config.clone(),
+            stats: self.stats.clone(),
+        }))
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        self.inner.query_object(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_retry_config() {
+        let config = RetryConfig {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            errors: vec![TransientError::from_str("locked").unwrap()],
+        };
+        assert_eq!(config.backoff(0), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(400));
+        assert_eq!(config.backoff(u32::MAX), Duration::MAX);
+
+        let locked = anyhow!("Object (0x1, 2) already locked by a different transaction");
+        assert!(config.is_transient(&locked));
+        // Only the configured kinds are retried
+        let timeout = anyhow!("Operation timed out");
+        assert!(!config.is_transient(&timeout));
+        assert!(TransientError::Timeout.matches(&timeout));
+        assert!(TransientError::from_str("overloaded").is_err());
+    }
+}
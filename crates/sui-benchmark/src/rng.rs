@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::rngs::StdRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use std::sync::Mutex;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{get_key_pair_from_rng, KeypairTraits, SuiPublicKey};
+
+// Rng seeded with --global-seed, drawn from by every BenchRng
+static GLOBAL_RNG: RngSource = RngSource::new();
+
+/// One stream shared by all threads once seeded, so a draw does not depend
+/// on which runtime thread the drawing task happens to run on
+#[derive(Debug)]
+pub struct RngSource {
+    seeded: Mutex<Option<StdRng>>,
+}
+
+impl RngSource {
+    pub const fn new() -> Self {
+        Self {
+            seeded: Mutex::new(None),
+        }
+    }
+
+    /// Restarts the stream from `seed`
+    pub fn set_seed(&self, seed: u64) {
+        *self.seeded.lock().unwrap() = Some(StdRng::seed_from_u64(seed));
+    }
+
+    fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        match &mut *self.seeded.lock().unwrap() {
+            Some(rng) => f(rng),
+            None => f(&mut rand::thread_rng()),
+        }
+    }
+}
+
+impl Default for RngSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Seeds every rng of the crate, accounts, object and recipient selection,
+/// gas budgets and delays included
+pub fn set_global_seed(seed: u64) {
+    GLOBAL_RNG.set_seed(seed);
+}
+
+/// Rng of the benchmark, deterministic once --global-seed is set and backed
+/// by `thread_rng` otherwise. Draws made in a fixed order, e.g. during gas
+/// generation and workload init, are reproducible. Draws of concurrent
+/// workers depend on the order the runtime schedules them in
+#[derive(Debug, Clone, Copy)]
+pub struct BenchRng {
+    source: &'static RngSource,
+}
+
+impl BenchRng {
+    /// Rng drawing from `source` instead of the global one
+    pub fn from_source(source: &'static RngSource) -> Self {
+        Self { source }
+    }
+
+    fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        self.source.with_rng(f)
+    }
+}
+
+impl Default for BenchRng {
+    fn default() -> Self {
+        bench_rng()
+    }
+}
+
+impl RngCore for BenchRng {
+    fn next_u32(&mut self) -> u32 {
+        self.with_rng(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.with_rng(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.with_rng(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.with_rng(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+// Both backing rngs are cryptographically secure
+impl CryptoRng for BenchRng {}
+
+pub fn bench_rng() -> BenchRng {
+    BenchRng::from_source(&GLOBAL_RNG)
+}
+
+/// `sui_types::crypto::get_key_pair` drawing from the benchmark rng
+pub fn get_key_pair<KP: KeypairTraits>() -> (SuiAddress, KP)
+where
+    <KP as KeypairTraits>::PubKey: SuiPublicKey,
+{
+    get_key_pair_from_rng(&mut bench_rng())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::test_utils::make_transfer_object_transaction_with_gas_budget;
+    use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber};
+    use sui_types::crypto::AccountKeyPair;
+
+    // Seeded sources of their own, the global one is shared by every test
+    fn seeded_rng(seed: u64) -> BenchRng {
+        let source: &'static RngSource = Box::leak(Box::default());
+        source.set_seed(seed);
+        BenchRng::from_source(source)
+    }
+
+    fn seeded_digest(seed: u64) -> String {
+        let mut rng = seeded_rng(seed);
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair_from_rng(&mut rng);
+        let (recipient, _): (_, AccountKeyPair) = get_key_pair_from_rng(&mut rng);
+        let object = |rng: &mut BenchRng| {
+            let mut bytes = [0u8; ObjectID::LENGTH];
+            rng.fill_bytes(&mut bytes);
+            (
+                ObjectID::from_bytes(bytes).unwrap(),
+                SequenceNumber::from(1),
+                ObjectDigest::MIN,
+            )
+        };
+        let transaction = make_transfer_object_transaction_with_gas_budget(
+            object(&mut rng),
+            object(&mut rng),
+            sender,
+            &keypair,
+            recipient,
+            10_000,
+            Some(1),
+        );
+        transaction.digest().to_string()
+    }
+
+    #[test]
+    fn test_global_seed_reproduces_transactions() {
+        assert_eq!(seeded_digest(7), seeded_digest(7));
+        assert_ne!(seeded_digest(7), seeded_digest(8));
+    }
+
+    #[test]
+    fn test_seeded_draws_do_not_depend_on_the_thread() {
+        let rng = seeded_rng(7);
+        let mut expected = seeded_rng(7);
+        let expected: Vec<u64> = (0..4).map(|_| expected.next_u64()).collect();
+        // Alternate threads, as a task moved between runtime threads would
+        let mut draws = vec![];
+        for _ in 0..2 {
+            let mut local = rng;
+            draws.push(local.next_u64());
+            let mut other = rng;
+            draws.push(std::thread::spawn(move || other.next_u64()).join().unwrap());
+        }
+        assert_eq!(draws, expected);
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::connection_health::ConnectionExclusion;
+use crate::{
+    EffectsWait, ExecutionEffects, LocalValidatorAggregatorProxy, ObjectQuery, ValidatorProxy,
+};
+use async_trait::async_trait;
+use prometheus::Registry;
+use rand::{thread_rng, Rng};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_json_rpc_types::SuiCertifiedTransaction;
+use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::messages::Transaction;
+use sui_types::object::Object;
+use test_utils::network::TestCluster;
+
+/// Client-side faults injected by `SimulatorValidatorProxy` on top of the
+/// network faults configured for the simulation itself
+#[derive(Debug, Clone, Default)]
+pub struct InjectedFaults {
+    // Probability with which a transaction is delayed before submission
+    pub delay_probability: f64,
+    pub delay_ms: Range<u64>,
+    // Probability with which a transaction fails without being submitted
+    pub error_probability: f64,
+}
+
+/// Drives a `TestCluster` running inside the simulator. The simulator seeds
+/// `thread_rng`, so injected faults are reproducible for a given seed.
+pub struct SimulatorValidatorProxy {
+    inner: Arc<LocalValidatorAggregatorProxy>,
+    faults: InjectedFaults,
+}
+
+impl SimulatorValidatorProxy {
+    pub async fn from_test_cluster(
+        test_cluster: &TestCluster,
+        registry: &Registry,
+        faults: InjectedFaults,
+    ) -> Self {
+        let inner = LocalValidatorAggregatorProxy::from_network_config(
+            test_cluster.swarm.config(),
+            registry,
+            Some(&test_cluster.fullnode_handle.rpc_url),
+            1,
+        )
+        .await;
+        Self {
+            inner: Arc::new(inner),
+            faults,
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorProxy for SimulatorValidatorProxy {
+    async fn get_object(&self, object_id: ObjectID) -> Result<Object, anyhow::Error> {
+        self.inner.get_object(object_id).await
+    }
+
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> anyhow::Result<(SuiCertifiedTransaction, ExecutionEffects)> {
+        let (delay, fail) = {
+            let mut rng = thread_rng();
+            let delay = (!self.faults.delay_ms.is_empty()
+                && rng.gen_bool(self.faults.delay_probability))
+            .then(|| Duration::from_millis(rng.gen_range(self.faults.delay_ms.clone())));
+            (delay, rng.gen_bool(self.faults.error_probability))
+        };
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        if fail {
+            anyhow::bail!("Injected fault for tx {:?}", tx.digest());
+        }
+        self.inner.execute_transaction(tx).await
+    }
+
+    fn clone_committee(&self) -> Committee {
+        self.inner.clone_committee()
+    }
+
+    fn get_current_epoch(&self) -> EpochId {
+        self.inner.get_current_epoch()
+    }
+
+    fn clone_new(&self) -> Box<dyn ValidatorProxy + Send + Sync> {
+        Box::new(Self {
+            inner: self.inner.clone(),
+            faults: self.faults.clone(),
+        })
+    }
+
+    async fn get_validators(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        self.inner.get_validators().await
+    }
+
+    fn effects_wait(&self) -> EffectsWait {
+        self.inner.effects_wait()
+    }
+
+    fn excluded_connections(&self) -> Vec<ConnectionExclusion> {
+        self.inner.excluded_connections()
+    }
+
+    async fn query_object(&self, query: ObjectQuery) -> Result<(), anyhow::Error> {
+        self.inner.query_object(query).await
+    }
+}
@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::ValidatorProxy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
+use sui_types::base_types::SuiAddress;
+use sui_types::committee::EpochId;
 use sui_types::{sui_system_state::SuiSystemState, SUI_SYSTEM_STATE_OBJECT_ID};
 use tokio::sync::oneshot::Sender;
 use tokio::sync::watch;
@@ -12,18 +16,88 @@ use tokio::time;
 use tokio::time::Instant;
 use tracing::info;
 
+/// Size and stake distribution of the committee, recorded with the results
+/// since they depend heavily on the validator count and stake concentration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeSummary {
+    pub epoch: EpochId,
+    pub num_validators: usize,
+    pub total_stake: u64,
+    // Stake of each active validator, largest first
+    pub validator_stakes: Vec<u64>,
+    // Address of each active validator, in the order of validator_stakes
+    #[serde(default)]
+    pub validator_addresses: Vec<SuiAddress>,
+}
+
+impl CommitteeSummary {
+    pub fn from_system_state(system_state: &SuiSystemState) -> Self {
+        let mut validators: Vec<(u64, SuiAddress)> = system_state
+            .validators
+            .active_validators
+            .iter()
+            .map(|v| {
+                (
+                    v.stake_amount + v.delegation_staking_pool.sui_balance,
+                    v.metadata.sui_address,
+                )
+            })
+            .collect();
+        validators.sort_unstable_by(|a, b| b.cmp(a));
+        let (validator_stakes, validator_addresses): (Vec<u64>, Vec<SuiAddress>) =
+            validators.into_iter().unzip();
+        Self {
+            epoch: system_state.epoch,
+            num_validators: validator_stakes.len(),
+            total_stake: validator_stakes.iter().sum(),
+            validator_stakes,
+            validator_addresses,
+        }
+    }
+
+    /// Whether results measured against both committees are comparable
+    pub fn same_topology(&self, other: &CommitteeSummary) -> bool {
+        self.validator_stakes == other.validator_stakes
+    }
+}
+
+impl fmt::Display for CommitteeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let largest_share = match (self.validator_stakes.first(), self.total_stake) {
+            (Some(largest), total) if total > 0 => *largest as f64 / total as f64,
+            _ => 0.0,
+        };
+        write!(
+            f,
+            "epoch {}, {} validators, total stake {}, largest stake share {:.1}%",
+            self.epoch,
+            self.num_validators,
+            self.total_stake,
+            largest_share * 100.0
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct SystemStateObserver {
     pub reference_gas_price: Receiver<u64>,
+    // Only updated when the epoch actually changes
+    pub epoch: Receiver<EpochId>,
+    pub committee: Receiver<CommitteeSummary>,
     pub _sender: Sender<()>,
 }
 
 impl SystemStateObserver {
-    pub fn new(proxy: Arc<dyn ValidatorProxy + Send + Sync>) -> Self {
+    /// Polls the system state object every `poll_interval`. Payloads read the
+    /// watch channels whenever they build a transaction, so changes reach
+    /// running workloads within one interval.
+    pub fn new(proxy: Arc<dyn ValidatorProxy + Send + Sync>, poll_interval: Duration) -> Self {
         let (sender, mut recv) = tokio::sync::oneshot::channel();
-        let mut interval = tokio::time::interval_at(Instant::now(), Duration::from_secs(60));
+        let mut interval = tokio::time::interval_at(Instant::now(), poll_interval);
         interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
         let (tx, rx) = watch::channel(1u64);
+        let (epoch_tx, epoch_rx) = watch::channel(proxy.get_current_epoch());
+        let (committee_tx, committee_rx) = watch::channel(CommitteeSummary::default());
         tokio::task::spawn(async move {
             loop {
                 tokio::select! {
@@ -31,9 +105,28 @@ impl SystemStateObserver {
                         if let Ok(system_state) = proxy.get_object(SUI_SYSTEM_STATE_OBJECT_ID).await {
                             let move_obj = system_state.data.try_as_move().unwrap();
                             if let Ok(result) = bcs::from_bytes::<SuiSystemState>(move_obj.contents()) {
-                                if tx.send(result.reference_gas_price).is_ok() {
+                                // Updated before the gas price, which callers wait on at startup
+                                let committee = CommitteeSummary::from_system_state(&result);
+                                committee_tx.send_if_modified(|c| {
+                                    if *c == committee {
+                                        return false;
+                                    }
+                                    *c = committee;
+                                    true
+                                });
+                                let price_changed = *tx.borrow() != result.reference_gas_price;
+                                // Always sent, callers wait for the first poll at startup
+                                if tx.send(result.reference_gas_price).is_ok() && price_changed {
                                     info!("Reference gas price = {:?}", result.reference_gas_price);
                                 }
+                                epoch_tx.send_if_modified(|epoch| {
+                                    if *epoch == result.epoch {
+                                        return false;
+                                    }
+                                    info!("Epoch changed from {} to {}", epoch, result.epoch);
+                                    *epoch = result.epoch;
+                                    true
+                                });
                             }
                         }
                     }
@@ -43,6 +136,23 @@ impl SystemStateObserver {
         });
         Self {
             reference_gas_price: rx,
+            epoch: epoch_rx,
+            committee: committee_rx,
+            _sender: sender,
+        }
+    }
+
+    /// Observer with a fixed reference gas price which never polls the system state
+    #[cfg(test)]
+    pub(crate) fn new_for_testing(reference_gas_price: u64) -> Self {
+        let (_, reference_gas_price) = watch::channel(reference_gas_price);
+        let (_, epoch) = watch::channel(0);
+        let (_, committee) = watch::channel(CommitteeSummary::default());
+        let (sender, _) = tokio::sync::oneshot::channel();
+        Self {
+            reference_gas_price,
+            epoch,
+            committee,
             _sender: sender,
         }
     }
@@ -0,0 +1,153 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::util::make_pay_all_sui_tx;
+use crate::workloads::Gas;
+use crate::ValidatorProxy;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::AccountKeyPair;
+use sui_types::gas_coin::GasCoin;
+use sui_types::object::{Object, Owner};
+use tracing::{error, info};
+
+/// Coins sent back by a single transaction
+const TEARDOWN_BATCH_SIZE: usize = 256;
+/// Gas budget of a transaction sending coins back, lowered to what its gas
+/// coin can pay
+const TEARDOWN_GAS_BUDGET: u64 = 100_000;
+
+/// What a teardown sent back to the primary gas owner
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TeardownReport {
+    pub accounts: usize,
+    pub returned_coins: usize,
+    // Balance of the returned coins, before the gas paid to return them
+    pub returned_mist: u64,
+    // Coins which failed to be returned
+    pub left_behind: usize,
+}
+
+/// Sends the SUI left in the coins of `gas` back to `recipient`. Coins
+/// deleted during the run, or now owned by an account outside `gas`, are
+/// skipped
+pub async fn return_gas(
+    proxy: Arc<dyn ValidatorProxy + Send + Sync>,
+    gas: Vec<Gas>,
+    recipient: SuiAddress,
+    gas_price: u64,
+) -> TeardownReport {
+    let mut accounts: HashMap<SuiAddress, Arc<AccountKeyPair>> = HashMap::new();
+    let mut object_ids: Vec<ObjectID> = vec![];
+    for (object_ref, owner, keypair) in gas {
+        if let Ok(address) = owner.get_owner_address() {
+            accounts.insert(address, keypair);
+        }
+        object_ids.push(object_ref.0);
+    }
+    object_ids.sort();
+    object_ids.dedup();
+    let mut objects = vec![];
+    for object_id in object_ids {
+        // Deleted objects, e.g. coins merged away, are gone
+        if let Ok(object) = proxy.get_object(object_id).await {
+            objects.push(object);
+        }
+    }
+    let plan = plan_teardown(objects, &accounts, recipient);
+    let mut report = TeardownReport {
+        accounts: plan.len(),
+        ..Default::default()
+    };
+    for (sender, coins) in plan {
+        let keypair = &accounts[&sender];
+        for batch in coins.chunks(TEARDOWN_BATCH_SIZE) {
+            // The largest coin comes first and pays the gas
+            let gas_budget = TEARDOWN_GAS_BUDGET.min(batch[0].1 / gas_price.max(1));
+            let tx = make_pay_all_sui_tx(
+                batch.iter().map(|(object_ref, _)| *object_ref).collect(),
+                recipient,
+                sender,
+                keypair,
+                gas_budget,
+                Some(gas_price),
+            );
+            match proxy.execute_transaction(tx.into()).await {
+                Ok(_) => {
+                    report.returned_coins += batch.len();
+                    report.returned_mist += batch.iter().map(|(_, balance)| balance).sum::<u64>();
+                }
+                Err(err) => {
+                    error!("Failed to return the coins of {}: {}", sender, err);
+                    report.left_behind += batch.len();
+                }
+            }
+        }
+    }
+    info!("Teardown: {:?}", report);
+    report
+}
+
+/// SUI coins of `objects` to send back to `recipient` with their balance,
+/// by the account of `accounts` owning them, largest first
+fn plan_teardown(
+    objects: Vec<Object>,
+    accounts: &HashMap<SuiAddress, Arc<AccountKeyPair>>,
+    recipient: SuiAddress,
+) -> BTreeMap<SuiAddress, Vec<(ObjectRef, u64)>> {
+    let mut plan: BTreeMap<SuiAddress, Vec<(ObjectRef, u64)>> = BTreeMap::new();
+    for object in objects {
+        let owner = match object.owner {
+            Owner::AddressOwner(owner) if owner != recipient && accounts.contains_key(&owner) => {
+                owner
+            }
+            _ => continue,
+        };
+        if let Ok(coin) = GasCoin::try_from(&object) {
+            plan.entry(owner)
+                .or_default()
+                .push((object.compute_object_reference(), coin.value()));
+        }
+    }
+    for coins in plan.values_mut() {
+        coins.sort_by(|a, b| b.1.cmp(&a.1));
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::get_key_pair;
+
+    #[test]
+    fn test_plan_teardown() {
+        let (account, keypair) = get_key_pair();
+        let (stranger, _) = get_key_pair::<AccountKeyPair>();
+        let (recipient, _) = get_key_pair::<AccountKeyPair>();
+        let accounts = HashMap::from([(account, Arc::new(keypair))]);
+        let small = Object::new_gas_with_balance_and_owner_for_testing(10, account);
+        let large = Object::new_gas_with_balance_and_owner_for_testing(1000, account);
+        let objects = vec![
+            small.clone(),
+            large.clone(),
+            // Not owned by a known account
+            Object::immutable_with_id_for_testing(ObjectID::random()),
+            Object::with_object_owner_for_testing(ObjectID::random(), ObjectID::random()),
+            Object::new_gas_with_balance_and_owner_for_testing(10, stranger),
+            Object::new_gas_with_balance_and_owner_for_testing(10, recipient),
+        ];
+        let plan = plan_teardown(objects, &accounts, recipient);
+        assert_eq!(
+            plan,
+            BTreeMap::from([(
+                account,
+                vec![
+                    (large.compute_object_reference(), 1000),
+                    (small.compute_object_reference(), 10)
+                ]
+            )])
+        );
+    }
+}
@@ -2,32 +2,104 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{Error, Result};
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal};
 use std::collections::HashMap;
+use std::str::FromStr;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
-use sui_types::{base_types::SuiAddress, coin, crypto::SuiKeyPair, SUI_FRAMEWORK_OBJECT_ID};
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    coin,
+    crypto::SuiKeyPair,
+    SUI_FRAMEWORK_OBJECT_ID,
+};
 
+use crate::workloads::custom_call::CustomCall;
+use crate::workloads::nft_mint::NftMetadataLengths;
 use crate::ValidatorProxy;
-use itertools::Itertools;
 use move_core_types::language_storage::TypeTag;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_types::base_types::ObjectRef;
 use sui_types::messages::{
-    CallArg, ObjectArg, TransactionData, VerifiedTransaction, DUMMY_GAS_PRICE,
+    CallArg, MoveCall, ObjectArg, SingleTransactionKind, TransactionData, TransactionKind,
+    VerifiedTransaction, DUMMY_GAS_PRICE,
 };
 use sui_types::utils::to_sender_signed_transaction;
 use tracing::log::info;
 
 use crate::workloads::{
-    Gas, GasCoinConfig, WorkloadGasConfig, WorkloadInitGas, WorkloadPayloadGas,
+    assign_coin_configs_to_senders, split_coin_configs_across_senders, Gas, GasCoinConfig,
+    SenderAccount, WorkloadGasConfig, WorkloadInitGas, WorkloadPayloadGas,
 };
 use sui_types::crypto::{AccountKeyPair, KeypairTraits};
+use sui_types::gas_coin::{GasCoin, GAS};
+use sui_types::object::Owner;
 
 // This is the maximum gas we will transfer from primary coin into any gas coin
 // for running the benchmark
 pub const MAX_GAS_FOR_TESTING: u64 = 1_000_000_000;
 
-pub type UpdatedAndNewlyMintedGasCoins = (Gas, Vec<Gas>);
+// Gas budget of pay transactions unless a workload overrides it
+pub const PAY_GAS_BUDGET: u64 = 1_000_000;
+
+// Coins split off the primary coin and paid out by one pair of transactions,
+// so the amounts, recipients and new coins held at once stay bounded however
+// many coins the run needs
+pub const GAS_GENERATION_BATCH_SIZE: usize = 256;
+
+// The primary gas and coin after a split, and the coins split off
+pub type UpdatedAndNewlyMintedGasCoins = (Gas, ObjectRef, Vec<Gas>);
+
+/// Distribution of amounts (in MIST) to sample from, parsed from
+/// "const:<amount>" or "lognormal:<median>:<sigma>"
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum AmountDistribution {
+    Const(u64),
+    LogNormal { median: u64, sigma: f64 },
+}
+
+impl AmountDistribution {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        match self {
+            AmountDistribution::Const(amount) => *amount,
+            AmountDistribution::LogNormal { median, sigma } => {
+                let dist = LogNormal::new((*median as f64).ln(), *sigma).unwrap();
+                std::cmp::max(1, dist.sample(rng) as u64)
+            }
+        }
+    }
+}
+
+impl FromStr for AmountDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        match parts.as_slice() {
+            ["const", amount] => {
+                let amount: u64 = amount.parse().map_err(|e| format!("{e}"))?;
+                if amount == 0 {
+                    return Err("Constant amount must be greater than 0".to_string());
+                }
+                Ok(AmountDistribution::Const(amount))
+            }
+            ["lognormal", median, sigma] => {
+                let median: u64 = median.parse().map_err(|e| format!("{e}"))?;
+                let sigma: f64 = sigma.parse().map_err(|e| format!("{e}"))?;
+                if median == 0 {
+                    return Err("Median amount must be greater than 0".to_string());
+                }
+                LogNormal::new((median as f64).ln(), sigma).map_err(|e| format!("{e}"))?;
+                Ok(AmountDistribution::LogNormal { median, sigma })
+            }
+            _ => Err(
+                "Required distribution of the form const:<amount> or lognormal:<median>:<sigma>"
+                    .to_string(),
+            ),
+        }
+    }
+}
 
 pub fn get_ed25519_keypair_from_keystore(
     keystore_path: PathBuf,
@@ -40,6 +112,30 @@ pub fn get_ed25519_keypair_from_keystore(
     }
 }
 
+/// Loads the sender accounts of a keystore file, all of them must be ed25519
+pub fn load_sender_accounts(keystore_path: &str) -> Result<Vec<SenderAccount>> {
+    let keystore_path = PathBuf::from(keystore_path);
+    if !keystore_path.exists() {
+        anyhow::bail!("Sender keystore {:?} does not exist", keystore_path);
+    }
+    let keystore = FileBasedKeystore::new(&keystore_path)?;
+    if keystore.addresses().is_empty() {
+        anyhow::bail!("Sender keystore {:?} holds no keys", keystore_path);
+    }
+    keystore
+        .addresses()
+        .into_iter()
+        .map(|address| match keystore.get_key(&address) {
+            Ok(SuiKeyPair::Ed25519(kp)) => Ok((address, Arc::new(kp.copy()))),
+            other => Err(anyhow::anyhow!(
+                "Invalid key type for sender {}: {:?}",
+                address,
+                other
+            )),
+        })
+        .collect()
+}
+
 pub fn make_split_coin_tx(
     sender: SuiAddress,
     coin: ObjectRef,
@@ -74,6 +170,7 @@ pub fn make_pay_tx(
     split_amounts: Vec<u64>,
     gas: ObjectRef,
     keypair: &AccountKeyPair,
+    gas_budget: u64,
     gas_price: Option<u64>,
 ) -> VerifiedTransaction {
     let pay = TransactionData::new_pay(
@@ -82,12 +179,355 @@ pub fn make_pay_tx(
         addresses,
         split_amounts,
         gas,
-        1000000,
+        gas_budget,
         gas_price.unwrap_or(DUMMY_GAS_PRICE),
     );
     to_sender_signed_transaction(pay, keypair)
 }
 
+pub fn make_pay_all_sui_tx(
+    coins: Vec<ObjectRef>,
+    recipient: SuiAddress,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    // The first coin pays the gas
+    let data = TransactionData::new_pay_all_sui(
+        sender,
+        coins.clone(),
+        recipient,
+        coins[0],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_counter_create_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "counter".parse().unwrap(),
+        "create".parse().unwrap(),
+        vec![],
+        gas,
+        vec![],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_noop_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "noop".parse().unwrap(),
+        "noop".parse().unwrap(),
+        vec![],
+        gas,
+        vec![],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_object_fanout_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    count: u64,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "object_fanout".parse().unwrap(),
+        "create".parse().unwrap(),
+        vec![],
+        gas,
+        vec![CallArg::Pure(bcs::to_bytes(&count).unwrap())],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_nft_mint_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    lengths: NftMetadataLengths,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    // ASCII filler, valid in both strings and urls
+    let field = |length: u64| CallArg::Pure(bcs::to_bytes(&vec![b'a'; length as usize]).unwrap());
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "nft_mint".parse().unwrap(),
+        "mint".parse().unwrap(),
+        vec![],
+        gas,
+        vec![
+            field(lengths.name),
+            field(lengths.url),
+            field(lengths.description),
+        ],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_custom_call_tx(
+    gas: ObjectRef,
+    call: &CustomCall,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        call.package,
+        call.module.clone(),
+        call.function.clone(),
+        vec![],
+        gas,
+        vec![],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_batch_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    commands_per_tx: u64,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let commands = (0..commands_per_tx)
+        .map(|_| {
+            SingleTransactionKind::Call(MoveCall {
+                package: package_id,
+                module: "noop".parse().unwrap(),
+                function: "noop".parse().unwrap(),
+                type_arguments: vec![],
+                arguments: vec![],
+            })
+        })
+        .collect();
+    let data = TransactionData::new(
+        TransactionKind::Batch(commands),
+        sender,
+        gas,
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_divide_coin_tx(
+    gas: ObjectRef,
+    coin: ObjectRef,
+    split_count: u64,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    // Leaves `coin` with one share and sends the other split_count - 1 to
+    // the sender as new coins
+    let data = TransactionData::new_move_call(
+        sender,
+        SUI_FRAMEWORK_OBJECT_ID,
+        coin::PAY_MODULE_NAME.to_owned(),
+        coin::PAY_SPLIT_N_FUNC_NAME.to_owned(),
+        vec![GAS::type_tag()],
+        gas,
+        vec![
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(coin)),
+            CallArg::Pure(bcs::to_bytes(&split_count).unwrap()),
+        ],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_join_coins_tx(
+    gas: ObjectRef,
+    coin: ObjectRef,
+    coins: Vec<ObjectRef>,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        SUI_FRAMEWORK_OBJECT_ID,
+        coin::PAY_MODULE_NAME.to_owned(),
+        "join_vec".parse().unwrap(),
+        vec![GAS::type_tag()],
+        gas,
+        vec![
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(coin)),
+            CallArg::ObjVec(coins.into_iter().map(ObjectArg::ImmOrOwnedObject).collect()),
+        ],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_collection_create_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "collection".parse().unwrap(),
+        "create".parse().unwrap(),
+        vec![],
+        gas,
+        vec![],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_collection_churn_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    collection: ObjectRef,
+    num_ops: u64,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "collection".parse().unwrap(),
+        "churn".parse().unwrap(),
+        vec![],
+        gas,
+        vec![
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(collection)),
+            CallArg::Pure(bcs::to_bytes(&num_ops).unwrap()),
+        ],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_wrap_unwrap_create_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "wrap_unwrap".parse().unwrap(),
+        "create".parse().unwrap(),
+        vec![],
+        gas,
+        vec![],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_wrap_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    container: ObjectRef,
+    inner: ObjectRef,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "wrap_unwrap".parse().unwrap(),
+        "wrap".parse().unwrap(),
+        vec![],
+        gas,
+        vec![
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(container)),
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(inner)),
+        ],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
+pub fn make_unwrap_tx(
+    gas: ObjectRef,
+    package_id: ObjectID,
+    container: ObjectRef,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "wrap_unwrap".parse().unwrap(),
+        "unwrap".parse().unwrap(),
+        vec![],
+        gas,
+        vec![CallArg::Object(ObjectArg::ImmOrOwnedObject(container))],
+        gas_budget,
+        gas_price.unwrap_or(DUMMY_GAS_PRICE),
+    );
+    to_sender_signed_transaction(data, keypair)
+}
+
 pub async fn split_coin_and_pay(
     proxy: Arc<dyn ValidatorProxy + Send + Sync>,
     coin: ObjectRef,
@@ -118,6 +558,12 @@ pub async fn split_coin_and_pay(
         .find(|(k, _)| k.0 == gas.0 .0)
         .ok_or("Input gas missing in the effects")
         .map_err(Error::msg)?;
+    let updated_coin = effects
+        .mutated()
+        .into_iter()
+        .find(|(k, _)| k.0 == coin.0)
+        .ok_or("Split coin missing in the effects")
+        .map_err(Error::msg)?;
     let created_coins: Vec<ObjectRef> = effects.created().into_iter().map(|c| c.0).collect();
     assert_eq!(created_coins.len(), split_amounts.len());
     let recipient_addresses: Vec<SuiAddress> = coin_configs.iter().map(|g| g.address).collect();
@@ -128,6 +574,7 @@ pub async fn split_coin_and_pay(
         split_amounts,
         updated_gas.0,
         &gas.2,
+        PAY_GAS_BUDGET,
         Some(gas_price),
     );
     let (_, effects) = proxy.execute_transaction(verified_tx.into()).await?;
@@ -153,7 +600,11 @@ pub async fn split_coin_and_pay(
         .find(|(k, _)| k.0 == gas.0 .0)
         .ok_or("Input gas missing in the effects")
         .map_err(Error::msg)?;
-    Ok(((updated_gas.0, updated_gas.1, gas.2), transferred_coins?))
+    Ok((
+        (updated_gas.0, updated_gas.1, gas.2),
+        updated_coin.0,
+        transferred_coins?,
+    ))
 }
 
 pub async fn generate_all_gas_for_test(
@@ -161,13 +612,53 @@ pub async fn generate_all_gas_for_test(
     gas: Gas,
     coin: Gas,
     coin_type_tag: TypeTag,
-    workload_gas_config: WorkloadGasConfig,
+    mut workload_gas_config: WorkloadGasConfig,
     gas_price: u64,
+    gas_coins_per_sender: Option<u64>,
+    sender_pool: &[SenderAccount],
 ) -> Result<(WorkloadInitGas, WorkloadPayloadGas)> {
+    // Transfer object coins stay with their accounts, the transfer ring
+    // depends on them. So do coin churn coins, each payload pairs a gas coin
+    // with a churn coin of the same owner
+    for configs in [
+        &mut workload_gas_config.shared_counter_workload_payload_gas_config,
+        &mut workload_gas_config.delegation_gas_configs,
+        &mut workload_gas_config.collection_workload_payload_gas_config,
+        &mut workload_gas_config.wrap_unwrap_workload_payload_gas_config,
+        &mut workload_gas_config.dependency_chain_workload_payload_gas_config,
+        &mut workload_gas_config.shared_lifecycle_workload_payload_gas_config,
+        &mut workload_gas_config.noop_workload_payload_gas_config,
+        &mut workload_gas_config.send_to_object_workload_payload_gas_config,
+        &mut workload_gas_config.object_fanout_workload_payload_gas_config,
+        &mut workload_gas_config.nft_mint_workload_payload_gas_config,
+        &mut workload_gas_config.batch_workload_payload_gas_config,
+    ]
+    .into_iter()
+    .chain(
+        workload_gas_config
+            .custom_workload_payload_gas_configs
+            .values_mut(),
+    ) {
+        if !sender_pool.is_empty() {
+            assign_coin_configs_to_senders(configs, sender_pool);
+        } else if let Some(coins_per_sender) = gas_coins_per_sender {
+            split_coin_configs_across_senders(configs, coins_per_sender);
+        }
+    }
     info!(
         "Generating gas with number of coins for shared counter init = {:?}, number of coins for \
     shared counter payloads = {:?}, number of transfer object token = {:?}, number of coins for \
-    transfer object payloads = {:?}, number of coins for delegation payloads = {:?}",
+    transfer object payloads = {:?}, number of coins for delegation payloads = {:?}, number of \
+    read objects = {:?}, number of coins for collection init = {:?}, number of coins for \
+    collection payloads = {:?}, number of coins for wrap_unwrap init = {:?}, number of coins for \
+    wrap_unwrap payloads = {:?}, number of coins for dependency chain payloads = {:?}, number of \
+    coins for shared lifecycle init = {:?}, number of coins for shared lifecycle payloads = {:?}, \
+    number of coins for noop init = {:?}, number of coins for noop payloads = {:?}, number of \
+    send_to_object destinations = {:?}, number of coins for send_to_object payloads = {:?}, \
+    number of coins for object fanout init = {:?}, number of coins for object fanout payloads = {:?}, \
+    number of coins for nft mint init = {:?}, number of coins for nft mint payloads = {:?}, \
+    number of coins for batch init = {:?}, number of coins for batch payloads = {:?}, \
+    number of coins for coin churn payloads = {:?}",
         workload_gas_config
             .shared_counter_workload_init_gas_config
             .len(),
@@ -179,104 +670,231 @@ pub async fn generate_all_gas_for_test(
             .transfer_object_workload_payload_gas_config
             .len(),
         workload_gas_config.delegation_gas_configs.len(),
-    );
-    let mut coin_configs = vec![];
-    coin_configs.extend(
+        workload_gas_config.read_object_configs.len(),
         workload_gas_config
-            .shared_counter_workload_init_gas_config
-            .iter()
-            .cloned(),
-    );
-    coin_configs.extend(
+            .collection_workload_init_gas_config
+            .len(),
         workload_gas_config
-            .shared_counter_workload_payload_gas_config
-            .iter()
-            .cloned(),
-    );
-    coin_configs.extend(
+            .collection_workload_payload_gas_config
+            .len(),
         workload_gas_config
-            .transfer_object_workload_tokens
-            .iter()
-            .cloned(),
-    );
-    coin_configs.extend(
+            .wrap_unwrap_workload_init_gas_config
+            .len(),
         workload_gas_config
-            .transfer_object_workload_payload_gas_config
-            .iter()
-            .cloned(),
+            .wrap_unwrap_workload_payload_gas_config
+            .len(),
+        workload_gas_config
+            .dependency_chain_workload_payload_gas_config
+            .len(),
+        workload_gas_config
+            .shared_lifecycle_workload_init_gas_config
+            .len(),
+        workload_gas_config
+            .shared_lifecycle_workload_payload_gas_config
+            .len(),
+        workload_gas_config.noop_workload_init_gas_config.len(),
+        workload_gas_config.noop_workload_payload_gas_config.len(),
+        workload_gas_config
+            .send_to_object_workload_init_gas_config
+            .len(),
+        workload_gas_config
+            .send_to_object_workload_payload_gas_config
+            .len(),
+        workload_gas_config
+            .object_fanout_workload_init_gas_config
+            .len(),
+        workload_gas_config
+            .object_fanout_workload_payload_gas_config
+            .len(),
+        workload_gas_config.nft_mint_workload_init_gas_config.len(),
+        workload_gas_config.nft_mint_workload_payload_gas_config.len(),
+        workload_gas_config.batch_workload_init_gas_config.len(),
+        workload_gas_config.batch_workload_payload_gas_config.len(),
+        workload_gas_config
+            .coin_churn_workload_payload_gas_config
+            .len(),
     );
-    coin_configs.extend(workload_gas_config.delegation_gas_configs.iter().cloned());
-
-    let (_updated_primary_gas, mut new_gas_coins) = split_coin_and_pay(
-        proxy.clone(),
-        coin.0,
-        coin.1.get_owner_address()?,
-        coin_type_tag,
-        coin_configs,
-        gas,
-        gas_price,
-    )
-    .await?;
-
-    let transfer_tokens: Vec<Gas> = workload_gas_config
-        .transfer_object_workload_tokens
-        .iter()
-        .map(|c| {
-            let (index, _) = new_gas_coins
-                .iter()
-                .find_position(|g| g.1.get_owner_address().unwrap() == c.address)
-                .unwrap();
-            new_gas_coins.remove(index)
-        })
-        .collect();
-    let transfer_object_payload_gas: Vec<Gas> = workload_gas_config
-        .transfer_object_workload_payload_gas_config
-        .iter()
-        .map(|c| {
-            let (index, _) = new_gas_coins
-                .iter()
-                .find_position(|g| g.1.get_owner_address().unwrap() == c.address)
-                .unwrap();
-            new_gas_coins.remove(index)
-        })
-        .collect();
-    let shared_counter_init_gas: Vec<Gas> = workload_gas_config
-        .shared_counter_workload_init_gas_config
-        .iter()
-        .map(|c| {
-            let (index, _) = new_gas_coins
-                .iter()
-                .find_position(|g| g.1.get_owner_address().unwrap() == c.address)
-                .unwrap();
-            new_gas_coins.remove(index)
-        })
+    let mut coin_configs: Vec<&GasCoinConfig> = workload_gas_config
+        .all_configs()
+        .into_iter()
+        .flatten()
         .collect();
-    let shared_counter_payload_gas: Vec<Gas> = workload_gas_config
-        .shared_counter_workload_payload_gas_config
+    for (name, configs) in workload_gas_config
+        .custom_workload_init_gas_configs
         .iter()
-        .map(|c| {
-            let (index, _) = new_gas_coins
-                .iter()
-                .find_position(|g| g.1.get_owner_address().unwrap() == c.address)
-                .unwrap();
-            new_gas_coins.remove(index)
-        })
-        .collect();
+        .chain(
+            workload_gas_config
+                .custom_workload_payload_gas_configs
+                .iter(),
+        )
+    {
+        info!(
+            "Number of coins for registered workload {} = {}",
+            name,
+            configs.len()
+        );
+        coin_configs.extend(configs.iter());
+    }
+
+    let coin_sender = coin.1.get_owner_address()?;
+    let (mut gas, mut coin) = (gas, coin.0);
+    let mut new_gas_coins = Vec::with_capacity(coin_configs.len());
+    for batch in coin_configs.chunks(GAS_GENERATION_BATCH_SIZE) {
+        let (updated_gas, updated_coin, coins) = split_coin_and_pay(
+            proxy.clone(),
+            coin,
+            coin_sender,
+            coin_type_tag.clone(),
+            batch.iter().map(|config| (*config).clone()).collect(),
+            gas,
+            gas_price,
+        )
+        .await?;
+        gas = updated_gas;
+        coin = updated_coin;
+        new_gas_coins.extend(coins);
+    }
 
-    let delegation_payload_gas = workload_gas_config
-        .delegation_gas_configs
+    // Coins of the same owner are interchangeable, index them by owner so
+    // handing them out stays linear in the number of coins
+    let mut gas_by_owner: HashMap<SuiAddress, Vec<Gas>> = HashMap::new();
+    for gas in new_gas_coins {
+        gas_by_owner
+            .entry(gas.1.get_owner_address()?)
+            .or_default()
+            .push(gas);
+    }
+    let mut take_gas = |configs: &[GasCoinConfig]| -> Result<Vec<Gas>> {
+        configs
+            .iter()
+            .map(|c| {
+                gas_by_owner
+                    .get_mut(&c.address)
+                    .and_then(|coins| coins.pop())
+                    .ok_or_else(|| anyhow::anyhow!("Missing gas coin for {}", c.address))
+            })
+            .collect()
+    };
+
+    let transfer_tokens = take_gas(&workload_gas_config.transfer_object_workload_tokens)?;
+    let transfer_object_payload_gas =
+        take_gas(&workload_gas_config.transfer_object_workload_payload_gas_config)?;
+    let shared_counter_init_gas =
+        take_gas(&workload_gas_config.shared_counter_workload_init_gas_config)?;
+    let shared_counter_payload_gas =
+        take_gas(&workload_gas_config.shared_counter_workload_payload_gas_config)?;
+    let delegation_payload_gas = take_gas(&workload_gas_config.delegation_gas_configs)?;
+    let read_payload_objects = take_gas(&workload_gas_config.read_object_configs)?;
+    let collection_init_gas = take_gas(&workload_gas_config.collection_workload_init_gas_config)?;
+    let collection_payload_gas =
+        take_gas(&workload_gas_config.collection_workload_payload_gas_config)?;
+    let wrap_unwrap_init_gas = take_gas(&workload_gas_config.wrap_unwrap_workload_init_gas_config)?;
+    let wrap_unwrap_payload_gas =
+        take_gas(&workload_gas_config.wrap_unwrap_workload_payload_gas_config)?;
+    let dependency_chain_payload_gas =
+        take_gas(&workload_gas_config.dependency_chain_workload_payload_gas_config)?;
+    let shared_lifecycle_init_gas =
+        take_gas(&workload_gas_config.shared_lifecycle_workload_init_gas_config)?;
+    let shared_lifecycle_payload_gas =
+        take_gas(&workload_gas_config.shared_lifecycle_workload_payload_gas_config)?;
+    let noop_init_gas = take_gas(&workload_gas_config.noop_workload_init_gas_config)?;
+    let noop_payload_gas = take_gas(&workload_gas_config.noop_workload_payload_gas_config)?;
+    let send_to_object_init_gas =
+        take_gas(&workload_gas_config.send_to_object_workload_init_gas_config)?;
+    let send_to_object_payload_gas =
+        take_gas(&workload_gas_config.send_to_object_workload_payload_gas_config)?;
+    let object_fanout_init_gas =
+        take_gas(&workload_gas_config.object_fanout_workload_init_gas_config)?;
+    let object_fanout_payload_gas =
+        take_gas(&workload_gas_config.object_fanout_workload_payload_gas_config)?;
+    let nft_mint_init_gas = take_gas(&workload_gas_config.nft_mint_workload_init_gas_config)?;
+    let nft_mint_payload_gas = take_gas(&workload_gas_config.nft_mint_workload_payload_gas_config)?;
+    let batch_init_gas = take_gas(&workload_gas_config.batch_workload_init_gas_config)?;
+    let batch_payload_gas = take_gas(&workload_gas_config.batch_workload_payload_gas_config)?;
+    let coin_churn_payload_gas =
+        take_gas(&workload_gas_config.coin_churn_workload_payload_gas_config)?;
+    let mut custom_init_gas = HashMap::new();
+    for (name, configs) in workload_gas_config.custom_workload_init_gas_configs.iter() {
+        custom_init_gas.insert(*name, take_gas(configs)?);
+    }
+    let mut custom_payload_gas = HashMap::new();
+    for (name, configs) in workload_gas_config
+        .custom_workload_payload_gas_configs
         .iter()
-        .map(|c| {
-            let (index, _) = new_gas_coins
-                .iter()
-                .find_position(|g| g.1.get_owner_address().unwrap() == c.address)
-                .unwrap();
-            new_gas_coins.remove(index)
-        })
-        .collect();
+    {
+        custom_payload_gas.insert(*name, take_gas(configs)?);
+    }
+
+    if !sender_pool.is_empty() {
+        let mut coins = vec![];
+        for (configs, gas) in [
+            (
+                &workload_gas_config.shared_counter_workload_payload_gas_config,
+                &shared_counter_payload_gas,
+            ),
+            (
+                &workload_gas_config.delegation_gas_configs,
+                &delegation_payload_gas,
+            ),
+            (
+                &workload_gas_config.collection_workload_payload_gas_config,
+                &collection_payload_gas,
+            ),
+            (
+                &workload_gas_config.wrap_unwrap_workload_payload_gas_config,
+                &wrap_unwrap_payload_gas,
+            ),
+            (
+                &workload_gas_config.dependency_chain_workload_payload_gas_config,
+                &dependency_chain_payload_gas,
+            ),
+            (
+                &workload_gas_config.shared_lifecycle_workload_payload_gas_config,
+                &shared_lifecycle_payload_gas,
+            ),
+            (
+                &workload_gas_config.noop_workload_payload_gas_config,
+                &noop_payload_gas,
+            ),
+            (
+                &workload_gas_config.send_to_object_workload_payload_gas_config,
+                &send_to_object_payload_gas,
+            ),
+            (
+                &workload_gas_config.object_fanout_workload_payload_gas_config,
+                &object_fanout_payload_gas,
+            ),
+            (
+                &workload_gas_config.nft_mint_workload_payload_gas_config,
+                &nft_mint_payload_gas,
+            ),
+            (
+                &workload_gas_config.batch_workload_payload_gas_config,
+                &batch_payload_gas,
+            ),
+        ] {
+            coins.extend(configs.iter().zip(gas.iter()));
+        }
+        for (name, configs) in workload_gas_config
+            .custom_workload_payload_gas_configs
+            .iter()
+        {
+            coins.extend(configs.iter().zip(custom_payload_gas[name].iter()));
+        }
+        validate_sender_gas(proxy.clone(), sender_pool, &coins).await?;
+    }
 
     let workload_init_config = WorkloadInitGas {
         shared_counter_init_gas,
+        collection_init_gas,
+        wrap_unwrap_init_gas,
+        shared_lifecycle_init_gas,
+        noop_init_gas,
+        send_to_object_init_gas,
+        object_fanout_init_gas,
+        nft_mint_init_gas,
+        batch_init_gas,
+        custom_init_gas,
     };
 
     let workload_payload_config = WorkloadPayloadGas {
@@ -284,7 +902,168 @@ pub async fn generate_all_gas_for_test(
         transfer_object_payload_gas,
         shared_counter_payload_gas,
         delegation_payload_gas,
+        read_payload_objects,
+        collection_payload_gas,
+        wrap_unwrap_payload_gas,
+        dependency_chain_payload_gas,
+        shared_lifecycle_payload_gas,
+        noop_payload_gas,
+        send_to_object_payload_gas,
+        object_fanout_payload_gas,
+        nft_mint_payload_gas,
+        batch_payload_gas,
+        coin_churn_payload_gas,
+        custom_payload_gas,
     };
 
     Ok((workload_init_config, workload_payload_config))
 }
+
+/// Checks on chain, before the run, that every account of `sender_pool` owns
+/// a payload gas coin holding at least the amount configured for it
+async fn validate_sender_gas(
+    proxy: Arc<dyn ValidatorProxy + Send + Sync>,
+    sender_pool: &[SenderAccount],
+    coins: &[(&GasCoinConfig, &Gas)],
+) -> Result<()> {
+    for (address, _) in sender_pool {
+        let (config, gas) = coins
+            .iter()
+            .find(|(config, _)| config.address == *address)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Sender {} got no payload gas coin, the workloads need fewer senders",
+                    address
+                )
+            })?;
+        let object = proxy.get_object(gas.0 .0).await?;
+        let balance = GasCoin::try_from(&object)?.value();
+        if object.owner != Owner::AddressOwner(*address) || balance < config.amount {
+            anyhow::bail!(
+                "Sender {} does not own enough gas, coin {} holds {} of the {} required",
+                address,
+                gas.0 .0,
+                balance,
+                config.amount
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_proxy::MockValidatorProxy;
+    use crate::workloads::generate_coin_configs_for_single_owner;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sui_types::crypto::get_key_pair;
+    use sui_types::object::Object;
+
+    #[test]
+    fn test_parse_amount_distribution() {
+        assert_eq!(
+            "const:500".parse::<AmountDistribution>(),
+            Ok(AmountDistribution::Const(500))
+        );
+        assert_eq!(
+            "lognormal:1000:0.5".parse::<AmountDistribution>(),
+            Ok(AmountDistribution::LogNormal {
+                median: 1000,
+                sigma: 0.5
+            })
+        );
+        for malformed in [
+            "",
+            "const",
+            "const:0",
+            "const:-1",
+            "const:1:2",
+            "lognormal:1000",
+            "lognormal:0:0.5",
+            "lognormal:1000:-1",
+            "lognormal:1000:x",
+            "uniform:1:2",
+        ] {
+            assert!(
+                malformed.parse::<AmountDistribution>().is_err(),
+                "{}",
+                malformed
+            );
+        }
+    }
+
+    #[test]
+    fn test_sample_amount_distribution() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert!((0..100).all(|_| AmountDistribution::Const(500).sample(&mut rng) == 500));
+
+        let dist = AmountDistribution::LogNormal {
+            median: 1000,
+            sigma: 0.5,
+        };
+        let mut samples: Vec<u64> = (0..10_000).map(|_| dist.sample(&mut rng)).collect();
+        samples.sort_unstable();
+        let median = samples[samples.len() / 2];
+        assert!((900..1100).contains(&median), "median {}", median);
+        // Tiny medians never sample an amount of 0
+        let tiny = AmountDistribution::LogNormal {
+            median: 1,
+            sigma: 3.0,
+        };
+        assert!((0..1000).all(|_| tiny.sample(&mut rng) >= 1));
+    }
+
+    #[tokio::test]
+    async fn test_generate_gas_in_bounded_batches() {
+        let (owner, keypair): (_, AccountKeyPair) = get_key_pair();
+        let keypair = Arc::new(keypair);
+        let gas_object =
+            Object::new_gas_with_balance_and_owner_for_testing(MAX_GAS_FOR_TESTING, owner);
+        let coin_object =
+            Object::new_gas_with_balance_and_owner_for_testing(MAX_GAS_FOR_TESTING, owner);
+        let gas = (
+            gas_object.compute_object_reference(),
+            Owner::AddressOwner(owner),
+            keypair.clone(),
+        );
+        let coin = (
+            coin_object.compute_object_reference(),
+            Owner::AddressOwner(owner),
+            keypair,
+        );
+        let proxy = Arc::new(
+            MockValidatorProxy::new(vec![gas_object, coin_object], None, 0.0)
+                .await
+                .unwrap(),
+        );
+        // More coins than fit in one batch, the last batch is partial
+        let num_coins = 2 * GAS_GENERATION_BATCH_SIZE as u64 + 1;
+        let noop_workload_payload_gas_config =
+            generate_coin_configs_for_single_owner(num_coins, 10);
+        let recipient = noop_workload_payload_gas_config[0].address;
+        let workload_gas_config = WorkloadGasConfig {
+            noop_workload_payload_gas_config,
+            ..Default::default()
+        };
+        let (_, payload_gas) = generate_all_gas_for_test(
+            proxy.clone(),
+            gas,
+            coin,
+            GAS::type_tag(),
+            workload_gas_config,
+            1,
+            None,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert_eq!(payload_gas.noop_payload_gas.len() as u64, num_coins);
+        for (object_ref, owner, _) in &payload_gas.noop_payload_gas {
+            assert_eq!(*owner, Owner::AddressOwner(recipient));
+            let object = proxy.get_object(object_ref.0).await.unwrap();
+            assert_eq!(GasCoin::try_from(&object).unwrap().value(), 10);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_batch_tx, AmountDistribution};
+use crate::workloads::package::create_publish_package_transaction;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use std::{path::PathBuf, sync::Arc};
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::gas::MAX_GAS_BUDGET;
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+use test_utils::transaction::parse_package_ref;
+use tracing::info;
+
+/// Default budget of a batch of `commands_per_tx` calls
+pub fn batch_gas_budget(commands_per_tx: u64) -> u64 {
+    commands_per_tx.saturating_mul(MAX_GAS)
+}
+
+/// Checks a batch of `commands_per_tx` calls is valid and its default
+/// budget within the protocol's maximum gas budget. Batches have no limit
+/// of their own on the number of commands or inputs
+pub fn check_commands_per_tx(commands_per_tx: u64) -> Result<()> {
+    if commands_per_tx == 0 {
+        bail!("--commands-per-tx must be at least 1, batches cannot be empty");
+    }
+    if batch_gas_budget(commands_per_tx) > *MAX_GAS_BUDGET {
+        bail!(
+            "--commands-per-tx {} needs a gas budget above the maximum of {}, use at most {} commands",
+            commands_per_tx,
+            *MAX_GAS_BUDGET,
+            *MAX_GAS_BUDGET / MAX_GAS
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct BatchTestPayload {
+    package_id: ObjectID,
+    commands_per_tx: u64,
+    gas: Gas,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for BatchTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        _: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        Box::new(BatchTestPayload {
+            package_id: self.package_id,
+            commands_per_tx: self.commands_per_tx,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        make_batch_tx(
+            self.gas.0,
+            self.package_id,
+            self.commands_per_tx,
+            self.gas
+                .1
+                .get_owner_address()
+                .expect("Cannot convert owner to address"),
+            &self.gas.2,
+            sample_gas_budget(
+                self.gas_budget,
+                batch_gas_budget(self.commands_per_tx),
+                gas_price,
+            ),
+            Some(gas_price),
+        )
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0 .0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Batch
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &BatchTestPayload)
+    }
+}
+
+/// Batch transactions of `commands_per_tx` calls to a Move function doing
+/// nothing, measuring how the number of commands of a transaction weighs
+/// on throughput and validator CPU
+#[derive(Debug)]
+pub struct BatchWorkload {
+    pub package_id: Option<ObjectID>,
+    pub commands_per_tx: u64,
+    // Gas budget of batches, defaults to batch_gas_budget
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl BatchWorkload {
+    pub fn new_boxed(
+        commands_per_tx: u64,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(BatchWorkload {
+            package_id: None,
+            commands_per_tx,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init() -> Vec<GasCoinConfig> {
+        // Gas coin for publishing package
+        generate_coin_configs_for_single_owner(1, MAX_GAS_FOR_TESTING)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for BatchWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if self.package_id.is_some() {
+            return Ok(());
+        }
+        let (gas, owner, keypair) = init_config
+            .batch_init_gas
+            .first()
+            .ok_or_else(|| anyhow!("Not enough gas to initialize batch workload"))?;
+        info!("Publishing noop package for batches");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("data/noop");
+        let transaction = create_publish_package_transaction(
+            *gas,
+            path,
+            owner.get_owner_address()?,
+            keypair,
+            *system_state_observer.reference_gas_price.borrow(),
+        )?;
+        let (_, effects) = proxy.execute_transaction(transaction.into()).await?;
+        self.package_id = Some(
+            parse_package_ref(&effects.created())
+                .ok_or_else(|| anyhow!("Noop package was not published"))?
+                .0,
+        );
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let package_id = self
+            .package_id
+            .expect("Batch workload must be initialized before making payloads");
+        payload_config
+            .batch_payload_gas
+            .into_iter()
+            .map(|gas| {
+                Box::new(BatchTestPayload {
+                    package_id,
+                    commands_per_tx: self.commands_per_tx,
+                    gas,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Batch
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &BatchWorkload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_commands_per_tx() {
+        assert!(check_commands_per_tx(0).is_err());
+        assert!(check_commands_per_tx(1).is_ok());
+        let max = *MAX_GAS_BUDGET / MAX_GAS;
+        assert!(check_commands_per_tx(max).is_ok());
+        assert!(check_commands_per_tx(max + 1).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_divide_coin_tx, make_join_coins_tx, AmountDistribution};
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::gas::MAX_GAS_BUDGET;
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+
+// Gas budgeted per coin split off or merged back on top of the call itself
+const GAS_PER_CHURNED_COIN: u64 = MAX_GAS;
+
+/// Default budget of a transaction splitting a coin into `split_count` or
+/// merging them back
+pub fn coin_churn_gas_budget(split_count: u64) -> u64 {
+    split_count
+        .saturating_mul(GAS_PER_CHURNED_COIN)
+        .saturating_add(MAX_GAS)
+}
+
+/// Checks a coin can be split into `split_count` coins within the maximum
+/// gas budget of a transaction
+pub fn check_split_count(split_count: u64) -> Result<()> {
+    if split_count < 2 {
+        bail!("--split-count must be at least 2, a coin split into one is left as is");
+    }
+    if coin_churn_gas_budget(split_count) > *MAX_GAS_BUDGET {
+        bail!(
+            "--split-count {} needs a gas budget above the maximum of {}, use at most {} coins",
+            split_count,
+            *MAX_GAS_BUDGET,
+            (*MAX_GAS_BUDGET - MAX_GAS) / GAS_PER_CHURNED_COIN
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct CoinChurnTestPayload {
+    // Coin split and merged back, it keeps its id for the whole run
+    churn_coin: ObjectRef,
+    // Coins split off the churn coin, merged back by the next transaction
+    split_coins: Vec<ObjectRef>,
+    split_count: u64,
+    gas: Gas,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for CoinChurnTestPayload {
+    /// splits the churn coin into `split_count` coins when it is whole and
+    /// merges the coins split off back into it otherwise
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        let gas_budget = sample_gas_budget(
+            self.gas_budget,
+            coin_churn_gas_budget(self.split_count),
+            gas_price,
+        );
+        let sender = self
+            .gas
+            .1
+            .get_owner_address()
+            .expect("Cannot convert owner to address");
+        if self.split_coins.is_empty() {
+            make_divide_coin_tx(
+                self.gas.0,
+                self.churn_coin,
+                self.split_count,
+                sender,
+                &self.gas.2,
+                gas_budget,
+                Some(gas_price),
+            )
+        } else {
+            make_join_coins_tx(
+                self.gas.0,
+                self.churn_coin,
+                self.split_coins.clone(),
+                sender,
+                &self.gas.2,
+                gas_budget,
+                Some(gas_price),
+            )
+        }
+    }
+
+    fn make_new_payload(
+        self: Box<Self>,
+        new_object: ObjectRef,
+        new_gas: ObjectRef,
+        effects: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        // A split creates the coins the merge takes back, a merge deletes
+        // them and leaves the churn coin whole for the next split
+        let split_coins = if self.split_coins.is_empty() {
+            effects
+                .created()
+                .into_iter()
+                .map(|(object_ref, _)| object_ref)
+                .collect()
+        } else {
+            vec![]
+        };
+        Box::new(CoinChurnTestPayload {
+            churn_coin: new_object,
+            split_coins,
+            split_count: self.split_count,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.churn_coin.0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::CoinChurn
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &CoinChurnTestPayload)
+    }
+}
+
+/// Every payload splits a coin of its own into `split_count` coins and
+/// merges them back in turn, fragmenting and defragmenting the coins of
+/// its account
+#[derive(Debug)]
+pub struct CoinChurnWorkload {
+    pub split_count: u64,
+    // Gas budget of splits and merges, defaults to coin_churn_gas_budget
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl CoinChurnWorkload {
+    pub fn new_boxed(
+        split_count: u64,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(CoinChurnWorkload {
+            split_count,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        // A gas coin and a churn coin per payload. Coins of one owner are
+        // interchangeable, so any two make a pair
+        generate_coin_configs_for_single_owner(num_payloads * 2, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for CoinChurnWorkload {
+    async fn init(
+        &mut self,
+        _init_config: WorkloadInitGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        _system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        payload_config
+            .coin_churn_payload_gas
+            .chunks_exact(2)
+            .map(|pair| {
+                Box::new(CoinChurnTestPayload {
+                    churn_coin: pair[1].0,
+                    split_coins: vec![],
+                    split_count: self.split_count,
+                    gas: pair[0].clone(),
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::CoinChurn
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &CoinChurnWorkload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_split_count() {
+        assert!(check_split_count(0).is_err());
+        assert!(check_split_count(1).is_err());
+        assert!(check_split_count(2).is_ok());
+        let max = (*MAX_GAS_BUDGET - MAX_GAS) / GAS_PER_CHURNED_COIN;
+        assert!(check_split_count(max).is_ok());
+        assert!(check_split_count(max + 1).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_collection_churn_tx, make_collection_create_tx, AmountDistribution};
+use crate::workloads::package::create_publish_package_transaction;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{path::PathBuf, sync::Arc};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::AccountKeyPair;
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+use test_utils::transaction::parse_package_ref;
+use tracing::info;
+
+#[derive(Debug)]
+pub struct CollectionTestPayload {
+    package_id: ObjectID,
+    collection: Option<ObjectRef>,
+    gas: ObjectRef,
+    sender: SuiAddress,
+    keypair: Arc<AccountKeyPair>,
+    ops_per_tx: u64,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for CollectionTestPayload {
+    /// the first `make_transaction` call creates the collection owned by
+    /// this payload, every followup call churns `ops_per_tx` entries of it
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = Some(*self.system_state_observer.reference_gas_price.borrow());
+        let gas_budget = sample_gas_budget(
+            self.gas_budget,
+            MAX_GAS,
+            *self.system_state_observer.reference_gas_price.borrow(),
+        );
+        match self.collection {
+            Some(collection) => make_collection_churn_tx(
+                self.gas,
+                self.package_id,
+                collection,
+                self.ops_per_tx,
+                self.sender,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+            None => make_collection_create_tx(
+                self.gas,
+                self.package_id,
+                self.sender,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+        }
+    }
+
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        effects: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        let collection = match self.collection {
+            None => effects.created().get(0).unwrap().0,
+            Some(collection) => {
+                effects
+                    .mutated()
+                    .iter()
+                    .find(|(object_ref, _)| object_ref.0 == collection.0)
+                    .unwrap()
+                    .0
+            }
+        };
+        Box::new(CollectionTestPayload {
+            package_id: self.package_id,
+            collection: Some(collection),
+            gas: new_gas,
+            sender: self.sender,
+            keypair: self.keypair,
+            ops_per_tx: self.ops_per_tx,
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Collection
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &CollectionTestPayload)
+    }
+}
+
+/// Churns vector and table entries of owned collections, the computation
+/// cost of each transaction is reported in the `computation_cost` metric
+#[derive(Debug)]
+pub struct CollectionWorkload {
+    pub package_id: Option<ObjectID>,
+    // Entries inserted into (and removed from) each collection per transaction
+    pub ops_per_tx: u64,
+    // Gas budget of collection transactions, defaults to MAX_GAS
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl CollectionWorkload {
+    pub fn new_boxed(
+        ops_per_tx: u64,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(CollectionWorkload {
+            package_id: None,
+            ops_per_tx,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init() -> Vec<GasCoinConfig> {
+        // Gas coin for publishing package
+        generate_coin_configs_for_single_owner(1, MAX_GAS_FOR_TESTING)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        // Gas coins for creating and churning the collections
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for CollectionWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if self.package_id.is_some() {
+            return Ok(());
+        }
+        let (gas, owner, keypair) = init_config
+            .collection_init_gas
+            .first()
+            .ok_or_else(|| anyhow!("Not enough gas to initialize collection workload"))?;
+        info!("Publishing collection package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("data/collection");
+        let transaction = create_publish_package_transaction(
+            *gas,
+            path,
+            owner.get_owner_address()?,
+            keypair,
+            *system_state_observer.reference_gas_price.borrow(),
+        )?;
+        let (_, effects) = proxy.execute_transaction(transaction.into()).await?;
+        self.package_id = Some(
+            parse_package_ref(&effects.created())
+                .ok_or_else(|| anyhow!("Collection package was not published"))?
+                .0,
+        );
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let package_id = self
+            .package_id
+            .expect("Collection workload must be initialized before making payloads");
+        payload_config
+            .collection_payload_gas
+            .into_iter()
+            .map(|(gas, owner, keypair)| {
+                Box::new(CollectionTestPayload {
+                    package_id,
+                    collection: None,
+                    gas,
+                    sender: owner.get_owner_address().unwrap(),
+                    keypair,
+                    ops_per_tx: self.ops_per_tx,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Collection
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &CollectionWorkload)
+    }
+}
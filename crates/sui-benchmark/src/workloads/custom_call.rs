@@ -0,0 +1,351 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::rng::bench_rng;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::make_custom_call_tx;
+use crate::workloads::payload::Payload;
+use crate::workloads::registry::WorkloadFactory;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use move_core_types::identifier::Identifier;
+use rand::prelude::*;
+use rand_distr::WeightedAliasIndex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::gas::{MAX_GAS_BUDGET, MIN_GAS_BUDGET};
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+
+/// Name the custom call workload is registered under
+pub const CUSTOM_CALL_WORKLOAD: &str = "custom_call";
+
+/// Move function of an already published package, taking no arguments but
+/// the transaction context
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CustomCall {
+    pub package: ObjectID,
+    pub module: Identifier,
+    pub function: Identifier,
+    // Relative weight of the call among the custom calls
+    pub weight: u32,
+    // Gas budget of the call, MAX_GAS unless given
+    pub gas_budget: u64,
+}
+
+impl fmt::Display for CustomCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}::{}::{}", self.package, self.module, self.function)
+    }
+}
+
+impl FromStr for CustomCall {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || {
+            format!(
+                "Custom call {:?} is not of the form \
+                <package>::<module>::<function>[:<weight>[:<gas budget>]]",
+                s
+            )
+        };
+        let (package, module, function) = match s.trim().split("::").collect::<Vec<_>>()[..] {
+            [package, module, function] => (package, module, function),
+            _ => return Err(malformed()),
+        };
+        let parts: Vec<&str> = function.split(':').collect();
+        let (function, weight, gas_budget) = match parts[..] {
+            [function] => (function, 1, MAX_GAS),
+            [function, weight] => (
+                function,
+                weight.parse::<u32>().map_err(|e| format!("{}", e))?,
+                MAX_GAS,
+            ),
+            [function, weight, gas_budget] => (
+                function,
+                weight.parse::<u32>().map_err(|e| format!("{}", e))?,
+                gas_budget.parse::<u64>().map_err(|e| format!("{}", e))?,
+            ),
+            _ => return Err(malformed()),
+        };
+        let call = CustomCall {
+            package: ObjectID::from_str(package)
+                .map_err(|e| format!("Invalid package {}: {}", package, e))?,
+            module: Identifier::new(module).map_err(|e| format!("{}", e))?,
+            function: Identifier::new(function).map_err(|e| format!("{}", e))?,
+            weight,
+            gas_budget,
+        };
+        if call.weight == 0 {
+            return Err(format!("Weight of custom call {} must be above 0", call));
+        }
+        if call.gas_budget < *MIN_GAS_BUDGET || call.gas_budget > *MAX_GAS_BUDGET {
+            return Err(format!(
+                "Gas budget {} of custom call {} must be between {} and {}",
+                call.gas_budget, call, *MIN_GAS_BUDGET, *MAX_GAS_BUDGET
+            ));
+        }
+        Ok(call)
+    }
+}
+
+/// Calls of the custom call workload, each issued with probability
+/// proportional to its weight
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CustomCalls(pub Vec<CustomCall>);
+
+impl FromStr for CustomCalls {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let calls = s
+            .split(',')
+            .map(CustomCall::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if calls
+            .iter()
+            .try_fold(0u32, |total, call| total.checked_add(call.weight))
+            .is_none()
+        {
+            return Err("The weights of the custom calls add up above u32::MAX".to_string());
+        }
+        Ok(CustomCalls(calls))
+    }
+}
+
+impl CustomCalls {
+    /// Weight of the workload against the other workloads, the weights of
+    /// its calls added up
+    pub fn total_weight(&self) -> u32 {
+        self.0.iter().map(|call| call.weight).sum()
+    }
+
+    /// Amount of each payload coin. Any coin may carry the most expensive
+    /// call, which it pays with the headroom MAX_GAS_FOR_TESTING leaves the
+    /// built-in workloads over MAX_GAS
+    pub fn payload_coin_amount(&self) -> u64 {
+        let max_gas_budget = self.0.iter().map(|call| call.gas_budget).max();
+        max_gas_budget
+            .unwrap_or(MAX_GAS)
+            .saturating_mul(MAX_GAS_FOR_TESTING / MAX_GAS)
+            .max(MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[derive(Debug)]
+pub struct CustomCallTestPayload {
+    calls: Arc<CustomCalls>,
+    dist: Arc<WeightedAliasIndex<u32>>,
+    // Call issued by the next transaction
+    curr_index: usize,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for CustomCallTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        _: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        Box::new(CustomCallTestPayload {
+            curr_index: self.dist.sample(&mut bench_rng()),
+            calls: self.calls,
+            dist: self.dist,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        let call = &self.calls.0[self.curr_index];
+        make_custom_call_tx(
+            self.gas.0,
+            call,
+            self.gas
+                .1
+                .get_owner_address()
+                .expect("Cannot convert owner to address"),
+            &self.gas.2,
+            sample_gas_budget(None, call.gas_budget, gas_price),
+            Some(gas_price),
+        )
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0 .0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Custom(CUSTOM_CALL_WORKLOAD)
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &CustomCallTestPayload)
+    }
+}
+
+/// Calls Move functions of packages published beforehand, picking the
+/// function of each transaction by its weight, to benchmark the function
+/// mix of a dApp
+#[derive(Debug)]
+pub struct CustomCallWorkload {
+    pub calls: Arc<CustomCalls>,
+}
+
+impl CustomCallWorkload {
+    pub fn new_boxed(calls: Arc<CustomCalls>) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(CustomCallWorkload { calls }))
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for CustomCallWorkload {
+    async fn init(
+        &mut self,
+        _: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        _system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        // Nothing is published, the packages must already be there
+        for call in &self.calls.0 {
+            let object = proxy
+                .get_object(call.package)
+                .await
+                .with_context(|| format!("Package of custom call {} not found", call))?;
+            if !object.is_package() {
+                bail!("{} of custom call {} is not a package", call.package, call);
+            }
+        }
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        mut payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let weights = self.calls.0.iter().map(|call| call.weight).collect();
+        let dist = Arc::new(WeightedAliasIndex::new(weights).expect("Custom calls are weighted"));
+        payload_config
+            .custom_payload_gas
+            .remove(CUSTOM_CALL_WORKLOAD)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|gas| {
+                Box::new(CustomCallTestPayload {
+                    calls: self.calls.clone(),
+                    curr_index: dist.sample(&mut bench_rng()),
+                    dist: dist.clone(),
+                    gas,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Custom(CUSTOM_CALL_WORKLOAD)
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &CustomCallWorkload)
+    }
+}
+
+/// Builds the custom call workload of --custom-calls for the workload
+/// registry, weighted by the total weight of the calls
+pub struct CustomCallFactory {
+    calls: Arc<CustomCalls>,
+}
+
+impl CustomCallFactory {
+    pub fn new(calls: CustomCalls) -> Self {
+        CustomCallFactory {
+            calls: Arc::new(calls),
+        }
+    }
+}
+
+impl WorkloadFactory for CustomCallFactory {
+    fn name(&self) -> &'static str {
+        CUSTOM_CALL_WORKLOAD
+    }
+    fn generate_coin_config_for_payloads(&self, max_ops: u64) -> Vec<GasCoinConfig> {
+        generate_coin_configs_for_single_owner(max_ops, self.calls.payload_coin_amount())
+    }
+    fn make_workload(&self) -> Box<dyn Workload<dyn Payload>> {
+        CustomCallWorkload::new_boxed(self.calls.clone())
+    }
+    fn flags(&self) -> &'static [&'static str] {
+        &["custom-calls"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_calls() {
+        let calls: CustomCalls = "0x2::coin::zero:3,0x2::pay::noop:1:5000,0x2::a::b"
+            .parse()
+            .unwrap();
+        let summary: Vec<(String, u32, u64)> = calls
+            .0
+            .iter()
+            .map(|call| (call.function.to_string(), call.weight, call.gas_budget))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("zero".to_string(), 3, MAX_GAS),
+                ("noop".to_string(), 1, 5000),
+                ("b".to_string(), 1, MAX_GAS),
+            ]
+        );
+        assert_eq!(calls.0[0].package, ObjectID::from_str("0x2").unwrap());
+        assert_eq!(calls.total_weight(), 5);
+
+        for malformed in [
+            "",
+            "0x2::coin",
+            "0x2::coin::zero:0",
+            "0x2::coin::zero:1:2:3",
+            "0x2::coin::zero:-1",
+            "0x2::1coin::zero",
+            "nothex::coin::zero",
+            "0x2::coin::zero:4294967295,0x2::coin::zero:1",
+        ] {
+            assert!(malformed.parse::<CustomCalls>().is_err(), "{}", malformed);
+        }
+        let too_expensive = format!("0x2::coin::zero:1:{}", *MAX_GAS_BUDGET + 1);
+        assert!(too_expensive.parse::<CustomCalls>().is_err());
+    }
+
+    #[test]
+    fn test_payload_coins_pay_the_most_expensive_call() {
+        let cheap: CustomCalls = "0x2::a::b:1".parse().unwrap();
+        assert_eq!(cheap.payload_coin_amount(), MAX_GAS_FOR_TESTING);
+        let mixed: CustomCalls = format!("0x2::a::b:9,0x2::a::c:1:{}", MAX_GAS * 4)
+            .parse()
+            .unwrap();
+        assert_eq!(mixed.payload_coin_amount(), MAX_GAS_FOR_TESTING * 4);
+        let configs = CustomCallFactory::new(mixed).generate_coin_config_for_payloads(3);
+        assert!(configs.iter().all(|c| c.amount == MAX_GAS_FOR_TESTING * 4));
+    }
+}
@@ -1,19 +1,29 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::rng::bench_rng;
 use crate::system_state_observer::SystemStateObserver;
-use crate::workloads::payload::Payload;
+use crate::util::AmountDistribution;
+use crate::workloads::payload::{Payload, PostCondition};
 use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
-use crate::workloads::{GasCoinConfig, WorkloadInitGas, WorkloadPayloadGas};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
 use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use rand::seq::IteratorRandom;
+use std::str::FromStr;
 use std::sync::Arc;
-use sui_core::test_utils::make_transfer_sui_transaction;
+use sui_core::test_utils::make_transfer_sui_transaction_with_gas_budget;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::crypto::{get_key_pair, AccountKeyPair};
+use sui_types::crypto::AccountKeyPair;
+use sui_types::governance::StakedSui;
 use sui_types::messages::VerifiedTransaction;
-use test_utils::messages::make_delegation_transaction;
+use test_utils::messages::{
+    make_delegation_transaction_with_gas_budget, MAX_DELEGATION_GAS, MAX_GAS,
+};
 
 #[derive(Debug)]
 pub struct DelegationTestPayload {
@@ -22,6 +32,7 @@ pub struct DelegationTestPayload {
     validator: SuiAddress,
     sender: SuiAddress,
     keypair: Arc<AccountKeyPair>,
+    gas_budget: Option<AmountDistribution>,
     system_state_observer: Arc<SystemStateObserver>,
 }
 
@@ -31,20 +42,30 @@ impl Payload for DelegationTestPayload {
     /// followup call creates delegation transaction itself
     fn make_transaction(&self) -> VerifiedTransaction {
         match self.coin {
-            Some(coin) => make_delegation_transaction(
+            Some(coin) => make_delegation_transaction_with_gas_budget(
                 self.gas,
                 coin,
                 self.validator,
                 self.sender,
                 &self.keypair,
+                sample_gas_budget(
+                    self.gas_budget,
+                    MAX_DELEGATION_GAS,
+                    *self.system_state_observer.reference_gas_price.borrow(),
+                ),
                 Some(*self.system_state_observer.reference_gas_price.borrow()),
             ),
-            None => make_transfer_sui_transaction(
+            None => make_transfer_sui_transaction_with_gas_budget(
                 self.gas,
                 self.sender,
                 Some(1),
                 self.sender,
                 &self.keypair,
+                sample_gas_budget(
+                    self.gas_budget,
+                    MAX_GAS,
+                    *self.system_state_observer.reference_gas_price.borrow(),
+                ),
                 Some(*self.system_state_observer.reference_gas_price.borrow()),
             ),
         }
@@ -60,12 +81,27 @@ impl Payload for DelegationTestPayload {
             None => Some(effects.created().get(0).unwrap().0),
             Some(_) => None,
         };
+        // Move on to an active validator when the committee changed under us
+        let mut validator = self.validator;
+        if coin.is_none() {
+            let committee = self.system_state_observer.committee.borrow();
+            if !committee.validator_addresses.is_empty()
+                && !committee.validator_addresses.contains(&validator)
+            {
+                validator = *committee
+                    .validator_addresses
+                    .iter()
+                    .choose(&mut bench_rng())
+                    .unwrap();
+            }
+        }
         Box::new(DelegationTestPayload {
             coin,
             gas: new_gas,
-            validator: self.validator,
+            validator,
             sender: self.sender,
             keypair: self.keypair,
+            gas_budget: self.gas_budget,
             system_state_observer: self.system_state_observer,
         })
     }
@@ -78,29 +114,92 @@ impl Payload for DelegationTestPayload {
         WorkloadType::Delegation
     }
 
+    fn postcondition(&self) -> Option<PostCondition> {
+        // Only the delegation itself records stake, as a StakedSui object
+        // transferred to the delegator
+        self.coin.map(|_| PostCondition::CreatedObject {
+            owner: self.sender,
+            type_: StakedSui::type_(),
+        })
+    }
+
     fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self as &DelegationTestPayload)
     }
 }
 
-pub struct DelegationWorkload;
+/// Validators receiving stake, parsed from "random", "highest-stake",
+/// "lowest-stake" or a comma separated list of validator addresses
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub enum DelegationTarget {
+    #[default]
+    Random,
+    HighestStake,
+    LowestStake,
+    Validators(Vec<SuiAddress>),
+}
+
+impl DelegationTarget {
+    /// Validators payloads pick from, `active` holds the active validators
+    /// ordered by stake, largest first
+    pub fn select(&self, active: &[SuiAddress]) -> Result<Vec<SuiAddress>> {
+        let targets = match self {
+            DelegationTarget::Random => active.to_vec(),
+            DelegationTarget::HighestStake => active.first().copied().into_iter().collect(),
+            DelegationTarget::LowestStake => active.last().copied().into_iter().collect(),
+            DelegationTarget::Validators(validators) => {
+                if let Some(inactive) = validators.iter().find(|v| !active.contains(v)) {
+                    return Err(anyhow!("{} is not an active validator", inactive));
+                }
+                validators.clone()
+            }
+        };
+        if targets.is_empty() {
+            return Err(anyhow!("No active validators to delegate to"));
+        }
+        Ok(targets)
+    }
+}
+
+impl FromStr for DelegationTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(DelegationTarget::Random),
+            "highest-stake" => Ok(DelegationTarget::HighestStake),
+            "lowest-stake" => Ok(DelegationTarget::LowestStake),
+            _ => s
+                .split(',')
+                .map(|addr| SuiAddress::from_str(addr.trim()).map_err(|e| format!("{e}")))
+                .collect::<Result<Vec<_>, _>>()
+                .map(DelegationTarget::Validators)
+                .map_err(|e| {
+                    format!(
+                        "Required random, highest-stake, lowest-stake or a comma separated \
+                        list of validator addresses: {e}"
+                    )
+                }),
+        }
+    }
+}
+
+pub struct DelegationWorkload {
+    // Gas budget of delegations, defaults to the budget of each transaction kind
+    gas_budget: Option<AmountDistribution>,
+    target: DelegationTarget,
+}
 
 impl DelegationWorkload {
-    pub fn new_boxed() -> Box<dyn Workload<dyn Payload>> {
-        Box::<dyn Workload<dyn Payload>>::from(Box::new(DelegationWorkload))
+    pub fn new_boxed(
+        gas_budget: Option<AmountDistribution>,
+        target: DelegationTarget,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(DelegationWorkload { gas_budget, target }))
     }
 
     pub fn generate_gas_config_for_payloads(count: u64) -> Vec<GasCoinConfig> {
-        (0..count)
-            .map(|_| {
-                let (address, keypair) = get_key_pair();
-                GasCoinConfig {
-                    amount: MAX_GAS_FOR_TESTING,
-                    address,
-                    keypair: Arc::new(keypair),
-                }
-            })
-            .collect()
+        generate_coin_configs_for_single_owner(count, MAX_GAS_FOR_TESTING)
     }
 }
 
@@ -111,7 +210,8 @@ impl Workload<dyn Payload> for DelegationWorkload {
         _: WorkloadInitGas,
         _: Arc<dyn ValidatorProxy + Sync + Send>,
         _system_state_observer: Arc<SystemStateObserver>,
-    ) {
+    ) -> Result<()> {
+        Ok(())
     }
 
     async fn make_test_payloads(
@@ -121,22 +221,35 @@ impl Workload<dyn Payload> for DelegationWorkload {
         proxy: Arc<dyn ValidatorProxy + Sync + Send>,
         system_state_observer: Arc<SystemStateObserver>,
     ) -> Vec<Box<dyn Payload>> {
-        let validators = proxy
-            .get_validators()
-            .await
-            .expect("failed to fetch validators");
+        // Stake based targets need the stake order tracked by the observer
+        let active = match self.target {
+            DelegationTarget::HighestStake | DelegationTarget::LowestStake => system_state_observer
+                .committee
+                .borrow()
+                .validator_addresses
+                .clone(),
+            _ => proxy
+                .get_validators()
+                .await
+                .expect("failed to fetch validators"),
+        };
+        let validators = self
+            .target
+            .select(&active)
+            .expect("failed to select delegation targets");
 
         gas_config
             .delegation_payload_gas
             .into_iter()
             .map(|(gas, owner, keypair)| {
-                let validator = *validators.iter().choose(&mut rand::thread_rng()).unwrap();
+                let validator = *validators.iter().choose(&mut bench_rng()).unwrap();
                 Box::new(DelegationTestPayload {
                     coin: None,
                     gas,
                     validator,
                     sender: owner.get_owner_address().unwrap(),
                     keypair,
+                    gas_budget: self.gas_budget,
                     system_state_observer: system_state_observer.clone(),
                 })
             })
@@ -152,3 +265,43 @@ impl Workload<dyn Payload> for DelegationWorkload {
         write!(f, "DelegationWorkload")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delegation_target_selection() {
+        let active: Vec<SuiAddress> = (0..3)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        assert_eq!(
+            DelegationTarget::from_str("highest-stake")
+                .unwrap()
+                .select(&active)
+                .unwrap(),
+            vec![active[0]]
+        );
+        assert_eq!(
+            DelegationTarget::from_str("lowest-stake")
+                .unwrap()
+                .select(&active)
+                .unwrap(),
+            vec![active[2]]
+        );
+        assert_eq!(DelegationTarget::Random.select(&active).unwrap(), active);
+
+        let listed = format!("{},{}", active[2], active[1]);
+        assert_eq!(
+            DelegationTarget::from_str(&listed)
+                .unwrap()
+                .select(&active)
+                .unwrap(),
+            vec![active[2], active[1]]
+        );
+        let unknown = DelegationTarget::Validators(vec![SuiAddress::random_for_testing_only()]);
+        assert!(unknown.select(&active).is_err());
+        assert!(DelegationTarget::from_str("largest").is_err());
+        assert!(DelegationTarget::LowestStake.select(&[]).is_err());
+    }
+}
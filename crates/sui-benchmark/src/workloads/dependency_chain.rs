@@ -0,0 +1,182 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_pay_tx, AmountDistribution};
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use sui_core::test_utils::make_transfer_sui_transaction_with_gas_budget;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::AccountKeyPair;
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+
+// Value of the coin passed along a chain, it is never used to pay for gas
+const CHAIN_COIN_AMOUNT: u64 = 1;
+
+#[derive(Debug)]
+pub struct DependencyChainTestPayload {
+    // Coin created by the previous transaction of the chain
+    coin: Option<ObjectRef>,
+    // Position of the next transaction in its chain
+    step: u64,
+    chain_length: u64,
+    gas: ObjectRef,
+    sender: SuiAddress,
+    keypair: Arc<AccountKeyPair>,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for DependencyChainTestPayload {
+    /// the first transaction of a chain splits a new coin off the gas coin,
+    /// each following one spends the coin created by its predecessor in full,
+    /// which deletes it and creates the input of the next transaction
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = Some(*self.system_state_observer.reference_gas_price.borrow());
+        let gas_budget = sample_gas_budget(
+            self.gas_budget,
+            MAX_GAS,
+            *self.system_state_observer.reference_gas_price.borrow(),
+        );
+        match self.coin {
+            None => make_transfer_sui_transaction_with_gas_budget(
+                self.gas,
+                self.sender,
+                Some(CHAIN_COIN_AMOUNT),
+                self.sender,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+            Some(coin) => make_pay_tx(
+                vec![coin],
+                self.sender,
+                vec![self.sender],
+                vec![CHAIN_COIN_AMOUNT],
+                self.gas,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+        }
+    }
+
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        effects: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        // The coin at the end of a chain is left behind, the next chain
+        // starts over from the gas coin
+        let step = (self.step + 1) % self.chain_length;
+        let coin = if step == 0 {
+            None
+        } else {
+            Some(effects.created().get(0).unwrap().0)
+        };
+        Box::new(DependencyChainTestPayload {
+            coin,
+            step,
+            chain_length: self.chain_length,
+            gas: new_gas,
+            sender: self.sender,
+            keypair: self.keypair,
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::DependencyChain
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &DependencyChainTestPayload)
+    }
+}
+
+/// Every payload runs chains of `chain_length` transactions where each one
+/// spends an object created by the one before, so transactions within a
+/// chain are serialized while the chains of all payloads run in parallel
+#[derive(Debug)]
+pub struct DependencyChainWorkload {
+    pub chain_length: u64,
+    // Gas budget of chain transactions, defaults to MAX_GAS
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl DependencyChainWorkload {
+    pub fn new_boxed(
+        chain_length: u64,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(DependencyChainWorkload {
+            chain_length: chain_length.max(1),
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        // Gas coins paying for the chains and funding the chain coins
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for DependencyChainWorkload {
+    async fn init(
+        &mut self,
+        _: WorkloadInitGas,
+        _: Arc<dyn ValidatorProxy + Sync + Send>,
+        _system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        payload_config
+            .dependency_chain_payload_gas
+            .into_iter()
+            .map(|(gas, owner, keypair)| {
+                Box::new(DependencyChainTestPayload {
+                    coin: None,
+                    step: 0,
+                    chain_length: self.chain_length,
+                    gas,
+                    sender: owner.get_owner_address().unwrap(),
+                    keypair,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::DependencyChain
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &DependencyChainWorkload)
+    }
+}
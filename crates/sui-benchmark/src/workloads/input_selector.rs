@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::rng::bench_rng;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Selector shared by all payloads of a workload
+pub type SharedInputSelector<T> = Arc<Mutex<InputSelector<T>>>;
+
+/// Picks inputs at random from a pool while keeping track of the most recent
+/// picks, an input is not picked again until half of the pool has been picked
+/// after it. Spreading transactions over the full pool this way keeps them
+/// from being served out of caches warmed by the previous transactions.
+pub struct InputSelector<T> {
+    pool: Vec<T>,
+    // Indices into the pool that can be picked next
+    available: Vec<usize>,
+    // Indices picked recently, oldest first
+    recent: VecDeque<usize>,
+    window: usize,
+}
+
+impl<T: Clone> InputSelector<T> {
+    pub fn new(pool: Vec<T>) -> Self {
+        let window = pool.len() / 2;
+        Self {
+            available: (0..pool.len()).collect(),
+            recent: VecDeque::with_capacity(window + 1),
+            window,
+            pool,
+        }
+    }
+
+    pub fn new_shared(pool: Vec<T>) -> SharedInputSelector<T> {
+        Arc::new(Mutex::new(Self::new(pool)))
+    }
+
+    /// Returns None only if the pool is empty
+    pub fn pick(&mut self) -> Option<T> {
+        if self.available.is_empty() {
+            return None;
+        }
+        let pos = bench_rng().gen_range(0..self.available.len());
+        let index = self.available.swap_remove(pos);
+        self.recent.push_back(index);
+        if self.recent.len() > self.window {
+            let oldest = self.recent.pop_front().unwrap();
+            self.available.push(oldest);
+        }
+        Some(self.pool[index].clone())
+    }
+}
+
+impl<T> std::fmt::Debug for InputSelector<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputSelector")
+            .field("pool_size", &self.pool.len())
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_inputs_are_not_repeated() {
+        let mut selector = InputSelector::new((0..10).collect::<Vec<u32>>());
+        let picks: Vec<u32> = (0..1000).map(|_| selector.pick().unwrap()).collect();
+        for (i, pick) in picks.iter().enumerate() {
+            let previous = &picks[i.saturating_sub(5)..i];
+            assert!(
+                !previous.contains(pick),
+                "{} repeated within {:?}",
+                pick,
+                previous
+            );
+        }
+        // Every input of the pool gets picked
+        for input in 0..10 {
+            assert!(picks.contains(&input));
+        }
+
+        let mut single = InputSelector::new(vec![7]);
+        assert_eq!(single.pick(), Some(7));
+        assert_eq!(single.pick(), Some(7));
+        assert_eq!(InputSelector::<u32>::new(vec![]).pick(), None);
+    }
+}
@@ -1,26 +1,60 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod batch;
+pub mod coin_churn;
+pub mod collection;
+pub mod custom_call;
 pub mod delegation;
+pub mod dependency_chain;
+pub mod input_selector;
+pub mod nft_mint;
+pub mod noop;
+pub mod object_fanout;
+pub mod package;
 pub mod payload;
+pub mod read;
+pub mod registry;
+pub mod send_to_object;
 pub mod shared_counter;
+pub mod shared_lifecycle;
 pub mod transfer_object;
 pub mod workload;
 pub mod workload_configuration;
+pub mod wrap_unwrap;
 
+use anyhow::{bail, Result};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::rng::{bench_rng, get_key_pair};
+use crate::util::AmountDistribution;
 use crate::workloads::payload::Payload;
-use delegation::DelegationWorkload;
-use shared_counter::SharedCounterWorkload;
+use batch::BatchWorkload;
+use coin_churn::CoinChurnWorkload;
+use collection::CollectionWorkload;
+use delegation::{DelegationTarget, DelegationWorkload};
+use dependency_chain::DependencyChainWorkload;
+use nft_mint::{NftMetadataLengths, NftMintWorkload};
+use noop::NoopWorkload;
+use object_fanout::ObjectFanoutWorkload;
+use read::ReadWorkload;
+use send_to_object::SendToObjectWorkload;
+use shared_counter::{num_shared_counters, SharedCounterWorkload};
+use shared_lifecycle::SharedLifecycleWorkload;
+use strum::IntoEnumIterator;
 use sui_types::base_types::{ObjectRef, SuiAddress};
 use sui_types::crypto::AccountKeyPair;
 use sui_types::object::Owner;
 use transfer_object::TransferObjectWorkload;
 use workload::*;
+use wrap_unwrap::WrapUnwrapWorkload;
 
 pub type Gas = (ObjectRef, Owner, Arc<AccountKeyPair>);
+// Existing account supplied to send the benchmark transactions
+pub type SenderAccount = (SuiAddress, Arc<AccountKeyPair>);
 
 #[derive(Clone)]
 pub struct GasCoinConfig {
@@ -32,15 +66,92 @@ pub struct GasCoinConfig {
     pub keypair: Arc<AccountKeyPair>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct WorkloadInitGas {
     // Gas coins to initialize shared counter workload
     // This includes the coins to publish the package and create
     // shared counters
     pub shared_counter_init_gas: Vec<Gas>,
+    // Gas coin to publish the collection package
+    pub collection_init_gas: Vec<Gas>,
+    // Gas coin to publish the wrap_unwrap package
+    pub wrap_unwrap_init_gas: Vec<Gas>,
+    // Gas coin to publish the basics package of the shared lifecycle workload
+    pub shared_lifecycle_init_gas: Vec<Gas>,
+    // Gas coin to publish the noop package
+    pub noop_init_gas: Vec<Gas>,
+    // Destination objects of the send_to_object workload
+    pub send_to_object_init_gas: Vec<Gas>,
+    // Gas coin to publish the object fanout package
+    pub object_fanout_init_gas: Vec<Gas>,
+    // Gas coin to publish the nft mint package
+    pub nft_mint_init_gas: Vec<Gas>,
+    // Gas coin to publish the noop package called by the batches
+    pub batch_init_gas: Vec<Gas>,
+    // Init coins of the registered workloads, by workload name
+    pub custom_init_gas: HashMap<&'static str, Vec<Gas>>,
 }
 
-#[derive(Clone, Debug)]
+impl WorkloadInitGas {
+    /// Moves the init coins of `workload` out into a config of their own
+    pub fn take(&mut self, workload: WorkloadType) -> WorkloadInitGas {
+        let take = std::mem::take;
+        match workload {
+            WorkloadType::SharedCounter => WorkloadInitGas {
+                shared_counter_init_gas: take(&mut self.shared_counter_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::Collection => WorkloadInitGas {
+                collection_init_gas: take(&mut self.collection_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::WrapUnwrap => WorkloadInitGas {
+                wrap_unwrap_init_gas: take(&mut self.wrap_unwrap_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::SharedLifecycle => WorkloadInitGas {
+                shared_lifecycle_init_gas: take(&mut self.shared_lifecycle_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::Noop => WorkloadInitGas {
+                noop_init_gas: take(&mut self.noop_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::SendToObject => WorkloadInitGas {
+                send_to_object_init_gas: take(&mut self.send_to_object_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::ObjectFanout => WorkloadInitGas {
+                object_fanout_init_gas: take(&mut self.object_fanout_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::NftMint => WorkloadInitGas {
+                nft_mint_init_gas: take(&mut self.nft_mint_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::Batch => WorkloadInitGas {
+                batch_init_gas: take(&mut self.batch_init_gas),
+                ..Default::default()
+            },
+            WorkloadType::Custom(name) => WorkloadInitGas {
+                custom_init_gas: self
+                    .custom_init_gas
+                    .remove_entry(name)
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            WorkloadType::Combination => take(self),
+            WorkloadType::TransferObject
+            | WorkloadType::Delegation
+            | WorkloadType::Read
+            | WorkloadType::DependencyChain
+            | WorkloadType::CoinChurn => WorkloadInitGas::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct WorkloadPayloadGas {
     // Gas coins to be used as transfer tokens
     // These are the objects which get transferred
@@ -54,112 +165,851 @@ pub struct WorkloadPayloadGas {
     pub shared_counter_payload_gas: Vec<Gas>,
     // Gas coins needed to run delegation flow
     pub delegation_payload_gas: Vec<Gas>,
+    // Objects queried by the read workload
+    pub read_payload_objects: Vec<Gas>,
+    // Gas coins needed to create and churn collections
+    pub collection_payload_gas: Vec<Gas>,
+    // Gas coins needed to create containers and wrap or unwrap their objects
+    pub wrap_unwrap_payload_gas: Vec<Gas>,
+    // Gas coins needed to run dependency chains
+    pub dependency_chain_payload_gas: Vec<Gas>,
+    // Gas coins needed to create and increment shared counters during the run
+    pub shared_lifecycle_payload_gas: Vec<Gas>,
+    // Gas coins needed to call the noop function
+    pub noop_payload_gas: Vec<Gas>,
+    // Gas coins needed to send coins to the destination objects
+    pub send_to_object_payload_gas: Vec<Gas>,
+    // Gas coins needed to create the objects of the object fanout workload
+    pub object_fanout_payload_gas: Vec<Gas>,
+    // Gas coins needed to mint NFTs
+    pub nft_mint_payload_gas: Vec<Gas>,
+    // Gas coins paying for the batch transactions
+    pub batch_payload_gas: Vec<Gas>,
+    // Gas coins and the coins split and merged back by the coin churn
+    // workload, in pairs
+    pub coin_churn_payload_gas: Vec<Gas>,
+    // Payload coins of the registered workloads, by workload name
+    pub custom_payload_gas: HashMap<&'static str, Vec<Gas>>,
 }
 
-#[derive(Clone)]
+impl WorkloadPayloadGas {
+    /// Moves the payload coins of `workload` out into a config of their own
+    pub fn take(&mut self, workload: WorkloadType) -> WorkloadPayloadGas {
+        let take = std::mem::take;
+        match workload {
+            WorkloadType::SharedCounter => WorkloadPayloadGas {
+                shared_counter_payload_gas: take(&mut self.shared_counter_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::TransferObject => WorkloadPayloadGas {
+                transfer_tokens: take(&mut self.transfer_tokens),
+                transfer_object_payload_gas: take(&mut self.transfer_object_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::Delegation => WorkloadPayloadGas {
+                delegation_payload_gas: take(&mut self.delegation_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::Read => WorkloadPayloadGas {
+                read_payload_objects: take(&mut self.read_payload_objects),
+                ..Default::default()
+            },
+            WorkloadType::Collection => WorkloadPayloadGas {
+                collection_payload_gas: take(&mut self.collection_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::WrapUnwrap => WorkloadPayloadGas {
+                wrap_unwrap_payload_gas: take(&mut self.wrap_unwrap_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::DependencyChain => WorkloadPayloadGas {
+                dependency_chain_payload_gas: take(&mut self.dependency_chain_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::SharedLifecycle => WorkloadPayloadGas {
+                shared_lifecycle_payload_gas: take(&mut self.shared_lifecycle_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::Noop => WorkloadPayloadGas {
+                noop_payload_gas: take(&mut self.noop_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::SendToObject => WorkloadPayloadGas {
+                send_to_object_payload_gas: take(&mut self.send_to_object_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::ObjectFanout => WorkloadPayloadGas {
+                object_fanout_payload_gas: take(&mut self.object_fanout_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::NftMint => WorkloadPayloadGas {
+                nft_mint_payload_gas: take(&mut self.nft_mint_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::Batch => WorkloadPayloadGas {
+                batch_payload_gas: take(&mut self.batch_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::CoinChurn => WorkloadPayloadGas {
+                coin_churn_payload_gas: take(&mut self.coin_churn_payload_gas),
+                ..Default::default()
+            },
+            WorkloadType::Custom(name) => WorkloadPayloadGas {
+                custom_payload_gas: self
+                    .custom_payload_gas
+                    .remove_entry(name)
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            WorkloadType::Combination => take(self),
+        }
+    }
+
+    /// Every coin and object handed to the payloads
+    pub fn all_gas(&self) -> Vec<Gas> {
+        let mut gas: Vec<Gas> = [
+            &self.transfer_tokens,
+            &self.transfer_object_payload_gas,
+            &self.shared_counter_payload_gas,
+            &self.delegation_payload_gas,
+            &self.read_payload_objects,
+            &self.collection_payload_gas,
+            &self.wrap_unwrap_payload_gas,
+            &self.dependency_chain_payload_gas,
+            &self.shared_lifecycle_payload_gas,
+            &self.noop_payload_gas,
+            &self.send_to_object_payload_gas,
+            &self.object_fanout_payload_gas,
+            &self.nft_mint_payload_gas,
+            &self.batch_payload_gas,
+            &self.coin_churn_payload_gas,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+        gas.extend(self.custom_payload_gas.values().flatten().cloned());
+        gas
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct WorkloadGasConfig {
     pub shared_counter_workload_init_gas_config: Vec<GasCoinConfig>,
     pub shared_counter_workload_payload_gas_config: Vec<GasCoinConfig>,
     pub transfer_object_workload_tokens: Vec<GasCoinConfig>,
     pub transfer_object_workload_payload_gas_config: Vec<GasCoinConfig>,
     pub delegation_gas_configs: Vec<GasCoinConfig>,
+    pub read_object_configs: Vec<GasCoinConfig>,
+    pub collection_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub collection_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub wrap_unwrap_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub wrap_unwrap_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub dependency_chain_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub shared_lifecycle_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub shared_lifecycle_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub noop_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub noop_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub send_to_object_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub send_to_object_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub object_fanout_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub object_fanout_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub nft_mint_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub nft_mint_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub batch_workload_init_gas_config: Vec<GasCoinConfig>,
+    pub batch_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub coin_churn_workload_payload_gas_config: Vec<GasCoinConfig>,
+    pub custom_workload_init_gas_configs: HashMap<&'static str, Vec<GasCoinConfig>>,
+    pub custom_workload_payload_gas_configs: HashMap<&'static str, Vec<GasCoinConfig>>,
 }
 
-pub fn make_combination_workload(
-    target_qps: u64,
-    num_workers: u64,
-    in_flight_ratio: u64,
-    num_transfer_accounts: u64,
-    shared_counter_weight: u32,
-    transfer_object_weight: u32,
-    delegation_weight: u32,
-    payload_config: WorkloadPayloadGas,
-) -> WorkloadInfo {
-    let mut workloads = HashMap::<WorkloadType, (u32, Box<dyn Workload<dyn Payload>>)>::new();
-    if shared_counter_weight > 0 {
-        let workload = SharedCounterWorkload::new_boxed(None, vec![]);
-        workloads
-            .entry(WorkloadType::SharedCounter)
-            .or_insert((shared_counter_weight, workload));
-    }
-    if transfer_object_weight > 0 {
-        let workload = TransferObjectWorkload::new_boxed(num_transfer_accounts);
-        workloads
-            .entry(WorkloadType::TransferObject)
-            .or_insert((transfer_object_weight, workload));
-    }
-    if delegation_weight > 0 {
-        let workload = DelegationWorkload::new_boxed();
-        workloads
-            .entry(WorkloadType::Delegation)
-            .or_insert((delegation_weight, workload));
-    }
-    let workload = CombinationWorkload::new_boxed(workloads);
-    WorkloadInfo {
-        target_qps,
-        num_workers,
-        max_in_flight_ops: in_flight_ratio * target_qps,
-        workload,
-        payload_config,
+impl WorkloadGasConfig {
+    /// The coin configs of every built-in workload, init and payload ones
+    pub fn all_configs(&self) -> Vec<&Vec<GasCoinConfig>> {
+        vec![
+            &self.shared_counter_workload_init_gas_config,
+            &self.shared_counter_workload_payload_gas_config,
+            &self.transfer_object_workload_tokens,
+            &self.transfer_object_workload_payload_gas_config,
+            &self.delegation_gas_configs,
+            &self.read_object_configs,
+            &self.collection_workload_init_gas_config,
+            &self.collection_workload_payload_gas_config,
+            &self.wrap_unwrap_workload_init_gas_config,
+            &self.wrap_unwrap_workload_payload_gas_config,
+            &self.dependency_chain_workload_payload_gas_config,
+            &self.shared_lifecycle_workload_init_gas_config,
+            &self.shared_lifecycle_workload_payload_gas_config,
+            &self.noop_workload_init_gas_config,
+            &self.noop_workload_payload_gas_config,
+            &self.send_to_object_workload_init_gas_config,
+            &self.send_to_object_workload_payload_gas_config,
+            &self.object_fanout_workload_init_gas_config,
+            &self.object_fanout_workload_payload_gas_config,
+            &self.nft_mint_workload_init_gas_config,
+            &self.nft_mint_workload_payload_gas_config,
+            &self.batch_workload_init_gas_config,
+            &self.batch_workload_payload_gas_config,
+            &self.coin_churn_workload_payload_gas_config,
+        ]
+    }
+
+    /// Init and payload coin configs of the built-in `workload`
+    pub fn configs(&self, workload: WorkloadType) -> (&[GasCoinConfig], &[GasCoinConfig]) {
+        match workload {
+            WorkloadType::SharedCounter => (
+                &self.shared_counter_workload_init_gas_config,
+                &self.shared_counter_workload_payload_gas_config,
+            ),
+            WorkloadType::TransferObject => (
+                &self.transfer_object_workload_tokens,
+                &self.transfer_object_workload_payload_gas_config,
+            ),
+            WorkloadType::Delegation => (&[], &self.delegation_gas_configs),
+            WorkloadType::Read => (&[], &self.read_object_configs),
+            WorkloadType::Collection => (
+                &self.collection_workload_init_gas_config,
+                &self.collection_workload_payload_gas_config,
+            ),
+            WorkloadType::WrapUnwrap => (
+                &self.wrap_unwrap_workload_init_gas_config,
+                &self.wrap_unwrap_workload_payload_gas_config,
+            ),
+            WorkloadType::DependencyChain => {
+                (&[], &self.dependency_chain_workload_payload_gas_config)
+            }
+            WorkloadType::SharedLifecycle => (
+                &self.shared_lifecycle_workload_init_gas_config,
+                &self.shared_lifecycle_workload_payload_gas_config,
+            ),
+            WorkloadType::Noop => (
+                &self.noop_workload_init_gas_config,
+                &self.noop_workload_payload_gas_config,
+            ),
+            WorkloadType::SendToObject => (
+                &self.send_to_object_workload_init_gas_config,
+                &self.send_to_object_workload_payload_gas_config,
+            ),
+            WorkloadType::ObjectFanout => (
+                &self.object_fanout_workload_init_gas_config,
+                &self.object_fanout_workload_payload_gas_config,
+            ),
+            WorkloadType::NftMint => (
+                &self.nft_mint_workload_init_gas_config,
+                &self.nft_mint_workload_payload_gas_config,
+            ),
+            WorkloadType::Batch => (
+                &self.batch_workload_init_gas_config,
+                &self.batch_workload_payload_gas_config,
+            ),
+            WorkloadType::CoinChurn => (&[], &self.coin_churn_workload_payload_gas_config),
+            WorkloadType::Custom(name) => (
+                self.custom_workload_init_gas_configs
+                    .get(name)
+                    .map_or(&[], |configs| &configs[..]),
+                self.custom_workload_payload_gas_configs
+                    .get(name)
+                    .map_or(&[], |configs| &configs[..]),
+            ),
+            WorkloadType::Combination => (&[], &[]),
+        }
+    }
+
+    /// Sets the init and payload coin configs of `workload`, as generated by
+    /// `WorkloadParams::gas_configs` or the factory of a registered workload
+    pub fn insert(
+        &mut self,
+        workload: WorkloadType,
+        init: Vec<GasCoinConfig>,
+        payload: Vec<GasCoinConfig>,
+    ) {
+        let (init_configs, payload_configs) = match workload {
+            WorkloadType::SharedCounter => (
+                Some(&mut self.shared_counter_workload_init_gas_config),
+                &mut self.shared_counter_workload_payload_gas_config,
+            ),
+            WorkloadType::TransferObject => (
+                Some(&mut self.transfer_object_workload_tokens),
+                &mut self.transfer_object_workload_payload_gas_config,
+            ),
+            WorkloadType::Collection => (
+                Some(&mut self.collection_workload_init_gas_config),
+                &mut self.collection_workload_payload_gas_config,
+            ),
+            WorkloadType::WrapUnwrap => (
+                Some(&mut self.wrap_unwrap_workload_init_gas_config),
+                &mut self.wrap_unwrap_workload_payload_gas_config,
+            ),
+            WorkloadType::SharedLifecycle => (
+                Some(&mut self.shared_lifecycle_workload_init_gas_config),
+                &mut self.shared_lifecycle_workload_payload_gas_config,
+            ),
+            WorkloadType::Noop => (
+                Some(&mut self.noop_workload_init_gas_config),
+                &mut self.noop_workload_payload_gas_config,
+            ),
+            WorkloadType::SendToObject => (
+                Some(&mut self.send_to_object_workload_init_gas_config),
+                &mut self.send_to_object_workload_payload_gas_config,
+            ),
+            WorkloadType::ObjectFanout => (
+                Some(&mut self.object_fanout_workload_init_gas_config),
+                &mut self.object_fanout_workload_payload_gas_config,
+            ),
+            WorkloadType::NftMint => (
+                Some(&mut self.nft_mint_workload_init_gas_config),
+                &mut self.nft_mint_workload_payload_gas_config,
+            ),
+            WorkloadType::Batch => (
+                Some(&mut self.batch_workload_init_gas_config),
+                &mut self.batch_workload_payload_gas_config,
+            ),
+            WorkloadType::Custom(name) => {
+                self.custom_workload_init_gas_configs.insert(name, init);
+                self.custom_workload_payload_gas_configs
+                    .insert(name, payload);
+                return;
+            }
+            WorkloadType::Delegation => (None, &mut self.delegation_gas_configs),
+            WorkloadType::Read => (None, &mut self.read_object_configs),
+            WorkloadType::DependencyChain => {
+                (None, &mut self.dependency_chain_workload_payload_gas_config)
+            }
+            WorkloadType::CoinChurn => (None, &mut self.coin_churn_workload_payload_gas_config),
+            WorkloadType::Combination => {
+                panic!("The combination workload has no coins of its own")
+            }
+        };
+        match init_configs {
+            Some(init_configs) => *init_configs = init,
+            None => assert!(init.is_empty(), "Workload {} has no init coins", workload),
+        }
+        *payload_configs = payload;
+    }
+
+    /// Coins each workload needs, registered ones included, leaving out
+    /// the workloads that need none
+    pub fn requirements(&self) -> Vec<GasRequirement> {
+        let mut requirements: Vec<GasRequirement> = WorkloadType::iter()
+            .map(|workload| {
+                let (init, payload) = self.configs(workload);
+                GasRequirement::new(workload.to_string(), init, payload)
+            })
+            .collect();
+        let mut custom: Vec<&'static str> = self
+            .custom_workload_init_gas_configs
+            .keys()
+            .chain(self.custom_workload_payload_gas_configs.keys())
+            .copied()
+            .collect();
+        custom.sort();
+        custom.dedup();
+        for name in custom {
+            let (init, payload) = self.configs(WorkloadType::Custom(name));
+            requirements.push(GasRequirement::new(name.to_string(), init, payload));
+        }
+        requirements.retain(|requirement| requirement.coins() > 0);
+        requirements
     }
 }
 
-pub fn make_shared_counter_workload(
-    target_qps: u64,
-    num_workers: u64,
-    max_in_flight_ops: u64,
-    payload_config: WorkloadPayloadGas,
-) -> Option<WorkloadInfo> {
-    if target_qps == 0 || max_in_flight_ops == 0 || num_workers == 0 {
-        None
-    } else {
-        let workload = SharedCounterWorkload::new_boxed(None, vec![]);
-        Some(WorkloadInfo {
-            target_qps,
-            num_workers,
-            max_in_flight_ops,
+/// Coins a workload needs for its init and payloads, as split off the
+/// primary coin before the run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasRequirement {
+    pub workload: String,
+    pub init_coins: usize,
+    pub payload_coins: usize,
+    pub mist: u64,
+}
+
+impl GasRequirement {
+    fn new(workload: String, init: &[GasCoinConfig], payload: &[GasCoinConfig]) -> Self {
+        Self {
             workload,
-            payload_config,
+            init_coins: init.len(),
+            payload_coins: payload.len(),
+            mist: init
+                .iter()
+                .chain(payload)
+                .map(|config| config.amount)
+                .fold(0, u64::saturating_add),
+        }
+    }
+
+    pub fn coins(&self) -> usize {
+        self.init_coins + self.payload_coins
+    }
+}
+
+/// Configs for `count` coins of `amount` all owned by one new account. Each
+/// payload holds on to its own coin, so coins of the same owner are
+/// interchangeable, and sharing the keypair keeps the configs small even
+/// with one coin per in-flight transaction.
+pub fn generate_coin_configs_for_single_owner(count: u64, amount: u64) -> Vec<GasCoinConfig> {
+    let (address, keypair) = get_key_pair();
+    let keypair = Arc::new(keypair);
+    (0..count)
+        .map(|_| GasCoinConfig {
+            amount,
+            address,
+            keypair: keypair.clone(),
         })
+        .collect()
+}
+
+/// Hands out the coins of `configs` to new senders, `coins_per_sender`
+/// coins each, so fewer transactions in flight share one sender address
+pub fn split_coin_configs_across_senders(configs: &mut [GasCoinConfig], coins_per_sender: u64) {
+    for chunk in configs.chunks_mut(coins_per_sender.max(1) as usize) {
+        let (address, keypair) = get_key_pair();
+        let keypair: Arc<AccountKeyPair> = Arc::new(keypair);
+        for config in chunk {
+            config.address = address;
+            config.keypair = keypair.clone();
+        }
     }
 }
 
-pub fn make_transfer_object_workload(
-    target_qps: u64,
-    num_workers: u64,
-    max_in_flight_ops: u64,
-    num_transfer_accounts: u64,
-    payload_config: WorkloadPayloadGas,
-) -> Option<WorkloadInfo> {
-    if target_qps == 0 || max_in_flight_ops == 0 || num_workers == 0 {
-        None
-    } else {
-        let workload = TransferObjectWorkload::new_boxed(num_transfer_accounts);
-        Some(WorkloadInfo {
-            target_qps,
-            num_workers,
-            max_in_flight_ops,
-            workload,
-            payload_config,
+/// Hands out the coins of `configs` to the accounts of `senders` in turn,
+/// instead of to new accounts
+pub fn assign_coin_configs_to_senders(configs: &mut [GasCoinConfig], senders: &[SenderAccount]) {
+    for (config, (address, keypair)) in configs.iter_mut().zip(senders.iter().cycle()) {
+        config.address = *address;
+        config.keypair = keypair.clone();
+    }
+}
+
+/// Gas budgets of tagged transactions end in the tag, their last three
+/// decimal digits
+pub const TAG_MODULUS: u64 = 1000;
+// Tag given with --tx-tag plus one, zero when transactions are not tagged
+static TRANSACTION_TAG: AtomicU64 = AtomicU64::new(0);
+
+/// Tags every payload transaction built from now on, the tag is found
+/// on-chain as the gas budget modulo TAG_MODULUS
+pub fn set_transaction_tag(tag: u64) {
+    assert!(
+        tag < TAG_MODULUS,
+        "Transaction tag must be below {}",
+        TAG_MODULUS
+    );
+    TRANSACTION_TAG.store(tag + 1, Ordering::Relaxed);
+}
+
+/// `budget` rounded down so it ends in `tag`, changing it by less than
+/// TAG_MODULUS gas units
+pub fn tag_gas_budget(budget: u64, tag: Option<u64>) -> u64 {
+    match tag {
+        Some(tag) if budget >= TAG_MODULUS => budget - budget % TAG_MODULUS + tag,
+        _ => budget,
+    }
+}
+
+/// Budget of the next transaction, sampled from `budget` when set and
+/// `default` otherwise. Samples are capped at what a payload gas coin can pay
+/// at `gas_price`, a retry resubmits the same transaction so an unpayable
+/// budget would never succeed
+pub fn sample_gas_budget(budget: Option<AmountDistribution>, default: u64, gas_price: u64) -> u64 {
+    let budget = match budget {
+        Some(budget) => budget
+            .sample(&mut bench_rng())
+            .min(MAX_GAS_FOR_TESTING / gas_price.max(1)),
+        None => default,
+    };
+    let tag = TRANSACTION_TAG.load(Ordering::Relaxed).checked_sub(1);
+    tag_gas_budget(budget, tag)
+}
+
+/// Gas budgets of the transactions issued by each workload, as
+/// distributions sampled per transaction, None keeps the default budget of
+/// each transaction kind
+#[derive(Clone, Debug, Default)]
+pub struct WorkloadGasBudgets {
+    /// Budget of every workload without an override
+    pub default: Option<AmountDistribution>,
+    pub overrides: Vec<(WorkloadType, AmountDistribution)>,
+}
+
+impl WorkloadGasBudgets {
+    /// The gas budget of the transactions of `workload`
+    pub fn of(&self, workload: WorkloadType) -> Option<AmountDistribution> {
+        self.overrides
+            .iter()
+            .find(|(w, _)| *w == workload)
+            .map(|(_, budget)| *budget)
+            .or(self.default)
+    }
+}
+
+/// Settings of the built-in workloads, everything but their weights
+#[derive(Clone, Debug)]
+pub struct WorkloadParams {
+    pub num_transfer_accounts: u64,
+    pub transfer_amount_dist: Option<AmountDistribution>,
+    pub transfer_ring_check: bool,
+    pub delegation_target: DelegationTarget,
+    pub collection_ops_per_tx: u64,
+    pub dependency_chain_length: u64,
+    pub shared_lifecycle_create_percent: u32,
+    pub shared_lifecycle_mutations: u64,
+    pub send_to_object_destinations: u64,
+    pub create_fanout: u64,
+    pub nft_metadata_lengths: NftMetadataLengths,
+    pub commands_per_tx: u64,
+    pub split_count: u64,
+    pub randomize_inputs: bool,
+    pub shared_counter_hotness_factor: u32,
+    // Fixed number of shared counters, incremented round robin when set
+    pub contention_objects: Option<u64>,
+    pub shared_counter_init_timeout: Option<Duration>,
+    pub gas_budgets: WorkloadGasBudgets,
+}
+
+impl WorkloadParams {
+    /// Init and payload coin configs of the built-in `workload` with
+    /// `max_ops` transactions in flight. Transfer tokens take the place of
+    /// the init coins of the transfer object workload
+    pub fn gas_configs(
+        &self,
+        workload: WorkloadType,
+        max_ops: u64,
+    ) -> Result<(Vec<GasCoinConfig>, Vec<GasCoinConfig>)> {
+        Ok(match workload {
+            WorkloadType::SharedCounter => {
+                let num_counters = num_shared_counters(
+                    self.contention_objects,
+                    self.shared_counter_hotness_factor,
+                    max_ops,
+                )?;
+                (
+                    SharedCounterWorkload::generate_coin_config_for_init(num_counters),
+                    SharedCounterWorkload::generate_coin_config_for_payloads(max_ops),
+                )
+            }
+            WorkloadType::TransferObject => {
+                TransferObjectWorkload::generate_coin_config_for_payloads(
+                    max_ops,
+                    self.num_transfer_accounts,
+                    max_ops,
+                )
+            }
+            WorkloadType::Delegation => (
+                vec![],
+                DelegationWorkload::generate_gas_config_for_payloads(max_ops),
+            ),
+            WorkloadType::Read => (
+                vec![],
+                ReadWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::Collection => (
+                CollectionWorkload::generate_coin_config_for_init(),
+                CollectionWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::WrapUnwrap => (
+                WrapUnwrapWorkload::generate_coin_config_for_init(),
+                WrapUnwrapWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::DependencyChain => (
+                vec![],
+                DependencyChainWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::SharedLifecycle => (
+                SharedLifecycleWorkload::generate_coin_config_for_init(),
+                SharedLifecycleWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::Noop => (
+                NoopWorkload::generate_coin_config_for_init(),
+                NoopWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::SendToObject => (
+                SendToObjectWorkload::generate_coin_config_for_init(
+                    self.send_to_object_destinations,
+                ),
+                SendToObjectWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::ObjectFanout => (
+                ObjectFanoutWorkload::generate_coin_config_for_init(),
+                ObjectFanoutWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::NftMint => (
+                NftMintWorkload::generate_coin_config_for_init(),
+                NftMintWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::Batch => (
+                BatchWorkload::generate_coin_config_for_init(),
+                BatchWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::CoinChurn => (
+                vec![],
+                CoinChurnWorkload::generate_coin_config_for_payloads(max_ops),
+            ),
+            WorkloadType::Combination | WorkloadType::Custom(_) => {
+                bail!("{} is not a built-in workload", workload)
+            }
+        })
+    }
+
+    /// The built-in `workload` configured with these settings
+    pub fn make_workload(&self, workload: WorkloadType) -> Result<Box<dyn Workload<dyn Payload>>> {
+        let gas_budget = self.gas_budgets.of(workload);
+        Ok(match workload {
+            WorkloadType::SharedCounter => SharedCounterWorkload::new_boxed(
+                None,
+                vec![],
+                self.shared_counter_init_timeout,
+                gas_budget,
+                self.randomize_inputs,
+                self.contention_objects.is_some(),
+            ),
+            WorkloadType::TransferObject => TransferObjectWorkload::new_boxed(
+                self.num_transfer_accounts,
+                self.transfer_amount_dist,
+                gas_budget,
+                self.transfer_ring_check,
+            ),
+            WorkloadType::Delegation => {
+                DelegationWorkload::new_boxed(gas_budget, self.delegation_target.clone())
+            }
+            WorkloadType::Read => ReadWorkload::new_boxed(self.randomize_inputs),
+            WorkloadType::Collection => {
+                CollectionWorkload::new_boxed(self.collection_ops_per_tx, gas_budget)
+            }
+            WorkloadType::WrapUnwrap => WrapUnwrapWorkload::new_boxed(gas_budget),
+            WorkloadType::DependencyChain => {
+                DependencyChainWorkload::new_boxed(self.dependency_chain_length, gas_budget)
+            }
+            WorkloadType::SharedLifecycle => SharedLifecycleWorkload::new_boxed(
+                self.shared_lifecycle_create_percent,
+                self.shared_lifecycle_mutations,
+                gas_budget,
+            ),
+            WorkloadType::Noop => NoopWorkload::new_boxed(gas_budget),
+            WorkloadType::SendToObject => SendToObjectWorkload::new_boxed(gas_budget),
+            WorkloadType::ObjectFanout => {
+                ObjectFanoutWorkload::new_boxed(self.create_fanout, gas_budget)
+            }
+            WorkloadType::NftMint => {
+                NftMintWorkload::new_boxed(self.nft_metadata_lengths, gas_budget)
+            }
+            WorkloadType::Batch => BatchWorkload::new_boxed(self.commands_per_tx, gas_budget),
+            WorkloadType::CoinChurn => CoinChurnWorkload::new_boxed(self.split_count, gas_budget),
+            WorkloadType::Combination | WorkloadType::Custom(_) => {
+                bail!("{} is not a built-in workload", workload)
+            }
         })
     }
 }
 
-pub fn make_delegation_workload(
+/// Mix of `workloads`, each picked by its weight, sharing the workers and
+/// the in-flight transactions
+pub fn make_combination_workload(
     target_qps: u64,
     num_workers: u64,
     max_in_flight_ops: u64,
+    workloads: Vec<WeightAndPayload>,
     payload_config: WorkloadPayloadGas,
-) -> Option<WorkloadInfo> {
-    if target_qps == 0 || max_in_flight_ops == 0 || num_workers == 0 {
-        None
-    } else {
-        Some(WorkloadInfo {
-            target_qps,
-            num_workers,
-            max_in_flight_ops,
-            workload: DelegationWorkload::new_boxed(),
-            payload_config,
-        })
+) -> WorkloadInfo {
+    let mut combination = HashMap::<WorkloadType, WeightAndPayload>::new();
+    for (weight, workload) in workloads {
+        combination
+            .entry(workload.get_workload_type())
+            .or_insert((weight, workload));
+    }
+    WorkloadInfo {
+        target_qps,
+        num_workers,
+        max_in_flight_ops,
+        workload: CombinationWorkload::new_boxed(combination),
+        payload_config,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use sui_types::base_types::random_object_ref;
+    use test_utils::messages::MAX_GAS;
+
+    #[test]
+    fn test_tag_gas_budget() {
+        assert_eq!(tag_gas_budget(2_000_000, Some(42)), 2_000_042);
+        assert_eq!(tag_gas_budget(1_234_567, Some(42)), 1_234_042);
+        assert_eq!(tag_gas_budget(1_234_567, None), 1_234_567);
+        // Budgets too small to carry a tag are left alone
+        assert_eq!(tag_gas_budget(500, Some(42)), 500);
+    }
+
+    #[test]
+    fn test_payload_coin_configs_share_keypair() {
+        // 100k qps with the default in-flight ratio of 5
+        let max_ops = 500_000;
+        let configs = SharedCounterWorkload::generate_coin_config_for_payloads(max_ops);
+        assert_eq!(configs.len() as u64, max_ops);
+        // A keypair per coin would add an allocation per in-flight slot
+        assert_eq!(Arc::strong_count(&configs[0].keypair), max_ops as usize);
+        assert!(configs.iter().all(|c| c.address == configs[0].address));
+        assert!(std::mem::size_of::<GasCoinConfig>() <= 64);
+    }
+
+    #[test]
+    fn test_gas_config_keypairs_do_not_grow_with_max_ops() {
+        // 20k qps with the default in-flight ratio of 5
+        let max_ops = 100_000;
+        let params = WorkloadParams {
+            num_transfer_accounts: 2,
+            transfer_amount_dist: None,
+            transfer_ring_check: false,
+            delegation_target: DelegationTarget::Random,
+            collection_ops_per_tx: 1,
+            dependency_chain_length: 1,
+            shared_lifecycle_create_percent: 0,
+            shared_lifecycle_mutations: 0,
+            send_to_object_destinations: 4,
+            create_fanout: 1,
+            nft_metadata_lengths: NftMetadataLengths::default(),
+            commands_per_tx: 1,
+            split_count: 2,
+            randomize_inputs: false,
+            shared_counter_hotness_factor: 0,
+            contention_objects: Some(10),
+            shared_counter_init_timeout: None,
+            gas_budgets: WorkloadGasBudgets::default(),
+        };
+        // Read coins get an owner each, so that owned-object queries stay small
+        for workload in WorkloadType::iter()
+            .filter(|w| !matches!(w, WorkloadType::Combination | WorkloadType::Read))
+        {
+            let (init, payload) = params.gas_configs(workload, max_ops).unwrap();
+            assert!(payload.len() as u64 <= max_ops * params.num_transfer_accounts);
+            // The coins share the keypairs of the init owner, the payload
+            // owner and the transfer accounts
+            let keypairs: HashSet<*const AccountKeyPair> = init
+                .iter()
+                .chain(&payload)
+                .map(|c| Arc::as_ptr(&c.keypair))
+                .collect();
+            assert!(
+                keypairs.len() as u64 <= params.num_transfer_accounts + 2,
+                "{} generates {} keypairs",
+                workload,
+                keypairs.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_gas_requirements() {
+        let mut custom_workload_payload_gas_configs = HashMap::new();
+        custom_workload_payload_gas_configs
+            .insert("custom", generate_coin_configs_for_single_owner(3, 10));
+        let config = WorkloadGasConfig {
+            shared_counter_workload_init_gas_config: generate_coin_configs_for_single_owner(1, 100),
+            shared_counter_workload_payload_gas_config: generate_coin_configs_for_single_owner(
+                2, 10,
+            ),
+            custom_workload_payload_gas_configs,
+            ..Default::default()
+        };
+        // Workloads without coins are left out
+        assert_eq!(
+            config.requirements(),
+            vec![
+                GasRequirement {
+                    workload: "shared_counter".to_string(),
+                    init_coins: 1,
+                    payload_coins: 2,
+                    mist: 120,
+                },
+                GasRequirement {
+                    workload: "custom".to_string(),
+                    init_coins: 0,
+                    payload_coins: 3,
+                    mist: 30,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workload_gas_is_keyed_by_workload_type() {
+        let mut config = WorkloadGasConfig::default();
+        config.insert(
+            WorkloadType::TransferObject,
+            generate_coin_configs_for_single_owner(2, 10),
+            generate_coin_configs_for_single_owner(3, 10),
+        );
+        config.insert(
+            WorkloadType::CoinChurn,
+            vec![],
+            generate_coin_configs_for_single_owner(4, 10),
+        );
+        assert_eq!(config.transfer_object_workload_tokens.len(), 2);
+        let (init, payload) = config.configs(WorkloadType::CoinChurn);
+        assert_eq!((init.len(), payload.len()), (0, 4));
+
+        let (address, keypair) = get_key_pair();
+        let coin: Gas = (
+            random_object_ref(),
+            Owner::AddressOwner(address),
+            Arc::new(keypair),
+        );
+        let mut payload_gas = WorkloadPayloadGas {
+            transfer_tokens: vec![coin.clone(); 2],
+            transfer_object_payload_gas: vec![coin.clone(); 3],
+            noop_payload_gas: vec![coin.clone()],
+            ..Default::default()
+        };
+        let transfer = payload_gas.take(WorkloadType::TransferObject);
+        assert_eq!(transfer.all_gas().len(), 5);
+        assert_eq!(transfer.noop_payload_gas.len(), 0);
+        // Taken coins are gone, the other workloads keep theirs
+        assert_eq!(payload_gas.all_gas().len(), 1);
+        let mut init_gas = WorkloadInitGas {
+            noop_init_gas: vec![coin],
+            ..Default::default()
+        };
+        assert!(init_gas.take(WorkloadType::Read).noop_init_gas.is_empty());
+        assert_eq!(init_gas.take(WorkloadType::Noop).noop_init_gas.len(), 1);
+    }
+
+    #[test]
+    fn test_split_coin_configs_across_senders() {
+        let mut configs = generate_coin_configs_for_single_owner(10, MAX_GAS_FOR_TESTING);
+        split_coin_configs_across_senders(&mut configs, 4);
+        let senders: Vec<SuiAddress> = configs.iter().map(|c| c.address).collect();
+        assert_eq!(senders[0..4], [senders[0]; 4]);
+        assert_eq!(senders[4..8], [senders[4]; 4]);
+        assert_eq!(senders[8..10], [senders[8]; 2]);
+        assert!(senders[0] != senders[4] && senders[4] != senders[8]);
+        assert_eq!(Arc::strong_count(&configs[8].keypair), 2);
+    }
+
+    #[test]
+    fn test_sample_gas_budget() {
+        assert_eq!(sample_gas_budget(None, MAX_GAS, 1), MAX_GAS);
+        let budget = Some(AmountDistribution::Const(5_000));
+        assert_eq!(sample_gas_budget(budget, MAX_GAS, 1), 5_000);
+        // Capped at what a payload gas coin can pay
+        let gas_price = MAX_GAS_FOR_TESTING / 1_000;
+        assert_eq!(sample_gas_budget(budget, MAX_GAS, gas_price), 1_000);
+    }
+
+    #[test]
+    fn test_assign_coin_configs_to_senders() {
+        let mut configs = generate_coin_configs_for_single_owner(5, MAX_GAS_FOR_TESTING);
+        let sender_pool: Vec<SenderAccount> = (0..2)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                (address, Arc::new(keypair))
+            })
+            .collect();
+        assign_coin_configs_to_senders(&mut configs, &sender_pool);
+        let senders: Vec<SuiAddress> = configs.iter().map(|c| c.address).collect();
+        let (first, second) = (sender_pool[0].0, sender_pool[1].0);
+        assert_eq!(senders, vec![first, second, first, second, first]);
+        assert_eq!(Arc::strong_count(&sender_pool[0].1), 4);
     }
 }
@@ -0,0 +1,200 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_nft_mint_tx, AmountDistribution};
+use crate::workloads::package::create_publish_package_transaction;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{path::PathBuf, sync::Arc};
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+use test_utils::transaction::parse_package_ref;
+use tracing::info;
+
+// Storage cost of a byte of object data
+const GAS_PER_METADATA_BYTE: u64 = 100;
+
+/// Length in bytes of each metadata field of the minted NFTs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NftMetadataLengths {
+    pub name: u64,
+    pub url: u64,
+    pub description: u64,
+}
+
+impl NftMetadataLengths {
+    pub fn total(&self) -> u64 {
+        self.name + self.url + self.description
+    }
+}
+
+/// Default budget of a transaction minting an NFT with `lengths` of metadata
+pub fn nft_mint_gas_budget(lengths: NftMetadataLengths) -> u64 {
+    MAX_GAS + lengths.total() * GAS_PER_METADATA_BYTE
+}
+
+#[derive(Debug)]
+pub struct NftMintTestPayload {
+    package_id: ObjectID,
+    lengths: NftMetadataLengths,
+    gas: Gas,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for NftMintTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        _: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        Box::new(NftMintTestPayload {
+            package_id: self.package_id,
+            lengths: self.lengths,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        make_nft_mint_tx(
+            self.gas.0,
+            self.package_id,
+            self.lengths,
+            self.gas
+                .1
+                .get_owner_address()
+                .expect("Cannot convert owner to address"),
+            &self.gas.2,
+            sample_gas_budget(
+                self.gas_budget,
+                nft_mint_gas_budget(self.lengths),
+                gas_price,
+            ),
+            Some(gas_price),
+        )
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0 .0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::NftMint
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &NftMintTestPayload)
+    }
+}
+
+/// Mints NFTs through a published package, one new object owned by the
+/// sender per transaction. The metadata lengths set the size of the NFTs
+#[derive(Debug)]
+pub struct NftMintWorkload {
+    pub package_id: Option<ObjectID>,
+    pub lengths: NftMetadataLengths,
+    // Gas budget of mints, defaults to nft_mint_gas_budget
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl NftMintWorkload {
+    pub fn new_boxed(
+        lengths: NftMetadataLengths,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(NftMintWorkload {
+            package_id: None,
+            lengths,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init() -> Vec<GasCoinConfig> {
+        // Gas coin for publishing package
+        generate_coin_configs_for_single_owner(1, MAX_GAS_FOR_TESTING)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for NftMintWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if self.package_id.is_some() {
+            return Ok(());
+        }
+        let (gas, owner, keypair) = init_config
+            .nft_mint_init_gas
+            .first()
+            .ok_or_else(|| anyhow!("Not enough gas to initialize nft mint workload"))?;
+        info!("Publishing nft mint package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("data/nft_mint");
+        let transaction = create_publish_package_transaction(
+            *gas,
+            path,
+            owner.get_owner_address()?,
+            keypair,
+            *system_state_observer.reference_gas_price.borrow(),
+        )?;
+        let (_, effects) = proxy.execute_transaction(transaction.into()).await?;
+        self.package_id = Some(
+            parse_package_ref(&effects.created())
+                .ok_or_else(|| anyhow!("Nft mint package was not published"))?
+                .0,
+        );
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let package_id = self
+            .package_id
+            .expect("Nft mint workload must be initialized before making payloads");
+        payload_config
+            .nft_mint_payload_gas
+            .into_iter()
+            .map(|gas| {
+                Box::new(NftMintTestPayload {
+                    package_id,
+                    lengths: self.lengths,
+                    gas,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::NftMint
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &NftMintWorkload)
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_object_fanout_tx, AmountDistribution};
+use crate::workloads::package::create_publish_package_transaction;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{path::PathBuf, sync::Arc};
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+use test_utils::transaction::parse_package_ref;
+use tracing::info;
+
+// Gas budgeted per created object on top of the call itself
+const GAS_PER_CREATED_OBJECT: u64 = MAX_GAS;
+
+/// Default budget of a transaction creating `fanout` objects
+pub fn fanout_gas_budget(fanout: u64) -> u64 {
+    MAX_GAS + fanout * GAS_PER_CREATED_OBJECT
+}
+
+#[derive(Debug)]
+pub struct ObjectFanoutTestPayload {
+    package_id: ObjectID,
+    fanout: u64,
+    gas: Gas,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for ObjectFanoutTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        _: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        Box::new(ObjectFanoutTestPayload {
+            package_id: self.package_id,
+            fanout: self.fanout,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        make_object_fanout_tx(
+            self.gas.0,
+            self.package_id,
+            self.fanout,
+            self.gas
+                .1
+                .get_owner_address()
+                .expect("Cannot convert owner to address"),
+            &self.gas.2,
+            sample_gas_budget(self.gas_budget, fanout_gas_budget(self.fanout), gas_price),
+            Some(gas_price),
+        )
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0 .0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::ObjectFanout
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &ObjectFanoutTestPayload)
+    }
+}
+
+/// Calls a Move entry function creating `fanout` new objects owned by the
+/// sender, with the gas coin as the only input object. Nothing is read or
+/// transferred, so the workload stresses object creation and storage
+#[derive(Debug)]
+pub struct ObjectFanoutWorkload {
+    pub package_id: Option<ObjectID>,
+    // Objects created by each transaction
+    pub fanout: u64,
+    // Gas budget of fanout calls, defaults to fanout_gas_budget
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl ObjectFanoutWorkload {
+    pub fn new_boxed(
+        fanout: u64,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(ObjectFanoutWorkload {
+            package_id: None,
+            fanout,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init() -> Vec<GasCoinConfig> {
+        // Gas coin for publishing package
+        generate_coin_configs_for_single_owner(1, MAX_GAS_FOR_TESTING)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for ObjectFanoutWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if self.package_id.is_some() {
+            return Ok(());
+        }
+        let (gas, owner, keypair) = init_config
+            .object_fanout_init_gas
+            .first()
+            .ok_or_else(|| anyhow!("Not enough gas to initialize object fanout workload"))?;
+        info!("Publishing object fanout package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("data/object_fanout");
+        let transaction = create_publish_package_transaction(
+            *gas,
+            path,
+            owner.get_owner_address()?,
+            keypair,
+            *system_state_observer.reference_gas_price.borrow(),
+        )?;
+        let (_, effects) = proxy.execute_transaction(transaction.into()).await?;
+        self.package_id = Some(
+            parse_package_ref(&effects.created())
+                .ok_or_else(|| anyhow!("Object fanout package was not published"))?
+                .0,
+        );
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let package_id = self
+            .package_id
+            .expect("Object fanout workload must be initialized before making payloads");
+        payload_config
+            .object_fanout_payload_gas
+            .into_iter()
+            .map(|gas| {
+                Box::new(ObjectFanoutTestPayload {
+                    package_id,
+                    fanout: self.fanout,
+                    gas,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::ObjectFanout
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &ObjectFanoutWorkload)
+    }
+}
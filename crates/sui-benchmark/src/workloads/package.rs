@@ -0,0 +1,95 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Context, Result};
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
+use sui_types::base_types::{ObjectRef, SuiAddress};
+use sui_types::crypto::AccountKeyPair;
+use sui_types::messages::{TransactionData, VerifiedTransaction};
+use sui_types::utils::to_sender_signed_transaction;
+use test_utils::messages::{create_publish_move_package_transaction, MAX_GAS};
+use tracing::info;
+
+// Directory given with --package-bytecode
+static PACKAGE_BYTECODE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Publishes the workload packages from prebuilt bytecode in `dir` instead
+/// of compiling their Move source. `<dir>/<package>.json` holds the output
+/// of `sui move build --dump-bytecode-as-base64` for the package, packages
+/// without a file there are still compiled
+pub fn set_package_bytecode_dir(dir: PathBuf) -> Result<()> {
+    if !dir.is_dir() {
+        bail!("Package bytecode directory {} not found", dir.display());
+    }
+    PACKAGE_BYTECODE_DIR
+        .set(dir)
+        .map_err(|_| anyhow!("Package bytecode directory is already set"))
+}
+
+/// Modules of a package, as the JSON array of Base64 encoded modules
+/// printed by `sui move build --dump-bytecode-as-base64`
+pub fn read_package_bytecode(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read package bytecode {}", path.display()))?;
+    let modules: Vec<String> = serde_json::from_str(&json)
+        .with_context(|| format!("Malformed package bytecode {}", path.display()))?;
+    if modules.is_empty() {
+        bail!("Package bytecode {} has no modules", path.display());
+    }
+    modules
+        .iter()
+        .map(|module| base64::decode(module).map_err(|e| anyhow!("{}: {}", path.display(), e)))
+        .collect()
+}
+
+/// Transaction publishing the Move package at `source`, from its prebuilt
+/// bytecode when --package-bytecode has it, named after the last component
+/// of `source`, and compiled otherwise
+pub fn create_publish_package_transaction(
+    gas: ObjectRef,
+    source: PathBuf,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_price: u64,
+) -> Result<VerifiedTransaction> {
+    let prebuilt = PACKAGE_BYTECODE_DIR.get().and_then(|dir| {
+        let name = source.file_name()?.to_str()?;
+        Some(dir.join(format!("{}.json", name))).filter(|path| path.is_file())
+    });
+    match prebuilt {
+        Some(path) => {
+            info!("Publishing prebuilt package {}", path.display());
+            let modules = read_package_bytecode(&path)?;
+            let data = TransactionData::new_module(sender, gas, modules, MAX_GAS, gas_price);
+            Ok(to_sender_signed_transaction(data, keypair))
+        }
+        None => Ok(create_publish_move_package_transaction(
+            gas,
+            source,
+            sender,
+            keypair,
+            Some(gas_price),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_package_bytecode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("noop.json");
+        let modules = vec![vec![0xa1, 0x1c, 0xeb, 0x0b], vec![1, 2, 3]];
+        let encoded: Vec<String> = modules.iter().map(base64::encode).collect();
+        std::fs::write(&path, serde_json::to_string(&encoded).unwrap()).unwrap();
+        assert_eq!(read_package_bytecode(&path).unwrap(), modules);
+
+        std::fs::write(&path, "[]").unwrap();
+        assert!(read_package_bytecode(&path).is_err());
+        std::fs::write(&path, "[\"not base64!\"]").unwrap();
+        assert!(read_package_bytecode(&path).is_err());
+    }
+}
@@ -1,12 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use sui_types::base_types::{ObjectID, ObjectRef};
+use move_core_types::language_storage::StructTag;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 
 use sui_types::messages::VerifiedTransaction;
+use sui_types::object::Owner;
 
-use crate::ExecutionEffects;
-use rand::{prelude::*, rngs::OsRng};
+use crate::rng::BenchRng;
+use crate::{ExecutionEffects, ObjectQuery, ValidatorProxy};
+use anyhow::bail;
+use rand::prelude::*;
 use rand_distr::WeightedAliasIndex;
 
 use crate::workloads::workload::WorkloadType;
@@ -19,12 +23,59 @@ pub trait Payload: Send + Sync {
         effects: &ExecutionEffects,
     ) -> Box<dyn Payload>;
     fn make_transaction(&self) -> VerifiedTransaction;
+    /// Read-only payloads return the query to issue instead of a transaction
+    fn make_query(&self) -> Option<ObjectQuery> {
+        None
+    }
+    /// Returns the payload to issue next once a read-only query completed.
+    /// Only called on payloads returning a query from `make_query`
+    fn make_new_query_payload(self: Box<Self>) -> Box<dyn Payload> {
+        unreachable!("Transaction payloads do not issue queries")
+    }
     fn get_object_id(&self) -> ObjectID;
     fn get_workload_type(&self) -> WorkloadType;
+    /// What a successful transaction of the payload must have achieved
+    /// beyond its effects, checked with --check-postconditions
+    fn postcondition(&self) -> Option<PostCondition> {
+        None
+    }
 
     fn debug(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result;
 }
 
+/// Outcome a transaction is expected to have, e.g. the stake of a delegation
+/// being recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostCondition {
+    // The transaction created an object of this type owned by the address
+    CreatedObject { owner: SuiAddress, type_: StructTag },
+}
+
+impl PostCondition {
+    /// Checks the condition against the effects of the transaction, reading
+    /// the objects it created through `proxy`
+    pub async fn check(
+        &self,
+        effects: &ExecutionEffects,
+        proxy: &(dyn ValidatorProxy + Send + Sync),
+    ) -> anyhow::Result<()> {
+        match self {
+            PostCondition::CreatedObject { owner, type_ } => {
+                for (object_ref, object_owner) in effects.created() {
+                    if object_owner != Owner::AddressOwner(*owner) {
+                        continue;
+                    }
+                    let object = proxy.get_object(object_ref.0).await?;
+                    if object.type_() == Some(type_) {
+                        return Ok(());
+                    }
+                }
+                bail!("No {} owned by {} was created", type_, owner)
+            }
+        }
+    }
+}
+
 impl std::fmt::Debug for dyn Payload {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         self.debug(f)
@@ -36,7 +87,7 @@ pub struct CombinationPayload {
     pub payloads: Vec<Box<dyn Payload>>,
     pub dist: WeightedAliasIndex<u32>,
     pub curr_index: usize,
-    pub rng: OsRng,
+    pub rng: BenchRng,
 }
 
 impl Payload for CombinationPayload {
@@ -68,6 +119,28 @@ impl Payload for CombinationPayload {
         let curr = self.payloads.get(self.curr_index).unwrap();
         curr.make_transaction()
     }
+    fn make_query(&self) -> Option<ObjectQuery> {
+        let curr = self.payloads.get(self.curr_index).unwrap();
+        curr.make_query()
+    }
+    fn make_new_query_payload(self: Box<Self>) -> Box<dyn Payload> {
+        let mut new_payloads = vec![];
+        for (pos, e) in self.payloads.into_iter().enumerate() {
+            if pos == self.curr_index {
+                new_payloads.push(e.make_new_query_payload());
+            } else {
+                new_payloads.push(e);
+            }
+        }
+        let mut rng = self.rng;
+        let next_index = self.dist.sample(&mut rng);
+        Box::new(CombinationPayload {
+            payloads: new_payloads,
+            dist: self.dist,
+            curr_index: next_index,
+            rng: self.rng,
+        })
+    }
     fn get_object_id(&self) -> ObjectID {
         let curr = self.payloads.get(self.curr_index).unwrap();
         curr.get_object_id()
@@ -78,6 +151,10 @@ impl Payload for CombinationPayload {
             .unwrap()
             .get_workload_type()
     }
+    fn postcondition(&self) -> Option<PostCondition> {
+        let curr = self.payloads.get(self.curr_index).unwrap();
+        curr.postcondition()
+    }
 
     fn debug(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{:?}", self as &CombinationPayload)
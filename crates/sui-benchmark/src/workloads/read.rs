@@ -0,0 +1,146 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::rng::{bench_rng, get_key_pair};
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::input_selector::{InputSelector, SharedInputSelector};
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadInitGas, WorkloadPayloadGas};
+use crate::{ExecutionEffects, ObjectQuery, ValidatorProxy};
+use anyhow::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::messages::VerifiedTransaction;
+
+// Read objects are never used to pay for gas so they only need a token amount
+const READ_OBJECT_AMOUNT: u64 = 1;
+
+#[derive(Debug)]
+pub struct ReadTestPayload {
+    object: ObjectRef,
+    owner: SuiAddress,
+    // Moves on to another object of the pool after every query
+    input_selector: Option<SharedInputSelector<(ObjectRef, SuiAddress)>>,
+}
+
+impl Payload for ReadTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        _: ObjectRef,
+        _: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        self
+    }
+    fn make_transaction(&self) -> VerifiedTransaction {
+        unreachable!("Read payloads do not issue transactions")
+    }
+    /// Alternates randomly between reading the object itself and listing
+    /// all objects owned by its owner
+    fn make_query(&self) -> Option<ObjectQuery> {
+        if bench_rng().gen() {
+            Some(ObjectQuery::GetObject(self.object.0))
+        } else {
+            Some(ObjectQuery::GetOwnedObjects(self.owner))
+        }
+    }
+    fn make_new_query_payload(self: Box<Self>) -> Box<dyn Payload> {
+        match self
+            .input_selector
+            .as_ref()
+            .and_then(|selector| selector.lock().unwrap().pick())
+        {
+            Some((object, owner)) => Box::new(ReadTestPayload {
+                object,
+                owner,
+                input_selector: self.input_selector,
+            }),
+            None => self,
+        }
+    }
+    fn get_object_id(&self) -> ObjectID {
+        self.object.0
+    }
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Read
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &ReadTestPayload)
+    }
+}
+
+pub struct ReadWorkload {
+    // Spread queries over all read objects, avoiding recently read ones
+    randomize_inputs: bool,
+}
+
+impl ReadWorkload {
+    pub fn new_boxed(randomize_inputs: bool) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(ReadWorkload { randomize_inputs }))
+    }
+
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        (0..num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: READ_OBJECT_AMOUNT,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for ReadWorkload {
+    async fn init(
+        &mut self,
+        _: WorkloadInitGas,
+        _: Arc<dyn ValidatorProxy + Sync + Send>,
+        _system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        _system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let objects: Vec<(ObjectRef, SuiAddress)> = payload_config
+            .read_payload_objects
+            .into_iter()
+            .map(|(object, owner, _): Gas| (object, owner.get_owner_address().unwrap()))
+            .collect();
+        let input_selector = self
+            .randomize_inputs
+            .then(|| InputSelector::new_shared(objects.clone()));
+        objects
+            .into_iter()
+            .map(|(object, owner)| {
+                Box::new(ReadTestPayload {
+                    object,
+                    owner,
+                    input_selector: input_selector.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::Read
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReadWorkload")
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType};
+use crate::workloads::GasCoinConfig;
+
+/// Builds a workload defined outside this crate. The workload reports
+/// `WorkloadType::Custom(name)` and finds its coins under its name in
+/// `WorkloadInitGas::custom_init_gas` and `WorkloadPayloadGas::custom_payload_gas`
+pub trait WorkloadFactory: Send + Sync {
+    /// Name the workload is reported under
+    fn name(&self) -> &'static str;
+    /// Coins spent by `Workload::init`, e.g. to publish a package
+    fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        vec![]
+    }
+    /// Coins for `max_ops` payloads in flight
+    fn generate_coin_config_for_payloads(&self, max_ops: u64) -> Vec<GasCoinConfig>;
+    fn make_workload(&self) -> Box<dyn Workload<dyn Payload>>;
+    /// Long names of the flags tuning the workload, see `WorkloadType::flags`
+    fn flags(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Workloads run next to the built-in ones of the bench run, weighted
+/// against their weights. Register them before `WorkloadConfiguration::configure`
+#[derive(Default, Clone)]
+pub struct WorkloadRegistry {
+    factories: Vec<(u32, Arc<dyn WorkloadFactory>)>,
+}
+
+impl WorkloadRegistry {
+    pub fn register(&mut self, factory: Arc<dyn WorkloadFactory>, weight: u32) -> Result<()> {
+        let name = factory.name();
+        if weight == 0 {
+            bail!("Registered workload {} needs a nonzero weight", name);
+        }
+        if name.is_empty()
+            || WorkloadType::iter().any(|w| w.to_string() == name)
+            || self.factories.iter().any(|(_, f)| f.name() == name)
+        {
+            bail!("Workload name {:?} is empty or already taken", name);
+        }
+        self.factories.push((weight, factory));
+        Ok(())
+    }
+
+    pub fn factories(&self) -> impl Iterator<Item = &(u32, Arc<dyn WorkloadFactory>)> {
+        self.factories.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn WorkloadFactory>> {
+        self.factories
+            .iter()
+            .map(|(_, factory)| factory)
+            .find(|factory| factory.name() == name)
+    }
+
+    /// Every workload a bench run can weight, the built-in ones then the
+    /// registered ones, with the flags tuning it
+    pub fn workload_flags(&self) -> Vec<(WorkloadType, &'static [&'static str])> {
+        WorkloadType::iter()
+            .filter(|workload| *workload != WorkloadType::Combination)
+            .map(|workload| (workload, workload.flags()))
+            .chain(
+                self.factories
+                    .iter()
+                    .map(|(_, factory)| (WorkloadType::Custom(factory.name()), factory.flags())),
+            )
+            .collect()
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.factories.iter().map(|(weight, _)| weight).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NamedFactory(&'static str);
+
+    impl WorkloadFactory for NamedFactory {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        fn generate_coin_config_for_payloads(&self, _max_ops: u64) -> Vec<GasCoinConfig> {
+            vec![]
+        }
+        fn make_workload(&self) -> Box<dyn Workload<dyn Payload>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_register_rejects_taken_names() {
+        let mut registry = WorkloadRegistry::default();
+        registry
+            .register(Arc::new(NamedFactory("my_workload")), 3)
+            .unwrap();
+        assert!(registry
+            .register(Arc::new(NamedFactory("my_workload")), 1)
+            .is_err());
+        assert!(registry
+            .register(Arc::new(NamedFactory("noop")), 1)
+            .is_err());
+        assert!(registry.register(Arc::new(NamedFactory("")), 1).is_err());
+        assert!(registry
+            .register(Arc::new(NamedFactory("other")), 0)
+            .is_err());
+        assert_eq!(registry.total_weight(), 3);
+    }
+}
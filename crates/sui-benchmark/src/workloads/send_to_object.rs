@@ -0,0 +1,165 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::rng::bench_rng;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::AmountDistribution;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use rand::seq::SliceRandom;
+use std::sync::Arc;
+use sui_core::test_utils::make_transfer_sui_transaction_with_gas_budget;
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+
+// Mist sent to a destination object by each transaction
+const SEND_AMOUNT: u64 = 1;
+// Balance of each destination object, which only serves as an address
+const DESTINATION_AMOUNT: u64 = 1;
+
+#[derive(Debug)]
+pub struct SendToObjectTestPayload {
+    // Object whose address the next coin is sent to
+    destination: ObjectID,
+    destinations: Arc<Vec<ObjectID>>,
+    gas: Gas,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for SendToObjectTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        _: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        Box::new(SendToObjectTestPayload {
+            destination: *self.destinations.choose(&mut bench_rng()).unwrap(),
+            destinations: self.destinations,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        make_transfer_sui_transaction_with_gas_budget(
+            self.gas.0,
+            self.destination.into(),
+            Some(SEND_AMOUNT),
+            self.gas
+                .1
+                .get_owner_address()
+                .expect("Cannot convert owner to address"),
+            &self.gas.2,
+            sample_gas_budget(self.gas_budget, MAX_GAS, gas_price),
+            Some(gas_price),
+        )
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0 .0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::SendToObject
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &SendToObjectTestPayload)
+    }
+}
+
+/// Sends a new coin split off the gas coin to the address of one of a set
+/// of destination objects, instead of to an account. This tree has no way
+/// to receive objects sent to an object, the coins stay owned by the
+/// destination's address
+#[derive(Debug)]
+pub struct SendToObjectWorkload {
+    pub destinations: Vec<ObjectID>,
+    // Gas budget of sends, defaults to MAX_GAS
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl SendToObjectWorkload {
+    pub fn new_boxed(gas_budget: Option<AmountDistribution>) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(SendToObjectWorkload {
+            destinations: vec![],
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init(num_destinations: u64) -> Vec<GasCoinConfig> {
+        // The destination objects
+        generate_coin_configs_for_single_owner(num_destinations.max(1), DESTINATION_AMOUNT)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for SendToObjectWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        _system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if !self.destinations.is_empty() {
+            return Ok(());
+        }
+        if init_config.send_to_object_init_gas.is_empty() {
+            bail!("No destination objects to initialize send_to_object workload");
+        }
+        self.destinations = init_config
+            .send_to_object_init_gas
+            .iter()
+            .map(|(object_ref, _, _)| object_ref.0)
+            .collect();
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let destinations = Arc::new(self.destinations.clone());
+        payload_config
+            .send_to_object_payload_gas
+            .into_iter()
+            .enumerate()
+            .map(|(i, gas)| {
+                Box::new(SendToObjectTestPayload {
+                    // Payloads start spread over the destinations
+                    destination: destinations[i % destinations.len()],
+                    destinations: destinations.clone(),
+                    gas,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::SendToObject
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &SendToObjectWorkload)
+    }
+}
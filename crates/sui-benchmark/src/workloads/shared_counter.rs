@@ -4,34 +4,70 @@
 use super::workload::{Workload, WorkloadType};
 use crate::workloads::Gas;
 
+use crate::rng::bench_rng;
 use crate::system_state_observer::SystemStateObserver;
+use crate::util::AmountDistribution;
+use crate::workloads::input_selector::{InputSelector, SharedInputSelector};
+use crate::workloads::package::create_publish_package_transaction;
 use crate::workloads::payload::Payload;
 use crate::workloads::workload::MAX_GAS_FOR_TESTING;
-use crate::workloads::{GasCoinConfig, WorkloadInitGas, WorkloadPayloadGas};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
 use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
-use futures::future::join_all;
+use futures::{stream::FuturesUnordered, StreamExt};
 use rand::seq::SliceRandom;
+use std::time::Duration;
 use std::{path::PathBuf, sync::Arc};
-use sui_types::crypto::get_key_pair;
 use sui_types::{
     base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress},
     crypto::AccountKeyPair,
     messages::VerifiedTransaction,
 };
-use test_utils::messages::{make_counter_create_transaction, make_counter_increment_transaction};
-use test_utils::{
-    messages::create_publish_move_package_transaction, transaction::parse_package_ref,
+use test_utils::messages::{
+    make_counter_create_transaction, make_counter_increment_transaction_with_gas_budget, MAX_GAS,
 };
+use test_utils::transaction::parse_package_ref;
+use tokio::time::timeout;
 use tracing::info;
 
+/// Number of shared counters to create for `max_ops` payloads. Set directly
+/// by --contention-objects, payloads then write to them round-robin, or
+/// derived from the hotness factor otherwise
+pub fn num_shared_counters(
+    contention_objects: Option<u64>,
+    hotness_factor: u32,
+    max_ops: u64,
+) -> Result<u64> {
+    match contention_objects {
+        Some(0) => bail!("--contention-objects must create at least one shared counter"),
+        Some(num_counters) if num_counters > max_ops => bail!(
+            "--contention-objects {} exceeds the {} shared counter payloads, \
+            some counters would never be written",
+            num_counters,
+            max_ops
+        ),
+        Some(num_counters) => Ok(num_counters),
+        None => {
+            let ratio = 1.0 - (std::cmp::min(hotness_factor, 100) as f32 / 100.0);
+            Ok((max_ops as f32 * ratio) as u64)
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SharedCounterTestPayload {
     package_id: ObjectID,
     counter_id: ObjectID,
     counter_initial_shared_version: SequenceNumber,
     gas: Gas,
+    gas_budget: Option<AmountDistribution>,
     system_state_observer: Arc<SystemStateObserver>,
+    // Moves on to another counter of the pool after every increment
+    input_selector: Option<SharedInputSelector<(ObjectID, SequenceNumber, ObjectDigest)>>,
 }
 
 impl Payload for SharedCounterTestPayload {
@@ -41,16 +77,24 @@ impl Payload for SharedCounterTestPayload {
         new_gas: ObjectRef,
         _: &ExecutionEffects,
     ) -> Box<dyn Payload> {
+        let (counter_id, counter_initial_shared_version) = self
+            .input_selector
+            .as_ref()
+            .and_then(|selector| selector.lock().unwrap().pick())
+            .map(|counter| (counter.0, counter.1))
+            .unwrap_or((self.counter_id, self.counter_initial_shared_version));
         Box::new(SharedCounterTestPayload {
             package_id: self.package_id,
-            counter_id: self.counter_id,
-            counter_initial_shared_version: self.counter_initial_shared_version,
+            counter_id,
+            counter_initial_shared_version,
             gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
             system_state_observer: self.system_state_observer,
+            input_selector: self.input_selector,
         })
     }
     fn make_transaction(&self) -> VerifiedTransaction {
-        make_counter_increment_transaction(
+        make_counter_increment_transaction_with_gas_budget(
             self.gas.0,
             self.package_id,
             self.counter_id,
@@ -60,6 +104,11 @@ impl Payload for SharedCounterTestPayload {
                 .get_owner_address()
                 .expect("Cannot convert owner to address"),
             &self.gas.2,
+            sample_gas_budget(
+                self.gas_budget,
+                MAX_GAS,
+                *self.system_state_observer.reference_gas_price.borrow(),
+            ),
             Some(*self.system_state_observer.reference_gas_price.borrow()),
         )
     }
@@ -79,52 +128,42 @@ impl Payload for SharedCounterTestPayload {
 pub struct SharedCounterWorkload {
     pub basics_package_id: Option<ObjectID>,
     pub counters: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    // Abort init if creating the counters takes longer than this
+    pub init_timeout: Option<Duration>,
+    // Gas budget of counter increments, defaults to MAX_GAS
+    pub gas_budget: Option<AmountDistribution>,
+    // Spread increments over all counters, avoiding recently incremented ones
+    pub randomize_inputs: bool,
+    // Assign payloads to the counters in turn, each payload keeping its
+    // counter, so every counter has the same number of writers
+    pub round_robin: bool,
 }
 
 impl SharedCounterWorkload {
     pub fn new_boxed(
         basics_package_id: Option<ObjectID>,
         counters: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+        init_timeout: Option<Duration>,
+        gas_budget: Option<AmountDistribution>,
+        randomize_inputs: bool,
+        round_robin: bool,
     ) -> Box<dyn Workload<dyn Payload>> {
         Box::<dyn Workload<dyn Payload>>::from(Box::new(SharedCounterWorkload {
             basics_package_id,
             counters,
+            init_timeout,
+            gas_budget,
+            randomize_inputs,
+            round_robin,
         }))
     }
     pub fn generate_coin_config_for_init(num_counters: u64) -> Vec<GasCoinConfig> {
-        let mut configs = vec![];
-
-        // Gas coin for publishing package
-        let (address, keypair) = get_key_pair();
-        configs.push(GasCoinConfig {
-            amount: MAX_GAS_FOR_TESTING,
-            address,
-            keypair: Arc::new(keypair),
-        });
-
-        // Gas coins for creating counters
-        for _i in 0..num_counters {
-            let (address, keypair) = get_key_pair();
-            configs.push(GasCoinConfig {
-                amount: MAX_GAS_FOR_TESTING,
-                address,
-                keypair: Arc::new(keypair),
-            });
-        }
-        configs
+        // Gas coin for publishing package and gas coins for creating counters
+        generate_coin_configs_for_single_owner(num_counters + 1, MAX_GAS_FOR_TESTING)
     }
     pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
-        let mut configs = vec![];
         // Gas coins for running workload
-        for _i in 0..num_payloads {
-            let (address, keypair) = get_key_pair();
-            configs.push(GasCoinConfig {
-                amount: MAX_GAS_FOR_TESTING,
-                address,
-                keypair: Arc::new(keypair),
-            });
-        }
-        configs
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
     }
 }
 
@@ -138,7 +177,7 @@ pub async fn publish_basics_package(
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("../../sui_programmability/examples/basics");
     let transaction =
-        create_publish_move_package_transaction(gas, path, sender, keypair, Some(gas_price));
+        create_publish_package_transaction(gas, path, sender, keypair, gas_price).unwrap();
     let (_, effects) = proxy.execute_transaction(transaction.into()).await.unwrap();
     parse_package_ref(&effects.created()).unwrap()
 }
@@ -150,15 +189,15 @@ impl Workload<dyn Payload> for SharedCounterWorkload {
         init_config: WorkloadInitGas,
         proxy: Arc<dyn ValidatorProxy + Sync + Send>,
         system_state_observer: Arc<SystemStateObserver>,
-    ) {
+    ) -> Result<()> {
         if self.basics_package_id.is_some() {
-            return;
+            return Ok(());
         }
         let gas_price = *system_state_observer.reference_gas_price.borrow();
         let (head, tail) = init_config
             .shared_counter_init_gas
             .split_first()
-            .expect("Not enough gas to initialize shared counter workload");
+            .ok_or_else(|| anyhow!("Not enough gas to initialize shared counter workload"))?;
 
         // Publish basics package
         info!("Publishing basics package");
@@ -177,9 +216,9 @@ impl Workload<dyn Payload> for SharedCounterWorkload {
         );
         if !self.counters.is_empty() {
             // We already initialized the workload with some counters
-            return;
+            return Ok(());
         }
-        let mut futures = vec![];
+        let mut futures = FuturesUnordered::new();
         for (gas, sender, keypair) in tail.iter() {
             let transaction = make_counter_create_transaction(
                 *gas,
@@ -192,14 +231,37 @@ impl Workload<dyn Payload> for SharedCounterWorkload {
             );
             let proxy_ref = proxy.clone();
             futures.push(async move {
-                if let Ok((_, effects)) = proxy_ref.execute_transaction(transaction.into()).await {
-                    effects.created()[0].0
-                } else {
-                    panic!("Failed to create shared counter!");
-                }
+                proxy_ref
+                    .execute_transaction(transaction.into())
+                    .await
+                    .map(|(_, effects)| effects.created()[0].0)
             });
         }
-        self.counters = join_all(futures).await;
+        let num_counters = futures.len();
+        let mut counters = Vec::with_capacity(num_counters);
+        let create_counters = async {
+            while let Some(res) = futures.next().await {
+                counters.push(res.context("Failed to create shared counter")?);
+            }
+            Ok::<(), anyhow::Error>(())
+        };
+        match self.init_timeout {
+            Some(init_timeout) => {
+                let res = timeout(init_timeout, create_counters).await;
+                match res {
+                    Ok(res) => res?,
+                    Err(_) => bail!(
+                        "Timed out after {:?} initializing shared counter workload, created {} of {} counters",
+                        init_timeout,
+                        counters.len(),
+                        num_counters
+                    ),
+                }
+            }
+            None => create_counters.await?,
+        }
+        self.counters = counters;
+        Ok(())
     }
     async fn make_test_payloads(
         &self,
@@ -211,18 +273,32 @@ impl Workload<dyn Payload> for SharedCounterWorkload {
         // create counters using gas objects we created above
         info!("Creating shared txn payloads, hang tight..");
         let mut shared_payloads = vec![];
-        for g in payload_config.shared_counter_payload_gas.into_iter() {
-            // pick a random counter from the pool
-            let counter_ref = self
-                .counters
-                .choose(&mut rand::thread_rng())
-                .expect("Failed to get a random counter from the pool");
+        // Round-robin payloads stay on their counter, which keeps the fan-out
+        let input_selector = (self.randomize_inputs && !self.round_robin)
+            .then(|| InputSelector::new_shared(self.counters.clone()));
+        for (i, g) in payload_config
+            .shared_counter_payload_gas
+            .into_iter()
+            .enumerate()
+        {
+            // pick a random counter from the pool, or the next one in turn
+            let counter_ref = if self.round_robin {
+                self.counters.get(i % self.counters.len().max(1)).cloned()
+            } else {
+                match &input_selector {
+                    Some(selector) => selector.lock().unwrap().pick(),
+                    None => self.counters.choose(&mut bench_rng()).cloned(),
+                }
+            }
+            .expect("Failed to get a random counter from the pool");
             shared_payloads.push(Box::new(SharedCounterTestPayload {
                 package_id: self.basics_package_id.unwrap(),
                 counter_id: counter_ref.0,
                 counter_initial_shared_version: counter_ref.1,
                 gas: g,
+                gas_budget: self.gas_budget,
                 system_state_observer: system_state_observer.clone(),
+                input_selector: input_selector.clone(),
             }));
         }
         let payloads: Vec<Box<dyn Payload>> = shared_payloads
@@ -239,3 +315,45 @@ impl Workload<dyn Payload> for SharedCounterWorkload {
         write!(f, "{:?}", self as &SharedCounterWorkload)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::crypto::get_key_pair;
+    use sui_types::object::Owner;
+
+    #[test]
+    fn test_counter_increment_gas_budget() {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let keypair = Arc::new(keypair);
+        let payload = |gas_budget| SharedCounterTestPayload {
+            package_id: ObjectID::random(),
+            counter_id: ObjectID::random(),
+            counter_initial_shared_version: SequenceNumber::new(),
+            gas: (
+                random_object_ref(),
+                Owner::AddressOwner(sender),
+                keypair.clone(),
+            ),
+            gas_budget,
+            system_state_observer: Arc::new(SystemStateObserver::new_for_testing(1)),
+            input_selector: None,
+        };
+        let tx = payload(Some(AmountDistribution::Const(12_345))).make_transaction();
+        assert_eq!(tx.data().intent_message.value.gas_budget, 12_345);
+        let tx = payload(None).make_transaction();
+        assert_eq!(tx.data().intent_message.value.gas_budget, MAX_GAS);
+    }
+
+    #[test]
+    fn test_num_shared_counters() {
+        assert_eq!(num_shared_counters(None, 50, 100).unwrap(), 50);
+        assert_eq!(num_shared_counters(None, 100, 100).unwrap(), 0);
+        // The hotness factor is ignored once the counters are set directly
+        assert_eq!(num_shared_counters(Some(1), 0, 100).unwrap(), 1);
+        assert_eq!(num_shared_counters(Some(100), 50, 100).unwrap(), 100);
+        assert!(num_shared_counters(Some(0), 50, 100).is_err());
+        assert!(num_shared_counters(Some(101), 50, 100).is_err());
+    }
+}
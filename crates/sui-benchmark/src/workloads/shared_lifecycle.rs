@@ -0,0 +1,282 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::rng::bench_rng;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_counter_create_tx, AmountDistribution};
+use crate::workloads::payload::Payload;
+use crate::workloads::shared_counter::publish_basics_package;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+use sui_types::messages::VerifiedTransaction;
+use sui_types::object::Owner;
+use test_utils::messages::{make_counter_increment_transaction_with_gas_budget, MAX_GAS};
+use tracing::info;
+
+/// Counters created during the run, shared by all payloads of the workload
+#[derive(Debug)]
+pub struct CounterPool {
+    // Percentage of transactions creating a new counter
+    create_percent: u32,
+    // Increments a counter receives before it is retired from the pool
+    mutations: u64,
+    // Counters with the increments they have left
+    counters: Vec<(ObjectID, SequenceNumber, u64)>,
+}
+
+impl CounterPool {
+    pub fn new(create_percent: u32, mutations: u64) -> Self {
+        Self {
+            create_percent: create_percent.min(100),
+            mutations,
+            counters: vec![],
+        }
+    }
+
+    pub fn add(&mut self, counter_id: ObjectID, initial_shared_version: SequenceNumber) {
+        if self.mutations > 0 {
+            self.counters
+                .push((counter_id, initial_shared_version, self.mutations));
+        }
+    }
+
+    /// Counter the next transaction increments, None when it creates a new
+    /// counter instead, which it always does while the pool is empty
+    pub fn next<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<(ObjectID, SequenceNumber)> {
+        if self.counters.is_empty() || rng.gen_range(0..100) < self.create_percent {
+            return None;
+        }
+        let index = rng.gen_range(0..self.counters.len());
+        let (counter_id, initial_shared_version, left) = &mut self.counters[index];
+        let counter = (*counter_id, *initial_shared_version);
+        *left -= 1;
+        if *left == 0 {
+            self.counters.swap_remove(index);
+        }
+        Some(counter)
+    }
+}
+
+#[derive(Debug)]
+pub struct SharedLifecycleTestPayload {
+    package_id: ObjectID,
+    // Counter incremented by the next transaction, None creates one
+    counter: Option<(ObjectID, SequenceNumber)>,
+    gas: Gas,
+    gas_budget: Option<AmountDistribution>,
+    pool: Arc<Mutex<CounterPool>>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for SharedLifecycleTestPayload {
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        effects: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        let mut pool = self.pool.lock().unwrap();
+        if self.counter.is_none() {
+            // The new counter is the only object the transaction shared
+            if let Some((counter_ref, _)) = effects
+                .created()
+                .into_iter()
+                .find(|(_, owner)| matches!(owner, Owner::Shared { .. }))
+            {
+                pool.add(counter_ref.0, counter_ref.1);
+            }
+        }
+        let counter = pool.next(&mut bench_rng());
+        drop(pool);
+        Box::new(SharedLifecycleTestPayload {
+            package_id: self.package_id,
+            counter,
+            gas: (new_gas, self.gas.1, self.gas.2),
+            gas_budget: self.gas_budget,
+            pool: self.pool,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = *self.system_state_observer.reference_gas_price.borrow();
+        let sender = self
+            .gas
+            .1
+            .get_owner_address()
+            .expect("Cannot convert owner to address");
+        let gas_budget = sample_gas_budget(self.gas_budget, MAX_GAS, gas_price);
+        match self.counter {
+            None => make_counter_create_tx(
+                self.gas.0,
+                self.package_id,
+                sender,
+                &self.gas.2,
+                gas_budget,
+                Some(gas_price),
+            ),
+            Some((counter_id, initial_shared_version)) => {
+                make_counter_increment_transaction_with_gas_budget(
+                    self.gas.0,
+                    self.package_id,
+                    counter_id,
+                    initial_shared_version,
+                    sender,
+                    &self.gas.2,
+                    gas_budget,
+                    Some(gas_price),
+                )
+            }
+        }
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.counter.map_or(self.gas.0 .0, |counter| counter.0)
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::SharedLifecycle
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &SharedLifecycleTestPayload)
+    }
+}
+
+/// Creates shared counters during the run and increments them, so consensus
+/// keeps handling newly shared objects instead of a pool fixed at init. A
+/// counter is retired after `mutations` increments, the basics counter
+/// cannot be deleted
+#[derive(Debug)]
+pub struct SharedLifecycleWorkload {
+    pub basics_package_id: Option<ObjectID>,
+    // Percentage of transactions creating a new counter
+    pub create_percent: u32,
+    // Increments each created counter receives
+    pub mutations: u64,
+    // Gas budget of counter creations and increments, defaults to MAX_GAS
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl SharedLifecycleWorkload {
+    pub fn new_boxed(
+        create_percent: u32,
+        mutations: u64,
+        gas_budget: Option<AmountDistribution>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(SharedLifecycleWorkload {
+            basics_package_id: None,
+            create_percent,
+            mutations,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init() -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the basics package
+        generate_coin_configs_for_single_owner(1, MAX_GAS_FOR_TESTING)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for SharedLifecycleWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if self.basics_package_id.is_some() {
+            return Ok(());
+        }
+        let gas = init_config
+            .shared_lifecycle_init_gas
+            .first()
+            .ok_or_else(|| anyhow!("Not enough gas to initialize shared lifecycle workload"))?;
+        info!("Publishing basics package for the shared lifecycle workload");
+        self.basics_package_id = Some(
+            publish_basics_package(
+                gas.0,
+                proxy,
+                gas.1.get_owner_address()?,
+                &gas.2,
+                *system_state_observer.reference_gas_price.borrow(),
+            )
+            .await
+            .0,
+        );
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let pool = Arc::new(Mutex::new(CounterPool::new(
+            self.create_percent,
+            self.mutations,
+        )));
+        payload_config
+            .shared_lifecycle_payload_gas
+            .into_iter()
+            .map(|gas| {
+                Box::new(SharedLifecycleTestPayload {
+                    package_id: self.basics_package_id.unwrap(),
+                    counter: None,
+                    gas,
+                    gas_budget: self.gas_budget,
+                    pool: pool.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::SharedLifecycle
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &SharedLifecycleWorkload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_counter_pool_retires_counters() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pool = CounterPool::new(0, 3);
+        // Nothing to increment yet
+        assert_eq!(pool.next(&mut rng), None);
+        let counter = (ObjectID::random(), SequenceNumber::from_u64(1));
+        pool.add(counter.0, counter.1);
+        for _ in 0..3 {
+            assert_eq!(pool.next(&mut rng), Some(counter));
+        }
+        assert_eq!(pool.next(&mut rng), None);
+
+        let mut pool = CounterPool::new(100, 3);
+        pool.add(counter.0, counter.1);
+        assert_eq!(pool.next(&mut rng), None);
+    }
+}
@@ -1,24 +1,32 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{bail, Result};
 use async_trait::async_trait;
+use once_cell::sync::OnceCell;
 use rand::seq::IteratorRandom;
+use tracing::{error, info};
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress},
-    crypto::{get_key_pair, AccountKeyPair},
+    crypto::AccountKeyPair,
     messages::VerifiedTransaction,
     object::Owner,
 };
 
+use crate::rng::{bench_rng, get_key_pair};
 use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_pay_tx, AmountDistribution, PAY_GAS_BUDGET};
 use crate::workloads::payload::Payload;
-use crate::workloads::{Gas, GasCoinConfig, WorkloadInitGas, WorkloadPayloadGas};
+use crate::workloads::{
+    sample_gas_budget, Gas, GasCoinConfig, WorkloadInitGas, WorkloadPayloadGas,
+};
 use crate::{ExecutionEffects, ValidatorProxy};
-use sui_core::test_utils::make_transfer_object_transaction;
+use sui_core::test_utils::make_transfer_object_transaction_with_gas_budget;
+use test_utils::messages::MAX_GAS;
 
 use super::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
 
@@ -28,9 +36,102 @@ pub struct TransferObjectTestPayload {
     transfer_from: SuiAddress,
     transfer_to: SuiAddress,
     gas: Vec<Gas>,
+    // Amount to pay out of the transfer object, or None to transfer the whole object
+    amount: Option<u64>,
+    // Remaining balance of the transfer object
+    balance: u64,
+    amount_dist: Option<AmountDistribution>,
+    gas_budget: Option<AmountDistribution>,
+    // Set when objects cycle around a fixed ring of accounts
+    ring: Option<Arc<TransferRing>>,
     system_state_observer: Arc<SystemStateObserver>,
 }
 
+/// A fixed ring of accounts that transfer objects cycle around, so the owner
+/// of every object is predictable and can be checked after the run
+#[derive(Debug)]
+pub struct TransferRing {
+    accounts: Vec<SuiAddress>,
+    // Owner of each transfer object as of its last confirmed transaction
+    expected_owners: Mutex<HashMap<ObjectID, SuiAddress>>,
+}
+
+impl TransferRing {
+    fn new(mut accounts: Vec<SuiAddress>) -> Self {
+        accounts.sort();
+        Self {
+            accounts,
+            expected_owners: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The account following `account` in the ring
+    fn next(&self, account: SuiAddress) -> SuiAddress {
+        let pos = self
+            .accounts
+            .iter()
+            .position(|a| *a == account)
+            .expect("Account is not part of the transfer ring");
+        self.accounts[(pos + 1) % self.accounts.len()]
+    }
+
+    fn record(&self, object_id: ObjectID, owner: SuiAddress) {
+        self.expected_owners
+            .lock()
+            .unwrap()
+            .insert(object_id, owner);
+    }
+
+    /// Compares the owner of every transfer object with the expected one. An
+    /// object may also be owned by the next account in the ring, as its last
+    /// transfer can commit after the driver stopped waiting for it.
+    async fn verify(&self, proxy: Arc<dyn ValidatorProxy + Sync + Send>) -> Result<()> {
+        let expected_owners = self.expected_owners.lock().unwrap().clone();
+        let mut num_in_flight = 0;
+        let mut num_misplaced = 0;
+        for (object_id, expected) in expected_owners.iter() {
+            match proxy.get_object(*object_id).await {
+                Ok(object) if object.owner == Owner::AddressOwner(*expected) => (),
+                Ok(object) if object.owner == Owner::AddressOwner(self.next(*expected)) => {
+                    num_in_flight += 1
+                }
+                Ok(object) => {
+                    error!(
+                        "Transfer object {} is owned by {}, expected {}",
+                        object_id, object.owner, expected
+                    );
+                    num_misplaced += 1;
+                }
+                Err(err) => {
+                    error!("Transfer object {} is missing: {}", object_id, err);
+                    num_misplaced += 1;
+                }
+            }
+        }
+        info!(
+            "Checked owners of {} transfer objects, {} were transferred after the run ended",
+            expected_owners.len(),
+            num_in_flight
+        );
+        if num_misplaced > 0 {
+            bail!(
+                "{} of {} transfer objects are not where the transfer ring placed them",
+                num_misplaced,
+                expected_owners.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Samples the next amount to pay, falling back to transferring the
+/// whole object (None) if the sample exceeds the remaining balance
+fn sample_amount(amount_dist: Option<AmountDistribution>, balance: u64) -> Option<u64> {
+    amount_dist
+        .map(|dist| dist.sample(&mut bench_rng()))
+        .filter(|amount| *amount < balance)
+}
+
 impl Payload for TransferObjectTestPayload {
     fn make_new_payload(
         self: Box<Self>,
@@ -38,12 +139,25 @@ impl Payload for TransferObjectTestPayload {
         new_gas: ObjectRef,
         _: &ExecutionEffects,
     ) -> Box<dyn Payload> {
-        let recipient = self
-            .gas
-            .iter()
-            .find(|x| x.1.get_owner_address().unwrap() != self.transfer_to)
-            .unwrap()
-            .1;
+        let (transfer_from, balance) = match self.amount {
+            // A partial payment leaves the transfer object with the sender
+            Some(amount) => (self.transfer_from, self.balance - amount),
+            None => (self.transfer_to, self.balance),
+        };
+        let recipient = match &self.ring {
+            Some(ring) => {
+                ring.record(new_object.0, transfer_from);
+                ring.next(transfer_from)
+            }
+            None => self
+                .gas
+                .iter()
+                .find(|x| x.1.get_owner_address().unwrap() != transfer_from)
+                .unwrap()
+                .1
+                .get_owner_address()
+                .unwrap(),
+        };
         let updated_gas: Vec<Gas> = self
             .gas
             .into_iter()
@@ -57,9 +171,14 @@ impl Payload for TransferObjectTestPayload {
             .collect();
         Box::new(TransferObjectTestPayload {
             transfer_object: new_object,
-            transfer_from: self.transfer_to,
-            transfer_to: recipient.get_owner_address().unwrap(),
+            transfer_from,
+            transfer_to: recipient,
             gas: updated_gas,
+            amount: sample_amount(self.amount_dist, balance),
+            balance,
+            amount_dist: self.amount_dist,
+            gas_budget: self.gas_budget,
+            ring: self.ring,
             system_state_observer: self.system_state_observer,
         })
     }
@@ -69,14 +188,36 @@ impl Payload for TransferObjectTestPayload {
             .iter()
             .find(|x| x.1.get_owner_address().unwrap() == self.transfer_from)
             .unwrap();
-        make_transfer_object_transaction(
-            self.transfer_object,
-            *gas_obj,
-            self.transfer_from,
-            keypair,
-            self.transfer_to,
-            Some(*self.system_state_observer.reference_gas_price.borrow()),
-        )
+        let gas_price = Some(*self.system_state_observer.reference_gas_price.borrow());
+        match self.amount {
+            Some(amount) => make_pay_tx(
+                vec![self.transfer_object],
+                self.transfer_from,
+                vec![self.transfer_to],
+                vec![amount],
+                *gas_obj,
+                keypair,
+                sample_gas_budget(
+                    self.gas_budget,
+                    PAY_GAS_BUDGET,
+                    *self.system_state_observer.reference_gas_price.borrow(),
+                ),
+                gas_price,
+            ),
+            None => make_transfer_object_transaction_with_gas_budget(
+                self.transfer_object,
+                *gas_obj,
+                self.transfer_from,
+                keypair,
+                self.transfer_to,
+                sample_gas_budget(
+                    self.gas_budget,
+                    MAX_GAS,
+                    *self.system_state_observer.reference_gas_price.borrow(),
+                ),
+                gas_price,
+            ),
+        }
     }
     fn get_object_id(&self) -> ObjectID {
         self.transfer_object.0
@@ -93,15 +234,32 @@ impl Payload for TransferObjectTestPayload {
 #[derive(Debug)]
 pub struct TransferObjectWorkload {
     pub transfer_keypairs: Arc<HashMap<SuiAddress, AccountKeyPair>>,
+    // When set, pay amounts sampled from this distribution instead of
+    // transferring whole objects
+    pub amount_dist: Option<AmountDistribution>,
+    // Gas budget of transfers and payments, defaults to the budget of each transaction kind
+    pub gas_budget: Option<AmountDistribution>,
+    // Cycle objects around a fixed ring of accounts and check their owners after the run
+    pub ring_check: bool,
+    ring: OnceCell<Arc<TransferRing>>,
 }
 
 impl TransferObjectWorkload {
-    pub fn new_boxed(num_accounts: u64) -> Box<dyn Workload<dyn Payload>> {
+    pub fn new_boxed(
+        num_accounts: u64,
+        amount_dist: Option<AmountDistribution>,
+        gas_budget: Option<AmountDistribution>,
+        ring_check: bool,
+    ) -> Box<dyn Workload<dyn Payload>> {
         // create several accounts to transfer object between
         let keypairs: Arc<HashMap<SuiAddress, AccountKeyPair>> =
             Arc::new((0..num_accounts).map(|_| get_key_pair()).collect());
         Box::new(TransferObjectWorkload {
             transfer_keypairs: keypairs,
+            amount_dist,
+            gas_budget,
+            ring_check,
+            ring: OnceCell::new(),
         })
     }
     pub fn generate_coin_config_for_payloads(
@@ -126,7 +284,7 @@ impl TransferObjectWorkload {
             }
         }
 
-        let owner = *address_map.keys().choose(&mut rand::thread_rng()).unwrap();
+        let owner = *address_map.keys().choose(&mut bench_rng()).unwrap();
 
         // transfer tokens
         let mut token_configs = vec![];
@@ -150,8 +308,8 @@ impl Workload<dyn Payload> for TransferObjectWorkload {
         _init_config: WorkloadInitGas,
         _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
         _system_state_observer: Arc<SystemStateObserver>,
-    ) {
-        return;
+    ) -> Result<()> {
+        Ok(())
     }
     async fn make_test_payloads(
         &self,
@@ -169,6 +327,11 @@ impl Workload<dyn Payload> for TransferObjectWorkload {
         }
 
         let addresses: Vec<SuiAddress> = gas_by_address.keys().cloned().collect();
+        let ring = self.ring_check.then(|| {
+            self.ring
+                .get_or_init(|| Arc::new(TransferRing::new(addresses.clone())))
+                .clone()
+        });
         let mut transfer_gas: Vec<Vec<Gas>> = vec![];
         for i in 0..num_payloads {
             let mut account_transfer_gas = vec![];
@@ -184,19 +347,42 @@ impl Workload<dyn Payload> for TransferObjectWorkload {
             .collect();
         refs.iter()
             .map(|(g, t)| {
-                let from = t.1;
-                let to = g.iter().find(|x| x.1 != from).unwrap().1;
+                let from = t.1.get_owner_address().unwrap();
+                let to = match &ring {
+                    Some(ring) => {
+                        ring.record(t.0 .0, from);
+                        ring.next(from)
+                    }
+                    None => g
+                        .iter()
+                        .find(|x| x.1 != t.1)
+                        .unwrap()
+                        .1
+                        .get_owner_address()
+                        .unwrap(),
+                };
                 Box::new(TransferObjectTestPayload {
                     transfer_object: t.0,
-                    transfer_from: from.get_owner_address().unwrap(),
-                    transfer_to: to.get_owner_address().unwrap(),
+                    transfer_from: from,
+                    transfer_to: to,
                     gas: g.to_vec(),
+                    amount: sample_amount(self.amount_dist, MAX_GAS_FOR_TESTING),
+                    balance: MAX_GAS_FOR_TESTING,
+                    amount_dist: self.amount_dist,
+                    gas_budget: self.gas_budget,
+                    ring: ring.clone(),
                     system_state_observer: system_state_observer.clone(),
                 })
             })
             .map(|b| Box::<dyn Payload>::from(b))
             .collect()
     }
+    async fn verify_final_state(&self, proxy: Arc<dyn ValidatorProxy + Sync + Send>) -> Result<()> {
+        match self.ring.get() {
+            Some(ring) => ring.verify(proxy).await,
+            None => Ok(()),
+        }
+    }
     fn get_workload_type(&self) -> WorkloadType {
         WorkloadType::TransferObject
     }
@@ -205,3 +391,89 @@ impl Workload<dyn Payload> for TransferObjectWorkload {
         write!(f, "{:?}", self as &TransferObjectWorkload)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::random_object_ref;
+
+    fn make_payload(
+        amount: Option<u64>,
+        gas_budget: Option<AmountDistribution>,
+    ) -> TransferObjectTestPayload {
+        let (from, from_keypair) = get_key_pair();
+        let (to, to_keypair): (_, AccountKeyPair) = get_key_pair();
+        TransferObjectTestPayload {
+            transfer_object: random_object_ref(),
+            transfer_from: from,
+            transfer_to: to,
+            gas: vec![
+                (
+                    random_object_ref(),
+                    Owner::AddressOwner(from),
+                    Arc::new(from_keypair),
+                ),
+                (
+                    random_object_ref(),
+                    Owner::AddressOwner(to),
+                    Arc::new(to_keypair),
+                ),
+            ],
+            amount,
+            balance: MAX_GAS_FOR_TESTING,
+            amount_dist: None,
+            gas_budget,
+            ring: None,
+            system_state_observer: Arc::new(SystemStateObserver::new_for_testing(1)),
+        }
+    }
+
+    #[test]
+    fn test_transfer_gas_budget() {
+        for amount in [None, Some(10)] {
+            let tx =
+                make_payload(amount, Some(AmountDistribution::Const(12_345))).make_transaction();
+            assert_eq!(tx.data().intent_message.value.gas_budget, 12_345);
+        }
+        let tx = make_payload(None, None).make_transaction();
+        assert_eq!(tx.data().intent_message.value.gas_budget, MAX_GAS);
+        let tx = make_payload(Some(10), None).make_transaction();
+        assert_eq!(tx.data().intent_message.value.gas_budget, PAY_GAS_BUDGET);
+    }
+
+    #[test]
+    fn test_sample_amount_within_balance() {
+        assert_eq!(sample_amount(None, 100), None);
+        assert_eq!(
+            sample_amount(Some(AmountDistribution::Const(10)), 100),
+            Some(10)
+        );
+        // Amounts the object cannot pay while keeping a balance transfer
+        // the whole object instead
+        assert_eq!(
+            sample_amount(Some(AmountDistribution::Const(100)), 100),
+            None
+        );
+        assert_eq!(
+            sample_amount(Some(AmountDistribution::Const(150)), 100),
+            None
+        );
+    }
+
+    #[test]
+    fn test_transfer_ring_cycles_accounts() {
+        let accounts: Vec<SuiAddress> =
+            (0..3).map(|_| get_key_pair::<AccountKeyPair>().0).collect();
+        let ring = TransferRing::new(accounts.clone());
+        let mut account = accounts[0];
+        let mut visited = vec![];
+        for _ in 0..accounts.len() {
+            account = ring.next(account);
+            visited.push(account);
+        }
+        // Every account is visited once before returning to the start
+        assert_eq!(account, accounts[0]);
+        visited.sort();
+        assert_eq!(visited, ring.accounts);
+    }
+}
@@ -1,14 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::{collections::HashMap, fmt};
 
+use crate::rng::bench_rng;
 use crate::system_state_observer::SystemStateObserver;
 use crate::workloads::{WorkloadInitGas, WorkloadPayloadGas};
-use rand::rngs::OsRng;
 use rand_distr::WeightedAliasIndex;
+use strum_macros::EnumIter;
 
 use crate::workloads::payload::{CombinationPayload, Payload};
 use crate::ValidatorProxy;
@@ -17,12 +19,26 @@ use crate::ValidatorProxy;
 // for running the benchmark
 pub const MAX_GAS_FOR_TESTING: u64 = 1_000_000_000;
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, EnumIter)]
 pub enum WorkloadType {
     SharedCounter,
     TransferObject,
     Combination,
     Delegation,
+    Read,
+    Collection,
+    WrapUnwrap,
+    DependencyChain,
+    SharedLifecycle,
+    Noop,
+    SendToObject,
+    ObjectFanout,
+    NftMint,
+    Batch,
+    CoinChurn,
+    // Built by a `WorkloadFactory` of the registry, named by the factory
+    #[strum(disabled)]
+    Custom(&'static str),
 }
 
 impl fmt::Display for WorkloadType {
@@ -32,6 +48,161 @@ impl fmt::Display for WorkloadType {
             WorkloadType::TransferObject => write!(f, "transfer_object"),
             WorkloadType::Combination => write!(f, "combination"),
             WorkloadType::Delegation => write!(f, "delegation"),
+            WorkloadType::Read => write!(f, "read"),
+            WorkloadType::Collection => write!(f, "collection"),
+            WorkloadType::WrapUnwrap => write!(f, "wrap_unwrap"),
+            WorkloadType::DependencyChain => write!(f, "dependency_chain"),
+            WorkloadType::SharedLifecycle => write!(f, "shared_lifecycle"),
+            WorkloadType::Noop => write!(f, "noop"),
+            WorkloadType::SendToObject => write!(f, "send_to_object"),
+            WorkloadType::ObjectFanout => write!(f, "object_fanout"),
+            WorkloadType::NftMint => write!(f, "nft_mint"),
+            WorkloadType::Batch => write!(f, "batch"),
+            WorkloadType::CoinChurn => write!(f, "coin_churn"),
+            WorkloadType::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+// Serialized by name, as accepted on the command line
+impl serde::Serialize for WorkloadType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl WorkloadType {
+    /// One line summary printed by `list-workloads`
+    pub fn description(&self) -> &'static str {
+        match self {
+            WorkloadType::SharedCounter => {
+                "Increments shared counters, exercising consensus on shared objects"
+            }
+            WorkloadType::TransferObject => {
+                "Transfers owned objects between accounts, the fast path for owned objects"
+            }
+            WorkloadType::Combination => "Mix of the other workloads picked by their weights",
+            WorkloadType::Delegation => "Splits off a coin and delegates it to a validator",
+            WorkloadType::Read => "Read-only object queries served by a fullnode",
+            WorkloadType::Collection => "Churns vector and table entries of owned Move objects",
+            WorkloadType::WrapUnwrap => {
+                "Alternately wraps an owned object into a container and unwraps it"
+            }
+            WorkloadType::DependencyChain => {
+                "Chains of transactions each spending the coin created by the one before"
+            }
+            WorkloadType::SharedLifecycle => {
+                "Creates shared counters during the run and increments the new counters"
+            }
+            WorkloadType::Noop => {
+                "Calls a Move function that does nothing, bounded by transaction verification"
+            }
+            WorkloadType::SendToObject => {
+                "Sends new coins to the addresses of objects instead of accounts"
+            }
+            WorkloadType::ObjectFanout => {
+                "Creates --create-fanout new owned objects per transaction, stressing storage"
+            }
+            WorkloadType::NftMint => {
+                "Mints NFTs through a published package, sized by the --nft-*-length flags"
+            }
+            WorkloadType::Batch => {
+                "Batch transactions of --commands-per-tx calls to a function doing nothing"
+            }
+            WorkloadType::CoinChurn => {
+                "Splits a coin into --split-count coins and merges them back, in turn"
+            }
+            WorkloadType::Custom(_) => "Registered with the workload registry by another crate",
+        }
+    }
+
+    /// Long names of the flags tuning the workload, its weight first,
+    /// printed by `list-workloads`
+    pub fn flags(&self) -> &'static [&'static str] {
+        match self {
+            WorkloadType::SharedCounter => &[
+                "shared-counter",
+                "shared-counter-hotness-factor",
+                "contention-objects",
+                "shared-counter-gas-budget",
+                "shared-counter-in-flight-ratio",
+                "randomize-inputs",
+            ],
+            WorkloadType::TransferObject => &[
+                "transfer-object",
+                "num-transfer-accounts",
+                "pay-amount-dist",
+                "transfer-ring-check",
+                "transfer-object-gas-budget",
+                "transfer-object-in-flight-ratio",
+            ],
+            WorkloadType::Combination => &[],
+            WorkloadType::Delegation => &[
+                "delegation",
+                "delegation-target",
+                "delegation-gas-budget",
+                "delegation-in-flight-ratio",
+            ],
+            WorkloadType::Read => &["read", "read-in-flight-ratio", "randomize-inputs"],
+            WorkloadType::Collection => &[
+                "collection",
+                "collection-ops-per-tx",
+                "collection-gas-budget",
+                "collection-in-flight-ratio",
+            ],
+            WorkloadType::WrapUnwrap => &[
+                "wrap-unwrap",
+                "wrap-unwrap-gas-budget",
+                "wrap-unwrap-in-flight-ratio",
+            ],
+            WorkloadType::DependencyChain => &[
+                "dependency-chain",
+                "dependency-chain-length",
+                "dependency-chain-gas-budget",
+                "dependency-chain-in-flight-ratio",
+            ],
+            WorkloadType::SharedLifecycle => &[
+                "shared-lifecycle",
+                "shared-lifecycle-create-percent",
+                "shared-lifecycle-mutations",
+                "shared-lifecycle-gas-budget",
+                "shared-lifecycle-in-flight-ratio",
+            ],
+            WorkloadType::Noop => &["noop", "noop-gas-budget", "noop-in-flight-ratio"],
+            WorkloadType::SendToObject => &[
+                "send-to-object",
+                "send-to-object-destinations",
+                "send-to-object-gas-budget",
+                "send-to-object-in-flight-ratio",
+            ],
+            WorkloadType::ObjectFanout => &[
+                "object-fanout",
+                "create-fanout",
+                "object-fanout-gas-budget",
+                "object-fanout-in-flight-ratio",
+            ],
+            WorkloadType::NftMint => &[
+                "nft-mint",
+                "nft-name-length",
+                "nft-url-length",
+                "nft-description-length",
+                "nft-mint-gas-budget",
+                "nft-mint-in-flight-ratio",
+            ],
+            WorkloadType::Batch => &[
+                "batch",
+                "commands-per-tx",
+                "batch-gas-budget",
+                "batch-in-flight-ratio",
+            ],
+            WorkloadType::CoinChurn => &[
+                "coin-churn",
+                "split-count",
+                "coin-churn-gas-budget",
+                "coin-churn-in-flight-ratio",
+            ],
+            // Declared by the factory of the workload
+            WorkloadType::Custom(_) => &[],
         }
     }
 }
@@ -43,7 +214,7 @@ pub trait Workload<T: Payload + ?Sized>: Send + Sync {
         init_config: WorkloadInitGas,
         proxy: Arc<dyn ValidatorProxy + Sync + Send>,
         system_state_observer: Arc<SystemStateObserver>,
-    );
+    ) -> Result<()>;
     async fn make_test_payloads(
         &self,
         num_payloads: u64,
@@ -51,6 +222,13 @@ pub trait Workload<T: Payload + ?Sized>: Send + Sync {
         proxy: Arc<dyn ValidatorProxy + Sync + Send>,
         system_state_observer: Arc<SystemStateObserver>,
     ) -> Vec<Box<T>>;
+    /// Checks the state the run left behind, e.g. that no objects were lost
+    async fn verify_final_state(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+    ) -> Result<()> {
+        Ok(())
+    }
     fn get_workload_type(&self) -> WorkloadType;
 
     fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
@@ -62,7 +240,7 @@ impl std::fmt::Debug for dyn Workload<dyn Payload> {
     }
 }
 
-type WeightAndPayload = (u32, Box<dyn Workload<dyn Payload>>);
+pub type WeightAndPayload = (u32, Box<dyn Workload<dyn Payload>>);
 
 #[derive(Debug)]
 pub struct CombinationWorkload {
@@ -76,7 +254,7 @@ impl Workload<dyn Payload> for CombinationWorkload {
         init_config: WorkloadInitGas,
         proxy: Arc<dyn ValidatorProxy + Sync + Send>,
         system_state_observer: Arc<SystemStateObserver>,
-    ) {
+    ) -> Result<()> {
         for (_, (_, workload)) in self.workloads.iter_mut() {
             workload
                 .init(
@@ -84,8 +262,9 @@ impl Workload<dyn Payload> for CombinationWorkload {
                     proxy.clone(),
                     system_state_observer.clone(),
                 )
-                .await;
+                .await?;
         }
+        Ok(())
     }
     async fn make_test_payloads(
         &self,
@@ -121,13 +300,19 @@ impl Workload<dyn Payload> for CombinationWorkload {
                 payloads: all_payloads,
                 dist: WeightedAliasIndex::new(dist).unwrap(),
                 curr_index: 0,
-                rng: OsRng::default(),
+                rng: bench_rng(),
             }));
         }
         res.into_iter()
             .map(|b| Box::<dyn Payload>::from(b))
             .collect()
     }
+    async fn verify_final_state(&self, proxy: Arc<dyn ValidatorProxy + Sync + Send>) -> Result<()> {
+        for (_, (_, workload)) in self.workloads.iter() {
+            workload.verify_final_state(proxy.clone()).await?;
+        }
+        Ok(())
+    }
     fn get_workload_type(&self) -> WorkloadType {
         WorkloadType::Combination
     }
@@ -1,22 +1,30 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use comfy_table::{Cell, ContentArrangement, Row, Table};
 
 use move_core_types::language_storage::TypeTag;
 
 use std::sync::Arc;
+use std::time::Duration;
+use sui_types::gas_coin::GasCoin;
+use tracing::info;
 
-use crate::options::{Opts, RunSpec};
+use crate::gas_manifest::GasManifest;
+use crate::limiting_proxy::LimitingProxy;
+use crate::options::{InFlightRatios, Opts, RunSpec};
 use crate::system_state_observer::SystemStateObserver;
-use crate::util::generate_all_gas_for_test;
-use crate::workloads::shared_counter::SharedCounterWorkload;
-use crate::workloads::transfer_object::TransferObjectWorkload;
-
-use crate::workloads::delegation::DelegationWorkload;
-use crate::workloads::workload::WorkloadInfo;
+use crate::util::{generate_all_gas_for_test, load_sender_accounts, AmountDistribution};
+use crate::workloads::batch::{batch_gas_budget, check_commands_per_tx};
+use crate::workloads::coin_churn::{check_split_count, coin_churn_gas_budget};
+use crate::workloads::nft_mint::{nft_mint_gas_budget, NftMetadataLengths};
+use crate::workloads::object_fanout::fanout_gas_budget;
+use crate::workloads::payload::Payload;
+use crate::workloads::registry::{WorkloadFactory, WorkloadRegistry};
+use crate::workloads::workload::{Workload, WorkloadInfo, WorkloadType, MAX_GAS_FOR_TESTING};
 use crate::workloads::{
-    make_combination_workload, make_delegation_workload, make_shared_counter_workload,
-    make_transfer_object_workload, Gas, WorkloadGasConfig, WorkloadInitGas, WorkloadPayloadGas,
+    make_combination_workload, Gas, GasCoinConfig, GasRequirement, SenderAccount,
+    WorkloadGasBudgets, WorkloadGasConfig, WorkloadInitGas, WorkloadParams, WorkloadPayloadGas,
 };
 use crate::ValidatorProxy;
 
@@ -35,290 +43,678 @@ impl WorkloadConfiguration {
         pay_coin_type_tag: TypeTag,
         proxy: Arc<dyn ValidatorProxy + Send + Sync>,
         opts: &Opts,
+        registry: &WorkloadRegistry,
         system_state_observer: Arc<SystemStateObserver>,
     ) -> Result<Vec<WorkloadInfo>> {
+        let init_timeout = opts.init_timeout_secs.map(Duration::from_secs);
+        // Only init goes through the limit, the payloads get their own proxy
+        let proxy: Arc<dyn ValidatorProxy + Send + Sync> = match opts.init_concurrency {
+            Some(0) => bail!("--init-concurrency must allow at least one call"),
+            Some(limit) => Arc::new(LimitingProxy::new(proxy, limit)),
+            None => proxy,
+        };
+        // Workload budgets override the budget, or distribution of budgets,
+        // shared by all workloads
+        let gas_budget = opts
+            .gas_budget_dist
+            .or_else(|| opts.gas_budget.map(AmountDistribution::Const));
+        let gas_budgets = WorkloadGasBudgets {
+            default: gas_budget,
+            overrides: opts
+                .gas_budgets
+                .0
+                .iter()
+                .map(|(workload, budget)| (*workload, AmountDistribution::Const(*budget)))
+                .collect(),
+        };
+        let sender_pool = match &opts.sender_addresses_file {
+            Some(path) => load_sender_accounts(path)?,
+            None => vec![],
+        };
+        if !sender_pool.is_empty() && opts.gas_coins_per_sender.is_some() {
+            bail!(
+                "--gas-coins-per-sender creates its own senders, it cannot be combined \
+                with --sender-addresses-file"
+            );
+        }
         match opts.run_spec {
             RunSpec::Bench {
                 target_qps,
                 num_workers,
                 in_flight_ratio,
-                shared_counter,
-                transfer_object,
-                delegation,
+                max_in_flight,
+                ref in_flight_ratios,
+                ref weights,
                 shared_counter_hotness_factor,
+                contention_objects,
                 ..
-            } => match self {
-                WorkloadConfiguration::Combined => {
-                    self.configure_combined_mode(
-                        num_workers,
-                        opts.num_transfer_accounts,
-                        shared_counter,
-                        transfer_object,
-                        delegation,
-                        shared_counter_hotness_factor,
-                        target_qps,
-                        in_flight_ratio,
-                        gas,
-                        pay_coin,
-                        pay_coin_type_tag,
-                        proxy,
-                        system_state_observer,
-                    )
-                    .await
+            } => {
+                if weights.get(WorkloadType::Batch) > 0 {
+                    check_commands_per_tx(opts.commands_per_tx)?;
+                    let gas_price = *system_state_observer.reference_gas_price.borrow();
+                    if gas_budgets.of(WorkloadType::Batch).is_none()
+                        && batch_gas_budget(opts.commands_per_tx).saturating_mul(gas_price)
+                            > MAX_GAS_FOR_TESTING
+                    {
+                        bail!(
+                            "Payload gas coins cannot pay for batches of {} commands at gas \
+                            price {}, lower --commands-per-tx",
+                            opts.commands_per_tx,
+                            gas_price
+                        );
+                    }
                 }
-                WorkloadConfiguration::Disjoint => {
-                    self.configure_disjoint_mode(
-                        num_workers,
-                        opts.num_transfer_accounts,
-                        shared_counter,
-                        transfer_object,
-                        delegation,
+                if weights.get(WorkloadType::CoinChurn) > 0 {
+                    check_split_count(opts.split_count)?;
+                    let gas_price = *system_state_observer.reference_gas_price.borrow();
+                    if gas_budgets.of(WorkloadType::CoinChurn).is_none()
+                        && coin_churn_gas_budget(opts.split_count).saturating_mul(gas_price)
+                            > MAX_GAS_FOR_TESTING
+                    {
+                        bail!(
+                            "Payload gas coins cannot pay for splitting a coin into {} at gas \
+                            price {}, lower --split-count",
+                            opts.split_count,
+                            gas_price
+                        );
+                    }
+                }
+                let nft_metadata_lengths = NftMetadataLengths {
+                    name: opts.nft_name_length,
+                    url: opts.nft_url_length,
+                    description: opts.nft_description_length,
+                };
+                if weights.get(WorkloadType::NftMint) > 0 {
+                    let gas_price = *system_state_observer.reference_gas_price.borrow();
+                    if gas_budgets.of(WorkloadType::NftMint).is_none()
+                        && nft_mint_gas_budget(nft_metadata_lengths).saturating_mul(gas_price)
+                            > MAX_GAS_FOR_TESTING
+                    {
+                        bail!(
+                            "Payload gas coins cannot pay for {} bytes of NFT metadata at gas \
+                            price {}, lower the --nft-*-length flags",
+                            nft_metadata_lengths.total(),
+                            gas_price
+                        );
+                    }
+                }
+                if weights.get(WorkloadType::ObjectFanout) > 0 {
+                    if opts.create_fanout == 0 {
+                        bail!("--create-fanout must create at least one object per transaction");
+                    }
+                    let gas_price = *system_state_observer.reference_gas_price.borrow();
+                    if gas_budgets.of(WorkloadType::ObjectFanout).is_none()
+                        && fanout_gas_budget(opts.create_fanout).saturating_mul(gas_price)
+                            > MAX_GAS_FOR_TESTING
+                    {
+                        bail!(
+                            "Payload gas coins cannot pay for {} created objects per transaction \
+                            at gas price {}, lower --create-fanout",
+                            opts.create_fanout,
+                            gas_price
+                        );
+                    }
+                }
+                if weights.get(WorkloadType::Read) > 0
+                    && (opts.local
+                        || !(opts.use_fullnode_for_execution || opts.use_fullnode_for_reads))
+                {
+                    bail!(
+                        "Read workload requires a fullnode, set --use-fullnode-for-execution \
+                        or --use-fullnode-for-reads"
+                    );
+                }
+                let mut weights = weights.0.clone();
+                weights.extend(
+                    registry
+                        .factories()
+                        .map(|(weight, factory)| (WorkloadType::Custom(factory.name()), *weight)),
+                );
+                let config = ModeConfig {
+                    weights,
+                    params: WorkloadParams {
+                        num_transfer_accounts: opts.num_transfer_accounts,
+                        transfer_amount_dist: opts.pay_amount_dist,
+                        transfer_ring_check: opts.transfer_ring_check,
+                        delegation_target: opts.delegation_target.clone(),
+                        collection_ops_per_tx: opts.collection_ops_per_tx,
+                        dependency_chain_length: opts.dependency_chain_length,
+                        shared_lifecycle_create_percent: opts.shared_lifecycle_create_percent,
+                        shared_lifecycle_mutations: opts.shared_lifecycle_mutations,
+                        send_to_object_destinations: opts.send_to_object_destinations,
+                        create_fanout: opts.create_fanout,
+                        nft_metadata_lengths,
+                        commands_per_tx: opts.commands_per_tx,
+                        split_count: opts.split_count,
+                        randomize_inputs: opts.randomize_inputs,
                         shared_counter_hotness_factor,
+                        contention_objects,
+                        shared_counter_init_timeout: init_timeout,
+                        gas_budgets,
+                    },
+                    registry,
+                    load: LoadConfig {
                         target_qps,
+                        num_workers,
                         in_flight_ratio,
-                        gas,
-                        pay_coin,
-                        pay_coin_type_tag,
-                        proxy,
-                        system_state_observer,
-                    )
-                    .await
+                        max_in_flight,
+                        in_flight_ratios: in_flight_ratios.clone(),
+                        sequential: opts.sequential_workloads,
+                    },
+                    gas_coins_per_sender: opts.gas_coins_per_sender,
+                    sender_pool,
+                    dry_run: opts.dry_run,
+                    gas_manifest: opts.gas_manifest.clone(),
+                };
+                match self {
+                    WorkloadConfiguration::Combined => {
+                        if !config.load.in_flight_ratios.is_empty() {
+                            bail!(
+                                "Per-workload in-flight ratios need --disjoint-mode true, combined \
+                                workers issue every workload from one in-flight window"
+                            );
+                        }
+                        self.configure_combined_mode(
+                            config,
+                            gas,
+                            pay_coin,
+                            pay_coin_type_tag,
+                            proxy,
+                            system_state_observer,
+                        )
+                        .await
+                    }
+                    WorkloadConfiguration::Disjoint => {
+                        self.configure_disjoint_mode(
+                            config,
+                            gas,
+                            pay_coin,
+                            pay_coin_type_tag,
+                            proxy,
+                            system_state_observer,
+                        )
+                        .await
+                    }
                 }
-            },
+            }
+            RunSpec::ClosedLoop { .. } => {
+                bail!("closed-loop runs are configured as the bench run of Opts::apply_closed_loop")
+            }
+            RunSpec::MergeHistograms { .. } => {
+                bail!("merge-histograms does not configure any workloads")
+            }
+            RunSpec::ListWorkloads => {
+                bail!("list-workloads does not configure any workloads")
+            }
         }
     }
 
     async fn configure_combined_mode(
         &self,
-        num_workers: u64,
-        num_transfer_accounts: u64,
-        shared_counter_weight: u32,
-        transfer_object_weight: u32,
-        delegation_weight: u32,
-        shared_counter_hotness_factor: u32,
-        target_qps: u64,
-        in_flight_ratio: u64,
+        config: ModeConfig<'_>,
         gas: Gas,
         coin: Gas,
         coin_type_tag: TypeTag,
         proxy: Arc<dyn ValidatorProxy + Send + Sync>,
         system_state_observer: Arc<SystemStateObserver>,
     ) -> Result<Vec<WorkloadInfo>> {
-        let shared_counter_ratio =
-            1.0 - (std::cmp::min(shared_counter_hotness_factor as u32, 100) as f32 / 100.0);
-        let max_ops = target_qps * in_flight_ratio;
-        let all_shared_counter_coin_configs = if shared_counter_weight == 0 {
-            None
-        } else {
-            let num_shared_counters = (max_ops as f32 * shared_counter_ratio) as u64;
-            let shared_counter_init_coin_configs =
-                SharedCounterWorkload::generate_coin_config_for_init(num_shared_counters);
-            let shared_counter_payload_coin_configs =
-                SharedCounterWorkload::generate_coin_config_for_payloads(max_ops);
-            Some((
-                shared_counter_init_coin_configs,
-                shared_counter_payload_coin_configs,
-            ))
-        };
-        let all_transfer_object_coin_configs = if transfer_object_weight == 0 {
-            None
-        } else {
-            Some(TransferObjectWorkload::generate_coin_config_for_payloads(
-                max_ops,
-                num_transfer_accounts,
-                max_ops,
-            ))
-        };
-        let delegation_gas_configs = if delegation_weight > 0 {
-            DelegationWorkload::generate_gas_config_for_payloads(max_ops)
-        } else {
-            vec![]
+        // Provisioned for the target qps, which a ramp up only reaches at its end
+        let load = &config.load;
+        let max_ops = load
+            .max_in_flight
+            .unwrap_or(load.target_qps * load.in_flight_ratio);
+        let weights: Vec<(WorkloadType, u32)> = config
+            .weights
+            .iter()
+            .copied()
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        let mut workload_gas_config = WorkloadGasConfig::default();
+        for &(workload, _) in &weights {
+            let (init, payload) = config.gas_configs(workload, max_ops)?;
+            workload_gas_config.insert(workload, init, payload);
+        }
+        let (workload_init_gas, workload_payload_gas) = match config
+            .provision_gas(
+                workload_gas_config,
+                gas,
+                coin,
+                coin_type_tag,
+                proxy.clone(),
+                &system_state_observer,
+            )
+            .await?
+        {
+            Some(gas) => gas,
+            None => return Ok(vec![]),
         };
-        let (shared_counter_workload_init_gas_config, shared_counter_workload_payload_gas_config) =
-            all_shared_counter_coin_configs.unwrap_or((vec![], vec![]));
-        let (transfer_object_workload_tokens, transfer_object_workload_payload_gas_config) =
-            all_transfer_object_coin_configs.unwrap_or((vec![], vec![]));
-        let (workload_init_gas, workload_payload_gas) = generate_all_gas_for_test(
-            proxy.clone(),
-            gas,
-            coin,
-            coin_type_tag,
-            WorkloadGasConfig {
-                shared_counter_workload_init_gas_config,
-                shared_counter_workload_payload_gas_config,
-                transfer_object_workload_tokens,
-                transfer_object_workload_payload_gas_config,
-                delegation_gas_configs,
-            },
-            *system_state_observer.reference_gas_price.borrow(),
-        )
-        .await?;
+        let mut workloads = vec![];
+        for (workload, weight) in weights {
+            workloads.push((weight, config.make_workload(workload)?));
+        }
         let mut combination_workload = make_combination_workload(
-            target_qps,
-            num_workers,
-            in_flight_ratio,
-            num_transfer_accounts,
-            shared_counter_weight,
-            transfer_object_weight,
-            delegation_weight,
+            config.load.target_qps,
+            config.load.num_workers,
+            max_ops,
+            workloads,
             workload_payload_gas,
         );
         combination_workload
             .workload
             .init(workload_init_gas, proxy, system_state_observer.clone())
-            .await;
+            .await?;
         Ok(vec![combination_workload])
     }
 
     async fn configure_disjoint_mode(
         &self,
-        num_workers: u64,
-        num_transfer_accounts: u64,
-        shared_counter_weight: u32,
-        transfer_object_weight: u32,
-        delegation_weight: u32,
-        shared_counter_hotness_factor: u32,
-        target_qps: u64,
-        in_flight_ratio: u64,
+        config: ModeConfig<'_>,
         gas: Gas,
         coin: Gas,
         coin_type_tag: TypeTag,
         proxy: Arc<dyn ValidatorProxy + Send + Sync>,
         system_state_observer: Arc<SystemStateObserver>,
     ) -> Result<Vec<WorkloadInfo>> {
+        let total_weight = config.weights.iter().map(|(_, weight)| weight).sum::<u32>();
+        let mut shares = vec![];
+        for &(workload, weight) in &config.weights {
+            let share = config.load.disjoint_share(workload, weight, total_weight)?;
+            if !share.is_idle() {
+                shares.push((workload, share));
+            }
+        }
+        let mut workload_gas_config = WorkloadGasConfig::default();
+        for &(workload, share) in &shares {
+            let (init, payload) = config.gas_configs(workload, share.max_ops)?;
+            workload_gas_config.insert(workload, init, payload);
+        }
+        let (mut workload_init_gas, mut workload_payload_gas) = match config
+            .provision_gas(
+                workload_gas_config,
+                gas,
+                coin,
+                coin_type_tag,
+                proxy.clone(),
+                &system_state_observer,
+            )
+            .await?
+        {
+            Some(gas) => gas,
+            None => return Ok(vec![]),
+        };
         let mut workloads = vec![];
-        let shared_counter_weight_ratio = shared_counter_weight as f32
-            / (shared_counter_weight + transfer_object_weight + delegation_weight) as f32;
-        let shared_counter_qps = (shared_counter_weight_ratio * target_qps as f32) as u64;
-        let shared_counter_num_workers =
-            (shared_counter_weight_ratio * num_workers as f32).ceil() as u64;
-        let shared_counter_max_ops = (shared_counter_qps * in_flight_ratio) as u64;
-        let shared_counter_ratio =
-            1.0 - (std::cmp::min(shared_counter_hotness_factor as u32, 100) as f32 / 100.0);
-        let num_shared_counters = (shared_counter_max_ops as f32 * shared_counter_ratio) as u64;
-        let (shared_counter_workload_init_gas_config, shared_counter_workload_payload_gas_config) =
-            if shared_counter_qps == 0
-                || shared_counter_max_ops == 0
-                || shared_counter_num_workers == 0
-            {
-                (vec![], vec![])
-            } else {
-                let shared_counter_init_coin_configs =
-                    SharedCounterWorkload::generate_coin_config_for_init(num_shared_counters);
-                let shared_counter_payload_coin_configs =
-                    SharedCounterWorkload::generate_coin_config_for_payloads(
-                        shared_counter_max_ops,
-                    );
-                (
-                    shared_counter_init_coin_configs,
-                    shared_counter_payload_coin_configs,
-                )
+        for (workload, share) in shares {
+            let mut workload_info = WorkloadInfo {
+                target_qps: share.qps,
+                num_workers: share.num_workers,
+                max_in_flight_ops: share.max_ops,
+                workload: config.make_workload(workload)?,
+                payload_config: workload_payload_gas.take(workload),
             };
+            workload_info
+                .workload
+                .init(
+                    workload_init_gas.take(workload),
+                    proxy.clone(),
+                    system_state_observer.clone(),
+                )
+                .await?;
+            workloads.push(workload_info);
+        }
+        Ok(workloads)
+    }
+}
 
-        let transfer_object_weight_ratio = transfer_object_weight as f32
-            / (shared_counter_weight + transfer_object_weight + delegation_weight) as f32;
-        let transfer_object_qps = (transfer_object_weight_ratio * target_qps as f32) as u64;
-        let transfer_object_num_workers =
-            (transfer_object_weight_ratio * num_workers as f32).ceil() as u64;
-        let transfer_object_max_ops = (transfer_object_qps * in_flight_ratio) as u64;
+/// Workloads of a bench run and how their gas is provisioned, as passed to
+/// the combined and disjoint modes
+struct ModeConfig<'a> {
+    // Built-in workloads followed by the registered ones, with their weights
+    weights: Vec<(WorkloadType, u32)>,
+    params: WorkloadParams,
+    registry: &'a WorkloadRegistry,
+    load: LoadConfig,
+    gas_coins_per_sender: Option<u64>,
+    sender_pool: Vec<SenderAccount>,
+    dry_run: bool,
+    gas_manifest: Option<String>,
+}
 
-        let delegate_weight_ratio = delegation_weight as f32
-            / (shared_counter_weight + transfer_object_weight + delegation_weight) as f32;
-        let delegate_qps = (delegate_weight_ratio * target_qps as f32) as u64;
-        let delegate_num_workers = (delegate_weight_ratio * num_workers as f32).ceil() as u64;
-        let delegate_max_ops = (delegate_qps * in_flight_ratio) as u64;
+impl ModeConfig<'_> {
+    /// Init and payload coin configs of `workload` with `max_ops`
+    /// transactions in flight
+    fn gas_configs(
+        &self,
+        workload: WorkloadType,
+        max_ops: u64,
+    ) -> Result<(Vec<GasCoinConfig>, Vec<GasCoinConfig>)> {
+        match workload {
+            WorkloadType::Custom(name) => {
+                let factory = self.factory(name)?;
+                Ok((
+                    factory.generate_coin_config_for_init(),
+                    factory.generate_coin_config_for_payloads(max_ops),
+                ))
+            }
+            _ => self.params.gas_configs(workload, max_ops),
+        }
+    }
 
-        let (transfer_object_workload_tokens, transfer_object_workload_payload_gas_config) =
-            if transfer_object_qps == 0
-                || transfer_object_max_ops == 0
-                || transfer_object_num_workers == 0
-            {
-                (vec![], vec![])
-            } else {
-                TransferObjectWorkload::generate_coin_config_for_payloads(
-                    transfer_object_max_ops,
-                    num_transfer_accounts,
-                    transfer_object_max_ops,
-                )
-            };
-        let delegation_gas_configs = if delegation_weight > 0 {
-            DelegationWorkload::generate_gas_config_for_payloads(delegate_max_ops)
-        } else {
-            vec![]
-        };
-        let (workload_init_gas, workload_payload_gas) = generate_all_gas_for_test(
-            proxy.clone(),
+    fn make_workload(&self, workload: WorkloadType) -> Result<Box<dyn Workload<dyn Payload>>> {
+        match workload {
+            WorkloadType::Custom(name) => Ok(self.factory(name)?.make_workload()),
+            _ => self.params.make_workload(workload),
+        }
+    }
+
+    fn factory(&self, name: &str) -> Result<&Arc<dyn WorkloadFactory>> {
+        self.registry
+            .get(name)
+            .with_context(|| format!("Workload {} is not registered", name))
+    }
+
+    /// Gas of the run, None on a dry run, which only prints the coins it
+    /// would need
+    async fn provision_gas(
+        &self,
+        workload_gas_config: WorkloadGasConfig,
+        gas: Gas,
+        coin: Gas,
+        coin_type_tag: TypeTag,
+        proxy: Arc<dyn ValidatorProxy + Send + Sync>,
+        system_state_observer: &SystemStateObserver,
+    ) -> Result<Option<(WorkloadInitGas, WorkloadPayloadGas)>> {
+        if self.dry_run {
+            print_gas_requirements(&workload_gas_config, proxy.as_ref(), &coin).await?;
+            return Ok(None);
+        }
+        generate_or_load_gas(
+            self.gas_manifest.as_deref(),
+            self.registry,
+            proxy,
             gas,
             coin,
             coin_type_tag,
-            WorkloadGasConfig {
-                shared_counter_workload_init_gas_config,
-                shared_counter_workload_payload_gas_config,
-                transfer_object_workload_tokens,
-                transfer_object_workload_payload_gas_config,
-                delegation_gas_configs,
-            },
+            workload_gas_config,
             *system_state_observer.reference_gas_price.borrow(),
+            self.gas_coins_per_sender,
+            &self.sender_pool,
         )
-        .await?;
-        if let Some(mut shared_counter_workload) = make_shared_counter_workload(
-            shared_counter_qps,
-            shared_counter_num_workers,
-            shared_counter_max_ops,
-            WorkloadPayloadGas {
-                transfer_tokens: vec![],
-                transfer_object_payload_gas: vec![],
-                shared_counter_payload_gas: workload_payload_gas.shared_counter_payload_gas,
-                delegation_payload_gas: vec![],
-            },
-        ) {
-            shared_counter_workload
-                .workload
-                .init(
-                    workload_init_gas,
-                    proxy.clone(),
-                    system_state_observer.clone(),
-                )
-                .await;
-            workloads.push(shared_counter_workload);
+        .await
+        .map(Some)
+    }
+}
+
+/// Qps, workers and in-flight transactions of a workload in disjoint mode
+#[derive(Clone, Copy, Debug)]
+struct DisjointShare {
+    qps: u64,
+    num_workers: u64,
+    max_ops: u64,
+}
+
+impl DisjointShare {
+    // Workloads without qps, workers or in-flight transactions are not run
+    fn is_idle(&self) -> bool {
+        self.qps == 0 || self.num_workers == 0 || self.max_ops == 0
+    }
+}
+
+/// Qps and in-flight transactions of a bench run, split between the
+/// workloads in disjoint mode
+#[derive(Clone, Debug)]
+struct LoadConfig {
+    target_qps: u64,
+    num_workers: u64,
+    in_flight_ratio: u64,
+    max_in_flight: Option<u64>,
+    in_flight_ratios: InFlightRatios,
+    // Disjoint workloads run one after the other instead of side by side
+    sequential: bool,
+}
+
+impl LoadConfig {
+    /// Share of `workload` with `weight` out of `total_weight`. Each workload
+    /// gets its own in-flight ratio times its qps when overridden, its share
+    /// of --max-in-flight when set, and in_flight_ratio times its qps
+    /// otherwise. Errors when a weighted workload would issue no transactions
+    fn disjoint_share(
+        &self,
+        workload: WorkloadType,
+        weight: u32,
+        total_weight: u32,
+    ) -> Result<DisjointShare> {
+        // Sequential workloads run alone, each at the full qps and in-flight
+        let ratio = if !self.sequential {
+            weight as f32 / total_weight as f32
+        } else if weight > 0 {
+            1.0
+        } else {
+            0.0
+        };
+        let qps = (ratio * self.target_qps as f32) as u64;
+        let num_workers = (ratio * self.num_workers as f32).ceil() as u64;
+        let max_ops = match (self.in_flight_ratios.of(workload), self.max_in_flight) {
+            (Some(workload_ratio), _) => qps * workload_ratio,
+            (None, Some(max_in_flight)) => (ratio * max_in_flight as f32) as u64,
+            (None, None) => qps * self.in_flight_ratio,
+        };
+        check_disjoint_share(workload, weight, qps, num_workers, max_ops)?;
+        Ok(DisjointShare {
+            qps,
+            num_workers,
+            max_ops,
+        })
+    }
+}
+
+/// Gas of the run, loaded from the --gas-manifest file once a previous run
+/// saved it there, generated and saved to it otherwise
+async fn generate_or_load_gas(
+    gas_manifest: Option<&str>,
+    registry: &WorkloadRegistry,
+    proxy: Arc<dyn ValidatorProxy + Send + Sync>,
+    gas: Gas,
+    coin: Gas,
+    coin_type_tag: TypeTag,
+    workload_gas_config: WorkloadGasConfig,
+    gas_price: u64,
+    gas_coins_per_sender: Option<u64>,
+    sender_pool: &[SenderAccount],
+) -> Result<(WorkloadInitGas, WorkloadPayloadGas)> {
+    let path = match gas_manifest {
+        Some(path) => path,
+        None => {
+            return generate_all_gas_for_test(
+                proxy,
+                gas,
+                coin,
+                coin_type_tag,
+                workload_gas_config,
+                gas_price,
+                gas_coins_per_sender,
+                sender_pool,
+            )
+            .await
         }
-        if let Some(mut transfer_object_workload) = make_transfer_object_workload(
-            transfer_object_qps,
-            transfer_object_num_workers,
-            transfer_object_max_ops,
-            num_transfer_accounts,
-            WorkloadPayloadGas {
-                transfer_tokens: workload_payload_gas.transfer_tokens,
-                transfer_object_payload_gas: workload_payload_gas.transfer_object_payload_gas,
-                shared_counter_payload_gas: vec![],
-                delegation_payload_gas: vec![],
-            },
-        ) {
-            transfer_object_workload
-                .workload
-                .init(
-                    WorkloadInitGas {
-                        shared_counter_init_gas: vec![],
-                    },
-                    proxy.clone(),
-                    system_state_observer.clone(),
-                )
-                .await;
-            workloads.push(transfer_object_workload);
+    };
+    if let Some(mut manifest) = GasManifest::load(path)? {
+        let num_coins = workload_gas_config
+            .requirements()
+            .iter()
+            .map(GasRequirement::coins)
+            .sum::<usize>();
+        if manifest.num_coins() != num_coins {
+            bail!(
+                "Gas manifest {} holds {} coins but the run needs {}, it was saved for another \
+                configuration. Delete it to generate new gas",
+                path,
+                manifest.num_coins(),
+                num_coins
+            );
         }
-        if let Some(delegation_workload) = make_delegation_workload(
-            delegate_qps,
-            delegate_num_workers,
-            delegate_max_ops,
-            WorkloadPayloadGas {
-                transfer_tokens: vec![],
-                transfer_object_payload_gas: vec![],
-                shared_counter_payload_gas: vec![],
-                delegation_payload_gas: workload_payload_gas.delegation_payload_gas,
-            },
-        ) {
-            workloads.push(delegation_workload);
+        manifest
+            .verify(proxy.as_ref())
+            .await
+            .with_context(|| format!("Gas manifest {} cannot be used", path))?;
+        info!("Loaded {} gas coins from {}", num_coins, path);
+        return manifest.into_gas(registry);
+    }
+    let (init_gas, payload_gas) = generate_all_gas_for_test(
+        proxy,
+        gas,
+        coin,
+        coin_type_tag,
+        workload_gas_config,
+        gas_price,
+        gas_coins_per_sender,
+        sender_pool,
+    )
+    .await?;
+    GasManifest::new(&init_gas, &payload_gas)?.save(path)?;
+    info!("Saved the generated gas coins to {}", path);
+    Ok((init_gas, payload_gas))
+}
+
+/// Prints the coins each workload needs and the MIST they add up to
+/// instead of generating them, for --dry-run. Only reads the pay coin
+async fn print_gas_requirements(
+    workload_gas_config: &WorkloadGasConfig,
+    proxy: &(dyn ValidatorProxy + Send + Sync),
+    coin: &Gas,
+) -> Result<()> {
+    let requirements = workload_gas_config.requirements();
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(200)
+        .set_header(vec!["workload", "init coins", "payload coins", "MIST"]);
+    for requirement in &requirements {
+        let mut row = Row::new();
+        row.add_cell(Cell::new(&requirement.workload));
+        row.add_cell(Cell::new(requirement.init_coins));
+        row.add_cell(Cell::new(requirement.payload_coins));
+        row.add_cell(Cell::new(requirement.mist));
+        table.add_row(row);
+    }
+    let total_coins: usize = requirements.iter().map(GasRequirement::coins).sum();
+    let total_mist = requirements
+        .iter()
+        .map(|requirement| requirement.mist)
+        .fold(0, u64::saturating_add);
+    let mut row = Row::new();
+    row.add_cell(Cell::new("total"));
+    row.add_cell(Cell::new(
+        requirements
+            .iter()
+            .map(|requirement| requirement.init_coins)
+            .sum::<usize>(),
+    ));
+    row.add_cell(Cell::new(
+        requirements
+            .iter()
+            .map(|requirement| requirement.payload_coins)
+            .sum::<usize>(),
+    ));
+    row.add_cell(Cell::new(total_mist));
+    table.add_row(row);
+    eprintln!("Gas Requirements:");
+    eprintln!("{}", table);
+    eprintln!(
+        "{} coins holding {} MIST in total, not counting the gas of the transactions \
+        splitting them off the pay coin",
+        total_coins, total_mist
+    );
+    // Other coin types have no balance to compare the MIST with
+    if let Ok(pay_coin) = GasCoin::try_from(&proxy.get_object(coin.0 .0).await?) {
+        if pay_coin.value() < total_mist {
+            eprintln!(
+                "Warning: the pay coin {} holds {} MIST, {} short of the run",
+                coin.0 .0,
+                pay_coin.value(),
+                total_mist - pay_coin.value()
+            );
+        } else {
+            eprintln!("The pay coin {} holds {} MIST", coin.0 .0, pay_coin.value());
         }
-        Ok(workloads)
+    }
+    Ok(())
+}
+
+/// Errors when a workload with a nonzero weight gets no qps, workers or
+/// in-flight transactions in disjoint mode, it would issue no transactions
+fn check_disjoint_share(
+    workload: WorkloadType,
+    weight: u32,
+    qps: u64,
+    num_workers: u64,
+    max_ops: u64,
+) -> Result<()> {
+    if weight > 0 && (qps == 0 || num_workers == 0 || max_ops == 0) {
+        bail!(
+            "Workload {} has weight {} but gets {} qps, {} workers and {} in-flight \
+            transactions in disjoint mode, so it would issue no transactions. \
+            Increase --target-qps or --num-workers (and --max-in-flight if set), \
+            or give it a larger weight",
+            workload,
+            weight,
+            qps,
+            num_workers,
+            max_ops
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(num_workers: u64, target_qps: u64) -> LoadConfig {
+        LoadConfig {
+            target_qps,
+            num_workers,
+            in_flight_ratio: 5,
+            max_in_flight: None,
+            in_flight_ratios: InFlightRatios::default(),
+            sequential: false,
+        }
+    }
+
+    #[test]
+    fn test_tiny_disjoint_weight_is_rejected() {
+        // Weights (1, 100, 100) over 2 workers at 100 qps
+        let load = load(2, 100);
+        let err = load
+            .disjoint_share(WorkloadType::SharedCounter, 1, 201)
+            .unwrap_err();
+        assert!(err.to_string().contains("--target-qps"));
+
+        let share = load
+            .disjoint_share(WorkloadType::TransferObject, 100, 201)
+            .unwrap();
+        assert_eq!((share.qps, share.num_workers, share.max_ops), (49, 1, 245));
+        // Workloads left out of the mix are not checked
+        let share = load.disjoint_share(WorkloadType::Noop, 0, 201).unwrap();
+        assert!(share.is_idle());
+    }
+
+    #[test]
+    fn test_disjoint_share_in_flight() {
+        let mut load = load(4, 100);
+        load.max_in_flight = Some(1000);
+        load.in_flight_ratios = InFlightRatios(vec![(WorkloadType::Noop, 2)]);
+        // The workload ratio wins over --max-in-flight
+        let share = load.disjoint_share(WorkloadType::Noop, 1, 2).unwrap();
+        assert_eq!((share.qps, share.num_workers, share.max_ops), (50, 2, 100));
+        let share = load.disjoint_share(WorkloadType::Batch, 1, 2).unwrap();
+        assert_eq!(share.max_ops, 500);
+
+        // Sequential workloads each get the full run
+        load.sequential = true;
+        let share = load.disjoint_share(WorkloadType::Batch, 1, 2).unwrap();
+        assert_eq!(
+            (share.qps, share.num_workers, share.max_ops),
+            (100, 4, 1000)
+        );
     }
 }
@@ -0,0 +1,223 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::{make_unwrap_tx, make_wrap_tx, make_wrap_unwrap_create_tx, AmountDistribution};
+use crate::workloads::package::create_publish_package_transaction;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadType, MAX_GAS_FOR_TESTING};
+use crate::workloads::{
+    generate_coin_configs_for_single_owner, sample_gas_budget, GasCoinConfig, WorkloadInitGas,
+    WorkloadPayloadGas,
+};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{path::PathBuf, sync::Arc};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::AccountKeyPair;
+use sui_types::messages::VerifiedTransaction;
+use test_utils::messages::MAX_GAS;
+use test_utils::transaction::parse_package_ref;
+use tracing::info;
+
+#[derive(Debug)]
+pub struct WrapUnwrapTestPayload {
+    package_id: ObjectID,
+    container: Option<ObjectRef>,
+    // The inner object while it is owned by the sender, None while wrapped
+    inner: Option<ObjectRef>,
+    gas: ObjectRef,
+    sender: SuiAddress,
+    keypair: Arc<AccountKeyPair>,
+    gas_budget: Option<AmountDistribution>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl Payload for WrapUnwrapTestPayload {
+    /// the first `make_transaction` call creates a container with an inner
+    /// object wrapped in it, followup calls alternate unwrapping the inner
+    /// object to the sender and wrapping it back
+    fn make_transaction(&self) -> VerifiedTransaction {
+        let gas_price = Some(*self.system_state_observer.reference_gas_price.borrow());
+        let gas_budget = sample_gas_budget(
+            self.gas_budget,
+            MAX_GAS,
+            *self.system_state_observer.reference_gas_price.borrow(),
+        );
+        match (self.container, self.inner) {
+            (None, _) => make_wrap_unwrap_create_tx(
+                self.gas,
+                self.package_id,
+                self.sender,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+            (Some(container), None) => make_unwrap_tx(
+                self.gas,
+                self.package_id,
+                container,
+                self.sender,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+            (Some(container), Some(inner)) => make_wrap_tx(
+                self.gas,
+                self.package_id,
+                container,
+                inner,
+                self.sender,
+                &self.keypair,
+                gas_budget,
+                gas_price,
+            ),
+        }
+    }
+
+    fn make_new_payload(
+        self: Box<Self>,
+        _: ObjectRef,
+        new_gas: ObjectRef,
+        effects: &ExecutionEffects,
+    ) -> Box<dyn Payload> {
+        let (container, inner) = match (self.container, self.inner) {
+            (None, _) => (effects.created().get(0).unwrap().0, None),
+            (Some(container), inner) => {
+                let container = effects
+                    .mutated()
+                    .iter()
+                    .find(|(object_ref, _)| object_ref.0 == container.0)
+                    .unwrap()
+                    .0;
+                // Wrapping consumes the inner object, unwrapping brings it back
+                let inner = match inner {
+                    Some(_) => None,
+                    None => Some(effects.unwrapped().get(0).unwrap().0),
+                };
+                (container, inner)
+            }
+        };
+        Box::new(WrapUnwrapTestPayload {
+            package_id: self.package_id,
+            container: Some(container),
+            inner,
+            gas: new_gas,
+            sender: self.sender,
+            keypair: self.keypair,
+            gas_budget: self.gas_budget,
+            system_state_observer: self.system_state_observer,
+        })
+    }
+
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::WrapUnwrap
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &WrapUnwrapTestPayload)
+    }
+}
+
+/// Moves objects in and out of owned containers to exercise the wrapped
+/// and unwrapped object transitions of effects
+#[derive(Debug)]
+pub struct WrapUnwrapWorkload {
+    pub package_id: Option<ObjectID>,
+    // Gas budget of wrap and unwrap transactions, defaults to MAX_GAS
+    pub gas_budget: Option<AmountDistribution>,
+}
+
+impl WrapUnwrapWorkload {
+    pub fn new_boxed(gas_budget: Option<AmountDistribution>) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(WrapUnwrapWorkload {
+            package_id: None,
+            gas_budget,
+        }))
+    }
+    pub fn generate_coin_config_for_init() -> Vec<GasCoinConfig> {
+        // Gas coin for publishing package
+        generate_coin_configs_for_single_owner(1, MAX_GAS_FOR_TESTING)
+    }
+    pub fn generate_coin_config_for_payloads(num_payloads: u64) -> Vec<GasCoinConfig> {
+        // Gas coins for creating the containers and moving the inner objects
+        generate_coin_configs_for_single_owner(num_payloads, MAX_GAS_FOR_TESTING)
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for WrapUnwrapWorkload {
+    async fn init(
+        &mut self,
+        init_config: WorkloadInitGas,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Result<()> {
+        if self.package_id.is_some() {
+            return Ok(());
+        }
+        let (gas, owner, keypair) = init_config
+            .wrap_unwrap_init_gas
+            .first()
+            .ok_or_else(|| anyhow!("Not enough gas to initialize wrap_unwrap workload"))?;
+        info!("Publishing wrap_unwrap package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("data/wrap_unwrap");
+        let transaction = create_publish_package_transaction(
+            *gas,
+            path,
+            owner.get_owner_address()?,
+            keypair,
+            *system_state_observer.reference_gas_price.borrow(),
+        )?;
+        let (_, effects) = proxy.execute_transaction(transaction.into()).await?;
+        self.package_id = Some(
+            parse_package_ref(&effects.created())
+                .ok_or_else(|| anyhow!("WrapUnwrap package was not published"))?
+                .0,
+        );
+        Ok(())
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _num_payloads: u64,
+        payload_config: WorkloadPayloadGas,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let package_id = self
+            .package_id
+            .expect("WrapUnwrap workload must be initialized before making payloads");
+        payload_config
+            .wrap_unwrap_payload_gas
+            .into_iter()
+            .map(|(gas, owner, keypair)| {
+                Box::new(WrapUnwrapTestPayload {
+                    package_id,
+                    container: None,
+                    inner: None,
+                    gas,
+                    sender: owner.get_owner_address().unwrap(),
+                    keypair,
+                    gas_budget: self.gas_budget,
+                    system_state_observer: system_state_observer.clone(),
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+
+    fn get_workload_type(&self) -> WorkloadType {
+        WorkloadType::WrapUnwrap
+    }
+
+    fn debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self as &WrapUnwrapWorkload)
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// A test binary of its own, as the global rng is shared by every test of
+// the process and draws of concurrent tests would interleave with its own
+#[cfg(feature = "test-utils")]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use sui_benchmark::mock_proxy::MockValidatorProxy;
+    use sui_benchmark::rng::{get_key_pair, set_global_seed};
+    use sui_benchmark::system_state_observer::SystemStateObserver;
+    use sui_benchmark::util::{generate_all_gas_for_test, AmountDistribution};
+    use sui_benchmark::workloads::delegation::DelegationTarget;
+    use sui_benchmark::workloads::nft_mint::NftMetadataLengths;
+    use sui_benchmark::workloads::workload::{WorkloadType, MAX_GAS_FOR_TESTING};
+    use sui_benchmark::workloads::{WorkloadGasBudgets, WorkloadGasConfig, WorkloadParams};
+    use sui_benchmark::ValidatorProxy;
+    use sui_types::base_types::ObjectID;
+    use sui_types::crypto::AccountKeyPair;
+    use sui_types::gas_coin::GAS;
+    use sui_types::object::{Object, Owner};
+
+    const MAX_OPS: u64 = 4;
+
+    /// Digests of the first transactions of transfer object payloads,
+    /// configured from scratch after seeding the global rng with `seed`
+    async fn configure_transfer_object(seed: u64) -> Vec<String> {
+        set_global_seed(seed);
+        let (owner, keypair): (_, AccountKeyPair) = get_key_pair();
+        let keypair = Arc::new(keypair);
+        let gas_object = Object::with_id_owner_gas_for_testing(
+            ObjectID::from_bytes([1; ObjectID::LENGTH]).unwrap(),
+            owner,
+            MAX_GAS_FOR_TESTING * 100,
+        );
+        let coin_object = Object::with_id_owner_gas_for_testing(
+            ObjectID::from_bytes([2; ObjectID::LENGTH]).unwrap(),
+            owner,
+            MAX_GAS_FOR_TESTING * 100,
+        );
+        let gas = (
+            gas_object.compute_object_reference(),
+            Owner::AddressOwner(owner),
+            keypair.clone(),
+        );
+        let coin = (
+            coin_object.compute_object_reference(),
+            Owner::AddressOwner(owner),
+            keypair,
+        );
+        let proxy: Arc<dyn ValidatorProxy + Send + Sync> = Arc::new(
+            MockValidatorProxy::new(vec![gas_object, coin_object], None, 0.0)
+                .await
+                .unwrap(),
+        );
+        let system_state_observer = Arc::new(SystemStateObserver::new(
+            proxy.clone(),
+            Duration::from_secs(60),
+        ));
+        // Wait for the reference gas price of the first poll
+        let mut reference_gas_price = system_state_observer.reference_gas_price.clone();
+        reference_gas_price.changed().await.unwrap();
+        let gas_price = *reference_gas_price.borrow();
+
+        let params = WorkloadParams {
+            num_transfer_accounts: 2,
+            transfer_amount_dist: Some(AmountDistribution::LogNormal {
+                median: 1000,
+                sigma: 0.5,
+            }),
+            transfer_ring_check: false,
+            delegation_target: DelegationTarget::Random,
+            collection_ops_per_tx: 1,
+            dependency_chain_length: 1,
+            shared_lifecycle_create_percent: 0,
+            shared_lifecycle_mutations: 0,
+            send_to_object_destinations: 1,
+            create_fanout: 1,
+            nft_metadata_lengths: NftMetadataLengths::default(),
+            commands_per_tx: 1,
+            split_count: 2,
+            randomize_inputs: true,
+            shared_counter_hotness_factor: 50,
+            contention_objects: None,
+            shared_counter_init_timeout: None,
+            gas_budgets: WorkloadGasBudgets::default(),
+        };
+        let workload_type = WorkloadType::TransferObject;
+        let (init, payload) = params.gas_configs(workload_type, MAX_OPS).unwrap();
+        let mut workload_gas_config = WorkloadGasConfig::default();
+        workload_gas_config.insert(workload_type, init, payload);
+        let (init_gas, mut payload_gas) = generate_all_gas_for_test(
+            proxy.clone(),
+            gas,
+            coin,
+            GAS::type_tag(),
+            workload_gas_config,
+            gas_price,
+            None,
+            &[],
+        )
+        .await
+        .unwrap();
+        let mut workload = params.make_workload(workload_type).unwrap();
+        workload
+            .init(init_gas, proxy.clone(), system_state_observer.clone())
+            .await
+            .unwrap();
+        workload
+            .make_test_payloads(
+                MAX_OPS,
+                payload_gas.take(workload_type),
+                proxy,
+                system_state_observer,
+            )
+            .await
+            .iter()
+            .map(|payload| payload.make_transaction().digest().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_global_seed_reproduces_payload_transactions() {
+        let digests = configure_transfer_object(7).await;
+        assert_eq!(digests.len() as u64, MAX_OPS);
+        assert_eq!(configure_transfer_object(7).await, digests);
+        assert_ne!(configure_transfer_object(8).await, digests);
+    }
+}
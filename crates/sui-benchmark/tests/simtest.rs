@@ -8,17 +8,22 @@ mod test {
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, Instant};
+    use sui_benchmark::simulator_proxy::{InjectedFaults, SimulatorValidatorProxy};
     use sui_benchmark::system_state_observer::SystemStateObserver;
     use sui_benchmark::util::generate_all_gas_for_test;
-    use sui_benchmark::workloads::delegation::DelegationWorkload;
-    use sui_benchmark::workloads::shared_counter::SharedCounterWorkload;
-    use sui_benchmark::workloads::transfer_object::TransferObjectWorkload;
-    use sui_benchmark::workloads::WorkloadGasConfig;
+    use sui_benchmark::workloads::delegation::DelegationTarget;
+    use sui_benchmark::workloads::nft_mint::NftMetadataLengths;
+    use sui_benchmark::workloads::workload::WorkloadType;
+    use sui_benchmark::workloads::{WorkloadGasBudgets, WorkloadGasConfig, WorkloadParams};
     use sui_benchmark::{
-        drivers::{bench_driver::BenchDriver, driver::Driver, Interval},
+        drivers::{
+            bench_driver::{BenchDriver, BenchDriverConfig},
+            driver::Driver,
+            Interval,
+        },
         util::get_ed25519_keypair_from_keystore,
         workloads::make_combination_workload,
-        LocalValidatorAggregatorProxy, ValidatorProxy,
+        ValidatorProxy,
     };
     use sui_config::SUI_KEYSTORE_FILENAME;
     use sui_macros::{register_fail_points, sim_test};
@@ -66,6 +71,17 @@ mod test {
         test_simulated_load(test_cluster, 15).await;
     }
 
+    #[sim_test(config = "test_config()")]
+    async fn test_simulated_load_client_delays() {
+        let test_cluster = build_test_cluster(4, 0).await;
+        let faults = InjectedFaults {
+            delay_probability: 0.05,
+            delay_ms: 100..2000,
+            error_probability: 0.0,
+        };
+        test_simulated_load_with_faults(test_cluster, 30, faults).await;
+    }
+
     #[sim_test(config = "test_config()")]
     async fn test_simulated_load_restarts() {
         let test_cluster = build_test_cluster(4, 0).await;
@@ -157,10 +173,22 @@ mod test {
     }
 
     async fn test_simulated_load(test_cluster: Arc<TestCluster>, test_duration_secs: u64) {
+        test_simulated_load_with_faults(
+            test_cluster,
+            test_duration_secs,
+            InjectedFaults::default(),
+        )
+        .await;
+    }
+
+    async fn test_simulated_load_with_faults(
+        test_cluster: Arc<TestCluster>,
+        test_duration_secs: u64,
+        faults: InjectedFaults,
+    ) {
         let swarm = &test_cluster.swarm;
         let context = &test_cluster.wallet;
         let sender = test_cluster.get_address_0();
-        let fullnode_rpc_url = &test_cluster.fullnode_handle.rpc_url;
 
         let keystore_path = swarm.dir().join(SUI_KEYSTORE_FILENAME);
         let ed25519_keypair =
@@ -182,15 +210,11 @@ mod test {
 
         let registry = prometheus::Registry::new();
         let proxy: Arc<dyn ValidatorProxy + Send + Sync> = Arc::new(
-            LocalValidatorAggregatorProxy::from_network_config(
-                swarm.config(),
-                &registry,
-                Some(fullnode_rpc_url),
-            )
-            .await,
+            SimulatorValidatorProxy::from_test_cluster(&test_cluster, &registry, faults).await,
         );
         let system_state_observer = {
-            let mut system_state_observer = SystemStateObserver::new(proxy.clone());
+            let mut system_state_observer =
+                SystemStateObserver::new(proxy.clone(), Duration::from_secs(60));
             if let Ok(_) = system_state_observer.reference_gas_price.changed().await {
                 info!("Got the reference gas price from system state object");
             }
@@ -204,39 +228,57 @@ mod test {
         let num_workers = get_var("SIM_STRESS_TEST_WORKERS", 10);
         let in_flight_ratio = get_var("SIM_STRESS_TEST_IFR", 2);
         let max_ops = target_qps * in_flight_ratio;
-        let num_shared_counters = max_ops;
-        let shared_counter_workload_init_gas_config =
-            SharedCounterWorkload::generate_coin_config_for_init(num_shared_counters);
-        let shared_counter_workload_payload_gas_config =
-            SharedCounterWorkload::generate_coin_config_for_payloads(max_ops);
-
-        let (transfer_object_workload_tokens, transfer_object_workload_payload_gas_config) =
-            TransferObjectWorkload::generate_coin_config_for_payloads(max_ops, 2, max_ops);
-        let delegation_gas_configs = DelegationWorkload::generate_gas_config_for_payloads(max_ops);
+        let params = WorkloadParams {
+            num_transfer_accounts: 2,
+            transfer_amount_dist: None,
+            transfer_ring_check: true,
+            delegation_target: DelegationTarget::Random,
+            collection_ops_per_tx: 0,
+            dependency_chain_length: 1,
+            shared_lifecycle_create_percent: 0,
+            shared_lifecycle_mutations: 0,
+            send_to_object_destinations: 0,
+            create_fanout: 1,
+            nft_metadata_lengths: NftMetadataLengths::default(),
+            commands_per_tx: 1,
+            split_count: 2,
+            randomize_inputs: false,
+            // As many shared counters as payloads
+            shared_counter_hotness_factor: 0,
+            contention_objects: None,
+            shared_counter_init_timeout: None,
+            gas_budgets: WorkloadGasBudgets::default(),
+        };
+        let workload_types = [
+            WorkloadType::SharedCounter,
+            WorkloadType::TransferObject,
+            WorkloadType::Delegation,
+        ];
+        let mut workload_gas_config = WorkloadGasConfig::default();
+        for workload in workload_types {
+            let (init, payload) = params.gas_configs(workload, max_ops).unwrap();
+            workload_gas_config.insert(workload, init, payload);
+        }
         let (workload_init_gas, workload_payload_gas) = generate_all_gas_for_test(
             proxy.clone(),
             primary_gas,
             coin,
             coin_type_tag,
-            WorkloadGasConfig {
-                shared_counter_workload_init_gas_config,
-                shared_counter_workload_payload_gas_config,
-                transfer_object_workload_tokens,
-                transfer_object_workload_payload_gas_config,
-                delegation_gas_configs,
-            },
+            workload_gas_config,
             reference_gas_price,
+            None, // gas_coins_per_sender
+            &[],  // sender_pool
         )
         .await
         .unwrap();
         let mut combination_workload = make_combination_workload(
             target_qps,
             num_workers,
-            in_flight_ratio,
-            2, // num transfer accounts
-            1, // shared_counter_weight
-            1, // transfer_object_weight
-            1, // delegation_weight
+            max_ops,
+            workload_types
+                .into_iter()
+                .map(|workload| (1, params.make_workload(workload).unwrap()))
+                .collect(),
             workload_payload_gas,
         );
         combination_workload
@@ -246,9 +288,14 @@ mod test {
                 proxy.clone(),
                 system_state_observer.clone(),
             )
-            .await;
+            .await
+            .unwrap();
 
-        let driver = BenchDriver::new(5, false);
+        let driver = BenchDriver::new(BenchDriverConfig {
+            stat_collection_interval: 5,
+            verify_effects_signatures: true,
+            ..Default::default()
+        });
 
         // Use 0 for unbounded
         let test_duration_secs = get_var("SIM_STRESS_TEST_DURATION_SECS", test_duration_secs);
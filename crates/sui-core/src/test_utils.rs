@@ -266,13 +266,27 @@ pub fn make_transfer_sui_transaction(
     sender: SuiAddress,
     keypair: &AccountKeyPair,
     gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    make_transfer_sui_transaction_with_gas_budget(
+        gas_object, recipient, amount, sender, keypair, MAX_GAS, gas_price,
+    )
+}
+
+pub fn make_transfer_sui_transaction_with_gas_budget(
+    gas_object: ObjectRef,
+    recipient: SuiAddress,
+    amount: Option<u64>,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
 ) -> VerifiedTransaction {
     let data = TransactionData::new_transfer_sui(
         recipient,
         sender,
         amount,
         gas_object,
-        MAX_GAS,
+        gas_budget,
         gas_price.unwrap_or(DUMMY_GAS_PRICE),
     );
     to_sender_signed_transaction(data, keypair)
@@ -285,13 +299,27 @@ pub fn make_transfer_object_transaction(
     keypair: &AccountKeyPair,
     recipient: SuiAddress,
     gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    make_transfer_object_transaction_with_gas_budget(
+        object_ref, gas_object, sender, keypair, recipient, MAX_GAS, gas_price,
+    )
+}
+
+pub fn make_transfer_object_transaction_with_gas_budget(
+    object_ref: ObjectRef,
+    gas_object: ObjectRef,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    recipient: SuiAddress,
+    gas_budget: u64,
+    gas_price: Option<u64>,
 ) -> VerifiedTransaction {
     let data = TransactionData::new_transfer(
         recipient,
         object_ref,
         sender,
         gas_object,
-        MAX_GAS,
+        gas_budget,
         gas_price.unwrap_or(DUMMY_GAS_PRICE),
     );
     to_sender_signed_transaction(data, keypair)
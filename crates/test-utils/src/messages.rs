@@ -373,6 +373,28 @@ pub fn make_counter_increment_transaction(
     sender: SuiAddress,
     keypair: &AccountKeyPair,
     gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    make_counter_increment_transaction_with_gas_budget(
+        gas_object,
+        package_id,
+        counter_id,
+        counter_initial_shared_version,
+        sender,
+        keypair,
+        MAX_GAS,
+        gas_price,
+    )
+}
+
+pub fn make_counter_increment_transaction_with_gas_budget(
+    gas_object: ObjectRef,
+    package_id: ObjectID,
+    counter_id: ObjectID,
+    counter_initial_shared_version: SequenceNumber,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
 ) -> VerifiedTransaction {
     let data = TransactionData::new_move_call(
         sender,
@@ -386,7 +408,7 @@ pub fn make_counter_increment_transaction(
             initial_shared_version: counter_initial_shared_version,
             mutable: true,
         })],
-        MAX_GAS,
+        gas_budget,
         gas_price.unwrap_or(1),
     );
     to_sender_signed_transaction(data, keypair)
@@ -399,6 +421,26 @@ pub fn make_delegation_transaction(
     sender: SuiAddress,
     keypair: &AccountKeyPair,
     gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    make_delegation_transaction_with_gas_budget(
+        gas_object,
+        coin,
+        validator,
+        sender,
+        keypair,
+        MAX_DELEGATION_GAS,
+        gas_price,
+    )
+}
+
+pub fn make_delegation_transaction_with_gas_budget(
+    gas_object: ObjectRef,
+    coin: ObjectRef,
+    validator: SuiAddress,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_budget: u64,
+    gas_price: Option<u64>,
 ) -> VerifiedTransaction {
     let data = TransactionData::new_move_call(
         sender,
@@ -416,7 +458,7 @@ pub fn make_delegation_transaction(
             CallArg::Object(ObjectArg::ImmOrOwnedObject(coin)),
             CallArg::Pure(bcs::to_bytes(&validator).unwrap()),
         ],
-        MAX_DELEGATION_GAS,
+        gas_budget,
         gas_price.unwrap_or(DUMMY_GAS_PRICE),
     );
     to_sender_signed_transaction(data, keypair)